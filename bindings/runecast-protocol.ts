@@ -0,0 +1,1658 @@
+// Generated by `cargo run --features typescript --bin gen-ts`. Do not edit.
+// Source of truth: the Rust types in runecast-protocol.
+
+/** Delivery wrapper; see `Envelope<T>` in envelope.rs. */
+export interface Envelope<T> {
+  seq: number;
+  ack?: number;
+  ts: number;
+  payload: T;
+}
+
+/** A message either wrapped in an envelope or sent raw (legacy). */
+export type MaybeEnveloped<T> = Envelope<T> | T;
+
+/**
+ * Audit trail attached to the result of every admin action.
+ *
+ * Servers fill it in when the action succeeds; it is meant to be logged
+ * as is.
+ */
+export interface AdminAudit {
+  /** Server-assigned id for this action */
+  action_id: string;
+  /** The admin who performed it */
+  admin_id: string;
+  performed_at: string;
+  /** The reason given with the request, if any */
+  reason?: string;
+}
+
+/** Admin game info (for admin panel). */
+export interface AdminGameInfo {
+  created_at: string;
+  game_id: string;
+  game_type?: GameType;
+  /** Lobby the game belongs to */
+  lobby_id?: string;
+  players: string[];
+  spectator_count: number;
+  state: GameState;
+}
+
+/** Which lobbies `AdminListLobbies` returns. Unset fields match everything. */
+export interface AdminLobbyFilter {
+  /** Only lobbies with (`true`) or without (`false`) an active game */
+  has_active_game?: boolean;
+  lobby_type?: LobbyType;
+  /** Only lobbies this player is in */
+  player_id?: string;
+}
+
+/** Admin lobby info, as listed by `AdminListLobbies`. */
+export interface AdminLobbyInfo {
+  created_at: string;
+  games: GameSummary[];
+  host_id?: string;
+  lobby_code?: string;
+  lobby_id: string;
+  lobby_type: LobbyType;
+  players: string[];
+}
+
+/**
+ * Adventure Mode random-event kinds. Carried on `ServerMessage::AdventureEvent`.
+ *
+ * The `Unknown` variant catches any future kind an older client doesn't
+ * recognize, so adding new kinds server-side is non-breaking. Clients
+ * matching on this enum should always handle the `Unknown` arm (e.g.
+ * by rendering a generic "Something happened!" toast).
+ */
+export type AdventureEventKind =
+  /** 3×3 blast centered on a random cell; those cells reroll. */
+  | "bomb"
+  /** Poisons a handful of cells for a couple of rounds. */
+  | "snake"
+  /** Steals all multipliers currently on the board. */
+  | "ufo"
+  /**
+   * Forward-compat fallback. Serde decodes any unknown kind string
+   * to this variant via `#[serde(other)]`.
+   */
+  | "unknown" | (string & {});
+
+/**
+ * Bot difficulty on the wire. Lowercase to match the backend's
+ * `game_players.bot_difficulty` CHECK constraint (`easy|medium|hard`).
+ */
+export type BotDifficulty = "easy" | "medium" | "hard";
+
+/** One bot seat requested for a custom game. */
+export interface BotSpec {
+  difficulty: BotDifficulty;
+}
+
+/** Messages sent from client to server. */
+export type ClientMessage =
+  /**
+   * Initial identification after WebSocket connect.
+   *
+   * If `resume_seq` is provided, attempt to resume a previous session
+   * and receive missed messages since that sequence number.
+   */
+  | {
+    type: "identify";
+    /** Last seen sequence number (for session resumption) */
+    resume_seq?: number;
+  }
+  /**
+   * Keep-alive ping. Server responds with `HeartbeatAck`.
+   *
+   * Should be sent every 20-30 seconds to survive proxy timeouts.
+   */
+  | {
+    type: "heartbeat";
+  }
+  /**
+   * Explicit acknowledgment of received messages.
+   *
+   * Can be used when no other message is being sent to confirm receipt.
+   * Usually, acks are piggybacked on other messages via the envelope.
+   */
+  | {
+    type: "ack";
+    /** Sequence number being acknowledged */
+    seq: number;
+  }
+  /**
+   * Request a full state sync from the server.
+   *
+   * Used to recover from state desync between client and server.
+   * Server responds with `LobbySnapshot` and optionally `GameSnapshot`.
+   */
+  | {
+    type: "request_sync";
+  }
+  /**
+   * Request debug state information for diagnosing context issues.
+   *
+   * Returns detailed information about the player's current state
+   * in the WebSocket handler, lobby, and game systems.
+   */
+  | {
+    type: "debug_state";
+  }
+  /**
+   * Join a channel-based lobby (default Discord Activity behavior).
+   *
+   * The lobby is automatically created if it doesn't exist.
+   */
+  | {
+    type: "join_channel_lobby";
+    /** Discord channel ID */
+    channel_id: string;
+    /** Discord guild ID (optional for DM activities) */
+    guild_id?: string;
+  }
+  /**
+   * Create a new custom lobby with a shareable code.
+   *
+   * Returns a 6-character code that others can use to join.
+   */
+  | {
+    type: "create_custom_lobby";
+  }
+  /** Join an existing custom lobby by its code. */
+  | {
+    type: "join_custom_lobby";
+    /** 6-character lobby code (case-insensitive) */
+    lobby_code: string;
+  }
+  /**
+   * Leave the current lobby.
+   *
+   * If in a game, this also leaves the game.
+   */
+  | {
+    type: "leave_lobby";
+  }
+  /**
+   * Join a game pool within the lobby.
+   *
+   * Players must join a game pool to be matched for that game type.
+   * Only one game pool can be joined at a time.
+   */
+  | {
+    type: "join_game_pool";
+    /** The game type pool to join */
+    game_type: GameType;
+  }
+  /**
+   * Leave the current game pool.
+   *
+   * Returns the player to the main lobby view.
+   */
+  | {
+    type: "leave_game_pool";
+  }
+  /** Request to create a new game (legacy - prefer `StartGame`). */
+  | {
+    type: "create_game";
+    mode: GameMode;
+  }
+  /**
+   * Host-only: update the live sandbox config for the current lobby.
+   * Rejected by the server if the caller is not the host or lacks the
+   * `sandbox_enabled` capability.
+   */
+  | {
+    type: "set_sandbox_config";
+    config: GameConfig;
+  }
+  /** Host-only: fire a random event immediately in the current custom game. */
+  | {
+    type: "trigger_adventure_event";
+    kind: AdventureEventKind;
+  }
+  /**
+   * Start a new game in the current lobby.
+   *
+   * By default, any player can start. Can be restricted to host only
+   * via server configuration.
+   *
+   * Requirements:
+   * - Must be in a lobby
+   * - 1-6 connected players
+   * - No game already in progress
+   */
+  | {
+    type: "start_game";
+    /** Optional game configuration */
+    config?: GameConfig;
+  }
+  /**
+   * Submit a word during your turn.
+   *
+   * The word is derived from the positions on the grid.
+   * Positions must form a valid path (adjacent cells, no repeats).
+   */
+  | {
+    type: "submit_word";
+    game_id: string;
+    /** Grid positions forming the word path */
+    positions: Position[];
+    /** The word being submitted (for validation) */
+    word: string;
+  }
+  /**
+   * Pass your turn without submitting a word.
+   *
+   * Awards 0 points and advances to the next player.
+   */
+  | {
+    type: "pass_turn";
+    game_id: string;
+  }
+  /**
+   * Shuffle the board (costs 1 gem).
+   *
+   * Randomizes tile positions while keeping their properties
+   * (letters, multipliers, gems stay on tiles, just positions change).
+   */
+  | {
+    type: "shuffle_board";
+    game_id: string;
+  }
+  /**
+   * Enter swap mode (for UI feedback).
+   *
+   * Broadcasts to other players that you're considering a swap.
+   * Triggers wobble animation on their screens.
+   */
+  | {
+    type: "enter_swap_mode";
+    game_id: string;
+  }
+  /** Exit swap mode without swapping. */
+  | {
+    type: "exit_swap_mode";
+    game_id: string;
+  }
+  /**
+   * Swap a tile's letter (costs 3 gems).
+   *
+   * Changes the letter on a specific tile. The multiplier and gem
+   * status of the tile are preserved.
+   */
+  | {
+    type: "swap_tile";
+    col: number;
+    game_id: string;
+    /** New letter (A-Z) */
+    new_letter: string;
+    row: number;
+  }
+  /**
+   * Join a game as a spectator.
+   *
+   * Spectators can view the game but cannot interact with it.
+   */
+  | {
+    type: "spectate_game";
+    game_id: string;
+  }
+  /**
+   * Join an active game.
+   *
+   * The player is added at the end of the turn order.
+   * Previous rounds count as 0 points.
+   */
+  | {
+    type: "join_game";
+    game_id: string;
+  }
+  /** Leave spectator mode and return to lobby view. */
+  | {
+    type: "leave_spectator";
+    game_id: string;
+  }
+  /** Legacy leave game message. */
+  | {
+    type: "leave_game";
+    game_id: string;
+  }
+  /**
+   * Broadcast current tile selection to other players.
+   *
+   * Sent as the player selects tiles, allowing spectators and
+   * other players to see the selection in real-time.
+   */
+  | {
+    type: "selection_update";
+    game_id: string;
+    positions: Position[];
+  }
+  /**
+   * Initiate a vote to start a turn timer on the current player.
+   *
+   * Requirements:
+   * - At least 3 players in game
+   * - Not your turn
+   * - No vote already in progress
+   * - Not in cooldown
+   */
+  | {
+    type: "initiate_timer_vote";
+    game_id: string;
+  }
+  /**
+   * Vote yes on an active timer vote.
+   *
+   * Requirements:
+   * - Vote must be in progress
+   * - You haven't already voted
+   * - You didn't initiate the vote
+   * - Not your turn
+   */
+  | {
+    type: "vote_for_timer";
+    game_id: string;
+  }
+  /**
+   * Trigger early rematch start for all players.
+   *
+   * Cancels the countdown and starts the game immediately for everyone
+   * still in the rematch pool.
+   */
+  | {
+    type: "trigger_rematch";
+    /** The game that just ended (for validation) */
+    previous_game_id: string;
+  }
+  /**
+   * Leave the rematch pool and return to lobby.
+   *
+   * The player will be removed from the rematch player list and
+   * other players will be notified.
+   */
+  | {
+    type: "leave_rematch";
+    /** The game to leave rematch for */
+    previous_game_id: string;
+  }
+  /** Request list of games (admin only). */
+  | {
+    type: "admin_get_games";
+  }
+  /** Delete a specific game (admin only). */
+  | {
+    type: "admin_delete_game";
+    game_id: string;
+    /** Why, for the audit trail */
+    reason?: string;
+  }
+  /** List lobbies matching `filter`, one page at a time (admin only). */
+  | {
+    type: "admin_list_lobbies";
+    filter: AdminLobbyFilter;
+    page: PageRequest;
+  }
+  /** Fetch any lobby's full snapshot (admin only). */
+  | {
+    type: "admin_inspect_lobby";
+    lobby_id: string;
+  }
+  /** Fetch any game's full snapshot (admin only). */
+  | {
+    type: "admin_inspect_game";
+    game_id: string;
+  }
+  /**
+   * Remove a player from their lobby and game and close their
+   * connection (admin only). They may reconnect.
+   */
+  | {
+    type: "admin_kick_player";
+    player_id: string;
+    reason: string;
+  }
+  /**
+   * Kick a player and refuse their connections until `expires_at`
+   * (admin only).
+   */
+  | {
+    type: "admin_ban_player";
+    /** When the ban lifts; `None` bans permanently */
+    expires_at?: string;
+    player_id: string;
+    reason: string;
+  }
+  /** End a game now, awarding the win per `winner` (admin only). */
+  | {
+    type: "admin_force_end_game";
+    game_id: string;
+    reason: string;
+    winner: WinnerPolicy;
+  }
+  /**
+   * Announce a news item to every connected client as `NewsAnnounced`
+   * (admin only).
+   */
+  | {
+    type: "admin_push_news";
+    item: NewsItemPayload;
+  };
+
+/**
+ * Marker payload for a custom (sandbox) game. Empty for now; a struct
+ * (not a bare bool) so we can carry custom metadata later without a
+ * wire break.
+ */
+export type CustomMeta = Record<string, never>;
+
+/** Backend game state in debug state response. */
+export type DebugBackendGameState =
+  | {
+    game_id: string;
+    lobby_id: string;
+    player_in_session_players: boolean;
+    session_player_ids: number[];
+    session_spectator_ids: number[];
+    spectator_in_session: boolean;
+  }
+  | {
+    error: string;
+  };
+
+/** Handler game state in debug state response. */
+export type DebugHandlerGameState =
+  | {
+    current_turn_index: number;
+    game_id: string;
+    handler_player_ids: number[];
+    player_in_handler_game: boolean;
+    round: number;
+    state: string;
+  }
+  | {
+    error: string;
+  };
+
+/** Lobby state in debug state response. */
+export type DebugLobbyState =
+  | {
+    active_game_id: string | null;
+    lobby_id: string;
+    lobby_player_ids: number[];
+    player_in_lobby: boolean;
+  }
+  | {
+    error: string;
+  };
+
+/** Player info in debug state response. */
+export interface DebugPlayerInfo {
+  user_id: number;
+  username: string;
+}
+
+/** WebSocket connection context in debug state response. */
+export interface DebugWebsocketContext {
+  game_id: string | null;
+  is_spectating: boolean;
+  lobby_id: string | null;
+}
+
+/** Standard error codes for protocol errors. */
+export type ErrorCode =
+  | "not_authenticated"
+  | "session_expired"
+  | "invalid_session"
+  | "lobby_not_found"
+  | "lobby_full"
+  | "not_in_lobby"
+  | "already_in_lobby"
+  | "game_not_found"
+  | "game_in_progress"
+  | "game_not_active"
+  | "not_in_game"
+  | "already_in_game"
+  | "not_your_turn"
+  | "invalid_action"
+  | "action_timeout"
+  | "invalid_path"
+  | "path_too_short"
+  | "word_not_in_dictionary"
+  | "word_already_used"
+  | "not_host"
+  | "not_enough_players"
+  | "too_many_players"
+  | "insufficient_gems"
+  | "too_many_requests"
+  | "message_too_large"
+  | "invalid_request"
+  | "internal_error"
+  /** A vote is running or its turn timer is already counting down. */
+  | "timer_vote_in_progress"
+  /** A failed vote is cooling down; try again later. */
+  | "timer_vote_cooldown"
+  /** `VoteForTimer` with no vote in progress. */
+  | "no_timer_vote"
+  /** Caller already voted (the initiator counts as having voted). */
+  | "already_voted"
+  /** The player whose turn it is can't start or join a vote on themselves. */
+  | "is_your_turn"
+  /**
+   * Caller lacks the capability for an entitlement-gated action
+   * (e.g. starting or configuring a custom sandbox game when
+   * `sandbox_enabled` is false for them). Server-authoritative.
+   */
+  | "not_authorized"
+  /** The target player is not connected or does not exist. */
+  | "player_not_found";
+
+/** Random-event config for a custom (sandbox) game. Absent for FFA/Adventure. */
+export interface EventConfig {
+  /** Kinds eligible to fire (ambient or fire-now). Empty = none. */
+  enabled_kinds: AdventureEventKind[];
+  /** Ambient per-turn probability one enabled kind fires (0.0–1.0). */
+  frequency: number;
+}
+
+/** Changes to game state (for delta updates). */
+export type GameChange =
+  /** Grid was updated (after word submission) */
+  | {
+    change_type: "grid_updated";
+    grid: GridCell[][];
+    /** Positions that were replaced */
+    replaced_positions?: Position[];
+  }
+  /** A player's score changed */
+  | {
+    change_type: "score_updated";
+    gems: number;
+    player_id: string;
+    score: number;
+  }
+  /** Turn changed to another player */
+  | {
+    change_type: "turn_changed";
+    player_id: string;
+  }
+  /** Round number changed */
+  | {
+    change_type: "round_changed";
+    round: number;
+  }
+  /** A word was added to used words */
+  | {
+    change_type: "word_used";
+    word: string;
+  }
+  /** A spectator joined the game */
+  | {
+    change_type: "spectator_joined";
+    spectator: SpectatorInfo;
+  }
+  /** A spectator left the game */
+  | {
+    change_type: "spectator_left";
+    spectator_id: string;
+  }
+  /** A player's connection state changed */
+  | {
+    change_type: "player_connection_changed";
+    is_connected: boolean;
+    player_id: string;
+  };
+
+/**
+ * Configuration options for starting a new game.
+ *
+ * These options customize game behavior for a single game session.
+ */
+export interface GameConfig {
+  /**
+   * Adventure level ID (1..=50). `Some` signals this session is an
+   * Adventure Mode run; the server pins bot difficulty, grid size, and
+   * target thresholds from the level manifest. `None` = normal session.
+   */
+  adventure_level?: number;
+  /** Bot seats to add (custom games only). Empty for FFA. */
+  bots?: BotSpec[];
+  /**
+   * `Some` marks this session as a custom (unranked) sandbox game.
+   * Mirrors `adventure_level` — both gate the same ranked-skip paths.
+   */
+  custom?: CustomMeta;
+  /** Random-event config for custom games. `None` for FFA/Adventure. */
+  events?: EventConfig;
+  /**
+   * Grid size (4 or 5). Determines which dice set is used.
+   * Default is 5 (standard 5x5 Big Boggle-style).
+   */
+  grid_size: number;
+  /**
+   * Score thresholds for 1/2/3-star completion of the current Adventure
+   * level. Sent from server → client at session start so the UI can
+   * render target chips. Only present when `adventure_level.is_some()`.
+   */
+  level_targets?: LevelTargets;
+  /**
+   * Number of rounds for a custom game. `None` = server default (5).
+   * Honored only for custom (sandbox) games; ignored for FFA.
+   */
+  num_rounds?: number;
+  /**
+   * If true, regenerate the entire board at the start of each round.
+   * Default is false (board persists across rounds).
+   */
+  regenerate_board_each_round: boolean;
+}
+
+/** Game mode variants. */
+export type GameMode = "solo" | "multiplayer" | "adventure";
+
+/** Player info specifically for `GameStarted` message (includes turn order). */
+export interface GamePlayerInfo {
+  avatar_url?: string;
+  gems: number;
+  is_connected: boolean;
+  score: number;
+  team?: number;
+  turn_order: number;
+  user_id: string;
+  username: string;
+}
+
+/** Complete snapshot of the game state. */
+export interface GameSnapshot {
+  current_turn: string;
+  /**
+   * `Some` marks an unranked custom (sandbox) game, mirroring
+   * `GameStarted.custom`, so the unranked badge survives reconnect/spectate.
+   */
+  custom?: CustomMeta;
+  game_id: string;
+  grid: GridCell[][];
+  max_rounds: number;
+  players: PlayerInfo[];
+  round: number;
+  spectators: SpectatorInfo[];
+  state: GameState;
+  /**
+   * Server-authoritative time left in a Daily Challenge run, in milliseconds.
+   * Mirrors `GameStarted.time_remaining_ms` so a daily run that recovers via
+   * the snapshot/reconnect path keeps its deadline. Omitted otherwise.
+   */
+  time_remaining_ms?: number;
+  /** When the turn timer expires (if active) */
+  timer_expiration_time?: string;
+  timer_vote_state: TimerVoteState;
+  used_words: string[];
+  /** Your player info (for the receiving client) */
+  your_player?: PlayerInfo;
+}
+
+/** High-level game state (not the full game data). */
+export type GameState =
+  /** Waiting for players / not started */
+  | "idle"
+  /** Players are queueing */
+  | "queueing"
+  /** Game is starting (countdown) */
+  | "starting"
+  /** Game is in progress */
+  | "in_progress"
+  /** Game has ended */
+  | "finished"
+  /** Game was cancelled */
+  | "cancelled";
+
+/** Summary of a game visible from the lobby. */
+export interface GameSummary {
+  current_round: number;
+  game_id: string;
+  max_rounds: number;
+  player_count: number;
+  spectator_count: number;
+  state: GameState;
+}
+
+/**
+ * Game type for game pools within a lobby.
+ *
+ * Each lobby can have multiple game pools, one per game type. Players wait in pools for matchmaking.
+ * Players join a game pool to find matches for that game type.
+ */
+export type GameType =
+  /** Free-for-all open game (default) */
+  | "open"
+  /** 2v2 team game */
+  | "two_v_two"
+  /** Adventure/co-op mode */
+  | "adventure"
+  /** Configurable unranked custom ("sandbox") game. */
+  | "sandbox"
+  /** Daily Challenge — solo timed run on the shared daily board. */
+  | "daily"
+  /** Forward-compat fallback for game types this client doesn't know. */
+  | "unknown" | (string & {});
+
+/** A single cell in the game grid. */
+export interface GridCell {
+  has_gem: boolean;
+  /**
+   * True when this cell has been **abducted** by a UFO adventure
+   * event. Abducted cells are temporarily unselectable (mirrors
+   * `is_hole` for path validation) and reroll back to normal on a
+   * later round. Adventure-only; multiplayer sessions never set
+   * this.
+   */
+  is_abducted?: boolean;
+  /**
+   * True when this cell is a "hole" — occupies its position in the
+   * layout but can't be selected as part of a word path. Used by
+   * Adventure Mode levels that want asymmetric playable regions.
+   * Hole cells have no letter contribution and are skipped by the
+   * refill pipeline.
+   */
+  is_hole?: boolean;
+  /**
+   * True when this cell has been **poisoned** by a Snake adventure
+   * event. Poisoned letters contribute negative value when used in
+   * a word (sign-flipped — `value` becomes `-value` for scoring),
+   * so players want to avoid them. Poison clears when the cell is
+   * rerolled (word consumption or another event hitting the cell).
+   * Adventure-only; multiplayer sessions never set this.
+   */
+  is_poisoned?: boolean;
+  letter: string;
+  multiplier?: Multiplier;
+  value: number;
+}
+
+/**
+ * Per-level score thresholds for Adventure Mode star awards.
+ *
+ * Hitting `one_star` completes the level; `two_star` and `three_star`
+ * are progressive bonuses. Stars are awarded server-side at session end.
+ */
+export interface LevelTargets {
+  one_star: number;
+  three_star: number;
+  two_star: number;
+}
+
+/** Changes to lobby state (for delta updates instead of full snapshots). */
+export type LobbyChange =
+  /** A player joined the lobby */
+  | {
+    change_type: "player_joined";
+    player: LobbyPlayerInfo;
+  }
+  /** A player left the lobby */
+  | {
+    change_type: "player_left";
+    player_id: string;
+    reason?: string;
+  }
+  /** A player's connection state changed */
+  | {
+    change_type: "player_connection_changed";
+    is_connected: boolean;
+    player_id: string;
+  }
+  /** A game's state changed */
+  | {
+    change_type: "game_state_changed";
+    game_id: string;
+    state: GameState;
+  }
+  /** Pool count updated for a game */
+  | {
+    change_type: "pool_updated";
+    game_id: string;
+    pool_count: number;
+  }
+  /** Host changed */
+  | {
+    change_type: "host_changed";
+    new_host_id: string;
+  };
+
+/** Game info as shown in lobby games list. */
+export interface LobbyGameInfo {
+  current_round: number;
+  game_id: string;
+  /**
+   * Which game type / pool this game belongs to (Open/Sandbox/Adventure),
+   * so clients can distinguish concurrent games in one lobby.
+   */
+  game_type: GameType;
+  max_rounds: number;
+  players: LobbyGamePlayerInfo[];
+}
+
+/** Player info in lobby game list (simplified). */
+export interface LobbyGamePlayerInfo {
+  score: number;
+  user_id: string;
+  username: string;
+}
+
+/** Player information in the lobby (pre-game). */
+export interface LobbyPlayerInfo {
+  /** Profile accent color (integer representation) */
+  accent_color?: number;
+  /** Game they are in, if any */
+  active_game_id: string | null;
+  avatar_url?: string;
+  /** Profile banner URL (Discord CDN) */
+  banner_url?: string;
+  /** The player's status within a game pool, if they are in one. */
+  current_game_pool?: GameType;
+  /** Game they are spectating, if any */
+  spectate_game_id: string | null;
+  /** User ID */
+  user_id: string;
+  username: string;
+}
+
+/** Complete lobby state snapshot. */
+export interface LobbySnapshot {
+  games: LobbyGameInfo[];
+  /**
+   * The lobby's current host (the player who controls sandbox config),
+   * if one has been assigned. Lets clients show host-only controls and a
+   * read-only mirror for non-hosts. Updated live on host transfer.
+   * Serialized as a STRING (like every other id) so JS doesn't lose
+   * precision on snowflake-magnitude ids (>2^53).
+   */
+  host_id?: string;
+  lobby_code?: string;
+  lobby_id: string;
+  lobby_type: LobbyType;
+  /** Maximum players allowed */
+  max_players: number;
+  players: LobbyPlayerInfo[];
+  /**
+   * Current sandbox config for the lobby, if a host has set one.
+   * Absent for normal (non-sandbox) lobbies.
+   */
+  sandbox_config?: GameConfig;
+}
+
+/** Type of lobby - determines how players join. */
+export type LobbyType =
+  /** Lobby tied to a specific Discord channel */
+  | "channel"
+  /** Custom lobby with a shareable code */
+  | "custom";
+
+/** Letter multiplier on a grid cell. */
+export type Multiplier = "double_letter" | "triple_letter" | "double_word";
+
+/**
+ * A news/notification item as sent over the wire (server → client).
+ *
+ * Mirrors the backend's `NewsItem` model but stripped of fields that
+ * shouldn't leave the server (`created_by` user id).
+ *
+ * Used in [`ServerMessage::NewsAnnounced`] for live push and is shape-
+ * compatible with what `/api/news` returns, so the same UI code path
+ * can render either source.
+ */
+export interface NewsItemPayload {
+  /** 0 = popup stays until dismissed; > 0 = auto-hide after N seconds. */
+  auto_hide_seconds: number;
+  created_at: string;
+  expires_at: string | null;
+  id: string;
+  message: string;
+  notification_type: NewsNotificationType;
+  /** Higher priority wins when multiple items are active. */
+  priority: number;
+  /**
+   * When true the frontend re-shows the popup every page load even if
+   * the user has dismissed it (dismissal becomes session-scoped only).
+   */
+  refresh_on_every_login: boolean;
+  title: string;
+}
+
+/**
+ * Type of news notification. Mirrors the canonical values the backend
+ * stores in `news.notification_type` (validated against the
+ * `ALLOWED_NOTIFICATION_TYPES` list at the API boundary).
+ *
+ * `#[serde(other)]` keeps old clients functional when the server
+ * introduces a new variant — unknown values deserialize to
+ * `Unknown` instead of erroring out. Mirrors the
+ * `AdventureEventKind::Unknown` pattern already in this crate.
+ */
+export type NewsNotificationType =
+  | "maintenance"
+  | "announcement"
+  | "update"
+  /** Fallback for variants the server adds after this client was built. */
+  | "unknown" | (string & {});
+
+/** Where a returned page sits in the full list. */
+export interface PageInfo {
+  /** The limit applied (after clamping) */
+  limit: number;
+  /** Offset of the next page, if there is one */
+  next_offset: number | null;
+  offset: number;
+  /** Matching items across all pages */
+  total: number;
+}
+
+/** Offset pagination for admin lists. */
+export interface PageRequest {
+  /** Items to return, clamped to `1..=`[`MAX_PAGE_LIMIT`] */
+  limit: number;
+  /** Items to skip */
+  offset: number;
+}
+
+/** Player information during a game. */
+export interface PlayerInfo {
+  avatar_url?: string;
+  /** Gems collected (0-10, used for powers) */
+  gems: number;
+  /** Whether the player is currently connected */
+  is_connected: boolean;
+  score: number;
+  /** Team number for team modes */
+  team?: number;
+  /** User ID (string to preserve JS number precision) */
+  user_id: string;
+  username: string;
+}
+
+/** Grid position (row, column). */
+export interface Position {
+  col: number;
+  row: number;
+}
+
+/**
+ * State of the auto-rematch countdown after a game ends.
+ *
+ * When a game ends, players are automatically re-queued and a countdown begins.
+ * Any player can trigger an early start, or opt out to return to the lobby.
+ */
+export type RematchCountdownState =
+  /** No rematch countdown active */
+  | {
+    status: "idle";
+  }
+  /** Countdown is active - new game will start when it reaches 0 */
+  | {
+    status: "active";
+    /** When the countdown expires (absolute time for clock sync) */
+    expires_at: string;
+    /** The game pool/game type for the rematch */
+    game_type: GameType;
+    /** Player IDs still in the rematch pool */
+    player_ids: string[];
+    /** Seconds remaining (for initial state display) */
+    seconds_remaining: number;
+  }
+  /** A player triggered an early start */
+  | {
+    status: "starting";
+    /** Who triggered the early start (None if countdown expired naturally) */
+    triggered_by?: string;
+  };
+
+/** Score information for results/leaderboards. */
+export interface ScoreInfo {
+  score: number;
+  user_id: string;
+  username: string;
+}
+
+/** Messages sent from server to client. */
+export type ServerMessage =
+  /**
+   * Initial server greeting after WebSocket connect.
+   *
+   * Sent immediately upon connection, before Identify.
+   */
+  | {
+    type: "hello";
+    /** Recommended heartbeat interval in milliseconds */
+    heartbeat_interval_ms: number;
+    /** Server version for compatibility checks */
+    server_version?: string;
+  }
+  /**
+   * Successful authentication response.
+   *
+   * Contains the full initial state snapshot.
+   */
+  | {
+    type: "ready";
+    /** Full game state (if in a game) */
+    game?: GameSnapshot;
+    /** Full lobby state (if in a lobby) */
+    lobby?: LobbySnapshot;
+    /** The authenticated player's user ID */
+    player_id: string;
+    /** Unique session ID for this connection */
+    session_id: string;
+  }
+  /**
+   * Session resumed successfully after reconnect.
+   *
+   * Contains any events missed during disconnection.
+   */
+  | {
+    type: "resumed";
+    /** Events that occurred while disconnected */
+    missed_events: ServerMessage[];
+  }
+  /**
+   * Heartbeat response.
+   *
+   * Echoes back for latency calculation.
+   */
+  | {
+    type: "heartbeat_ack";
+    /** Server timestamp when heartbeat was received */
+    server_time: number;
+  }
+  /**
+   * Session is invalid or expired.
+   *
+   * Client should re-authenticate.
+   */
+  | {
+    type: "invalid_session";
+    reason: string;
+  }
+  /** Sent when successfully joining a lobby. */
+  | {
+    type: "lobby_joined";
+    /** Full lobby state */
+    lobby: LobbySnapshot;
+    /** 6-char code for custom lobbies */
+    lobby_code?: string;
+    lobby_id: string;
+  }
+  /**
+   * Full lobby state snapshot.
+   *
+   * Sent on initial join or when delta sync fails.
+   */
+  | {
+    type: "lobby_snapshot";
+    lobby: LobbySnapshot;
+  }
+  /**
+   * Incremental lobby state update.
+   *
+   * More efficient than full snapshots for small changes.
+   */
+  | {
+    type: "lobby_delta";
+    changes: LobbyChange[];
+  }
+  /** Confirmation of leaving lobby. */
+  | {
+    type: "lobby_left";
+  }
+  /** Custom lobby was created successfully. */
+  | {
+    type: "custom_lobby_created";
+    lobby_code: string;
+    lobby_id: string;
+  }
+  /**
+   * A new game has started.
+   *
+   * Contains initial game state for all participants.
+   */
+  | {
+    type: "game_started";
+    /** Who goes first */
+    current_turn: string;
+    /**
+     * `Some` marks this as an unranked custom (sandbox) game so every
+     * participant — not just the host — can render the unranked badge.
+     * Omitted for FFA/Adventure (wire format unchanged).
+     */
+    custom?: CustomMeta;
+    game_id: string;
+    grid: GridCell[][];
+    max_rounds: number;
+    players: GamePlayerInfo[];
+    round: number;
+    /**
+     * Server-authoritative time left in the run, in milliseconds. Set for
+     * Daily Challenge runs (incl. resumes); omitted otherwise.
+     */
+    time_remaining_ms?: number;
+    /** Turn time limit in seconds (if configured) */
+    turn_time_limit?: number;
+    /** Your turn order (0-indexed) */
+    your_turn_order: number;
+  }
+  /**
+   * Full game state snapshot.
+   *
+   * Sent when joining as spectator or when delta sync fails.
+   */
+  | {
+    type: "game_snapshot";
+    game: GameSnapshot;
+    game_id: string;
+  }
+  /** Incremental game state update. */
+  | {
+    type: "game_delta";
+    changes: GameChange[];
+    game_id: string;
+  }
+  /** Game has ended normally. */
+  | {
+    type: "game_over";
+    /** Final scores, sorted by rank */
+    final_scores: ScoreInfo[];
+    game_id: string;
+    /** Whether it was a draw */
+    is_draw: boolean;
+    /** Winner's user ID */
+    winner_id: string;
+  }
+  /** Game was cancelled (not enough players, host left, etc.). */
+  | {
+    type: "game_cancelled";
+    game_id: string;
+    reason: string;
+  }
+  /**
+   * Adventure Mode level ended. Sent after `GameOver` for sessions that
+   * had `adventure_level.is_some()` in their config. The client uses
+   * this to render the level-complete modal; the server has already
+   * upserted the row in `adventure_progress` by the time this is sent.
+   */
+  | {
+    type: "adventure_level_result";
+    /**
+     * This run's duration in milliseconds (from game creation to
+     * game_over). Lets the client render "Time: 2:34" in the result
+     * modal without fetching progress. Unsigned since durations
+     * are non-negative, and consistent with other duration /
+     * interval fields in the protocol (`heartbeat_interval_ms`,
+     * `server_time`).
+     */
+    duration_ms?: number;
+    /** Which level was played (1..=50). */
+    level: number;
+    /** True iff `score` exceeded the prior `high_score` for this user + level. */
+    personal_best: boolean;
+    /** Human player's final score for this run. */
+    score: number;
+    /** Stars awarded (0..=3). 0 = target not hit; 1+ = hit one_star/two_star/three_star. */
+    stars: number;
+    /**
+     * If this run unlocked a new level, the newly-unlocked level id.
+     * `None` if no new level was unlocked (level was already completed
+     * or was the campaign finale).
+     */
+    unlocked_level?: number;
+  }
+  /**
+   * Daily Challenge run finished (deadline reached or player ended early).
+   * The server has already recorded the attempt + move log by send time.
+   */
+  | {
+    type: "daily_result";
+    /** Max attempts today for this user (1 free, 3 premium). */
+    attempts_max: number;
+    /** Attempts used so far today (incl. this one). */
+    attempts_used: number;
+    /** The player's best score across today's attempts. */
+    personal_best: number;
+    /** 1-based rank on today's shared leaderboard. */
+    rank: number;
+    /** This run's final score. */
+    score: number;
+    /** The day's seed (deterministic from the UTC date). */
+    seed: number;
+  }
+  /**
+   * Adventure Mode random event (bomb / snake / UFO). Broadcast by
+   * the event scheduler when a roll succeeds at round change.
+   * `affected_positions` is the set of grid cells the client should
+   * animate; `new_grid` is the post-effect board so the client can
+   * swap in the updated tiles atomically with the animation.
+   */
+  | {
+    type: "adventure_event";
+    affected_positions: Position[];
+    game_id: string;
+    kind: AdventureEventKind;
+    /**
+     * Short human-readable label for the event, already localized
+     * server-side. Rendered in a brief toast alongside the animation.
+     */
+    label: string;
+    /**
+     * Full post-effect grid. Clients apply this as an authoritative
+     * snapshot — no delta needed.
+     */
+    new_grid: GridCell[][];
+  }
+  /** A player joined the lobby. */
+  | {
+    type: "player_joined";
+    player: LobbyPlayerInfo;
+  }
+  /** A player left the lobby. */
+  | {
+    type: "player_left";
+    player_id: string;
+    reason?: string;
+  }
+  /** A player reconnected after disconnection. */
+  | {
+    type: "player_reconnected";
+    player_id: string;
+  }
+  /** A player disconnected (may reconnect). */
+  | {
+    type: "player_disconnected";
+    game_id?: string;
+    /** Grace period in seconds before they're removed */
+    grace_period_seconds: number;
+    player_id: string;
+  }
+  /** A word was successfully scored. */
+  | {
+    type: "word_scored";
+    game_id: string;
+    /** Gems earned from this word */
+    gems_earned: number;
+    /** Updated grid (letters replaced) */
+    new_grid: GridCell[][];
+    /** Positions that formed the word */
+    path: Position[];
+    player_id: string;
+    score: number;
+    /** New gem total */
+    total_gems: number;
+    /** New total score */
+    total_score: number;
+    word: string;
+  }
+  /** Turn changed to another player. */
+  | {
+    type: "turn_changed";
+    game_id: string;
+    player_id: string;
+    round: number;
+    /** Time remaining for this turn (if timer active) */
+    time_remaining?: number;
+  }
+  /** A player passed their turn. */
+  | {
+    type: "turn_passed";
+    game_id: string;
+    player_id: string;
+  }
+  /** Round number changed. */
+  | {
+    type: "round_changed";
+    game_id: string;
+    max_rounds: number;
+    /**
+     * New grid if the board changed at the start of this round: either
+     * regenerated (`regenerate_board_each_round: true`) or adventure
+     * effects (poison, abduction) expired. Omitted when unchanged.
+     */
+    new_grid?: GridCell[][];
+    round: number;
+  }
+  /** Board was shuffled. */
+  | {
+    type: "board_shuffled";
+    game_id: string;
+    gems_spent: number;
+    new_grid: GridCell[][];
+    player_id: string;
+    /** Player's remaining gems after shuffle */
+    total_gems: number;
+  }
+  /** A tile was swapped. */
+  | {
+    type: "tile_swapped";
+    col: number;
+    game_id: string;
+    gems_spent: number;
+    new_letter: string;
+    old_letter: string;
+    player_id: string;
+    row: number;
+    /** Player's remaining gems after swap */
+    total_gems: number;
+  }
+  /** Player entered swap mode (for animation). */
+  | {
+    type: "swap_mode_entered";
+    game_id: string;
+    player_id: string;
+  }
+  /** Player exited swap mode. */
+  | {
+    type: "swap_mode_exited";
+    game_id: string;
+    player_id: string;
+  }
+  /** Successfully joined as spectator. */
+  | {
+    type: "spectator_joined";
+    /** Full game state */
+    game: GameSnapshot;
+    game_id: string;
+  }
+  /** A new spectator joined (broadcast to others). */
+  | {
+    type: "spectator_added";
+    game_id: string;
+    spectator: SpectatorInfo;
+  }
+  /** A spectator left. */
+  | {
+    type: "spectator_removed";
+    game_id: string;
+    spectator_id: string;
+  }
+  /** Spectator joined as player. */
+  | {
+    type: "spectator_became_player";
+    game_id: string;
+    player_id: string;
+    username: string;
+  }
+  /** Confirmation of leaving spectator mode. */
+  | {
+    type: "spectator_left";
+  }
+  /** Another player's tile selection (for live preview). */
+  | {
+    type: "selection_update";
+    game_id: string;
+    player_id: string;
+    positions: Position[];
+  }
+  /** Timer vote state changed. */
+  | {
+    type: "timer_vote_update";
+    game_id: string;
+    state: TimerVoteState;
+  }
+  /** Turn timer started (vote passed). */
+  | {
+    type: "turn_timer_started";
+    game_id: string;
+    seconds: number;
+    target_player_id: string;
+  }
+  /** Turn timer expired - player auto-passed. */
+  | {
+    type: "turn_timer_expired";
+    game_id: string;
+    player_id: string;
+  }
+  /**
+   * Rematch countdown state update.
+   *
+   * Sent to all players on the results screen after a game ends.
+   */
+  | {
+    type: "rematch_countdown_update";
+    /** The game that just ended */
+    previous_game_id: string;
+    /** Current countdown state */
+    state: RematchCountdownState;
+  }
+  /**
+   * A player opted out of rematch pool.
+   *
+   * Broadcast to remaining players so they can update the player list.
+   */
+  | {
+    type: "player_left_rematch";
+    player_id: string;
+    /** The game they left from */
+    previous_game_id: string;
+  }
+  /**
+   * Rematch is starting (sent right before `GameStarted`).
+   *
+   * Allows frontend to show "Starting..." before the new game begins.
+   */
+  | {
+    type: "rematch_starting";
+    /** The previous game that ended */
+    previous_game_id: string;
+    /** Who triggered the early start (None if countdown expired naturally) */
+    triggered_by?: string;
+  }
+  | {
+    type: "player_pool_changed";
+    new_pool: GameType | null;
+    old_pool: GameType | null;
+    player_id: string;
+  }
+  /** Player joined the game pool. */
+  | {
+    type: "pool_joined";
+    game_id: string;
+    position: number;
+    total_in_pool: number;
+  }
+  /** Pool position updated. */
+  | {
+    type: "pool_update";
+    game_id: string;
+    position: number;
+    total_in_pool: number;
+  }
+  /** Left the pool. */
+  | {
+    type: "pool_left";
+  }
+  /** Response to admin game list request. */
+  | {
+    type: "admin_games_list";
+    games: AdminGameInfo[];
+  }
+  /** Game was deleted by admin. */
+  | {
+    type: "admin_game_deleted";
+    audit?: AdminAudit;
+    game_id: string;
+  }
+  /** Response to `AdminListLobbies`. */
+  | {
+    type: "admin_lobbies_list";
+    lobbies: AdminLobbyInfo[];
+    page: PageInfo;
+  }
+  /** Response to `AdminInspectLobby`. */
+  | {
+    type: "admin_lobby_inspected";
+    lobby: LobbySnapshot;
+  }
+  /** Response to `AdminInspectGame`. `your_player` is always absent. */
+  | {
+    type: "admin_game_inspected";
+    game: GameSnapshot;
+  }
+  /** A player was kicked by admin. */
+  | {
+    type: "admin_player_kicked";
+    audit: AdminAudit;
+    /** The game they were removed from, if any */
+    game_id?: string;
+    /** The lobby they were removed from, if any */
+    lobby_id?: string;
+    player_id: string;
+  }
+  /** A player was banned (and kicked) by admin. */
+  | {
+    type: "admin_player_banned";
+    audit: AdminAudit;
+    /** When the ban lifts; `None` if permanent */
+    expires_at: string | null;
+    player_id: string;
+  }
+  /** A game was ended early by admin. */
+  | {
+    type: "admin_game_force_ended";
+    audit: AdminAudit;
+    final_scores: ScoreInfo[];
+    game_id: string;
+    winner: WinnerPolicy;
+    /** Who the policy made the winners (empty for `no_winner`) */
+    winner_ids: string[];
+  }
+  /** A news item was announced by admin. */
+  | {
+    type: "admin_news_pushed";
+    audit: AdminAudit;
+    news_id: string;
+    /** Connected clients it was sent to */
+    recipients: number;
+  }
+  /**
+   * Generic state update (legacy format).
+   *
+   * Used for backward compatibility with existing frontend.
+   */
+  | {
+    type: "game_state";
+    current_turn: number;
+    game_id: string;
+    grid: GridCell[][];
+    max_rounds: number;
+    players: PlayerInfo[];
+    round: number;
+    spectators: SpectatorInfo[];
+    state: string;
+    timer_vote_state: TimerVoteState;
+    used_words: string[];
+  }
+  /** Lobby state update (legacy format). */
+  | {
+    type: "lobby_state";
+    games: LobbyGameInfo[];
+    lobby_id: string;
+    players: LobbyPlayerInfo[];
+  }
+  /** Debug state response with player context diagnostics. */
+  | {
+    type: "debug_state_response";
+    backend_game_state: DebugBackendGameState | null;
+    handler_game_state: DebugHandlerGameState | null;
+    lobby_state: DebugLobbyState | null;
+    player: DebugPlayerInfo;
+    timestamp: string;
+    websocket_context: DebugWebsocketContext;
+  }
+  /**
+   * A news/notification item was created or activated by an admin.
+   *
+   * Pushed to every connected client when an admin posts a new item
+   * via the news admin endpoints, or when an existing item is
+   * updated in a way that should re-surface it (becoming active,
+   * extending expires_at, etc.). Clients should treat it the same
+   * as if they had just fetched `/api/news` and seen this entry —
+   * in particular, maintenance items with a future `expires_at`
+   * drive the corner-countdown toast, while announcement/update
+   * items go through the existing full-screen overlay.
+   */
+  | {
+    type: "news_announced";
+    item: NewsItemPayload;
+  }
+  /** Error response. */
+  | {
+    type: "error";
+    code: ErrorCode;
+    /** Additional context (e.g., which field was invalid) */
+    details?: unknown;
+    message: string;
+  };
+
+/** Spectator information. */
+export interface SpectatorInfo {
+  avatar_url?: string;
+  user_id: string;
+  username: string;
+}
+
+/**
+ * State of the timer vote system.
+ *
+ * The timer vote allows players to collectively vote to start a turn timer
+ * on the current player. This prevents indefinite stalling.
+ */
+export type TimerVoteState =
+  /** No vote in progress, button is idle */
+  | {
+    status: "idle";
+  }
+  /** Vote is in progress */
+  | {
+    status: "vote_in_progress";
+    /** When the vote expires */
+    expires_at: string;
+    /** User ID of who initiated the vote */
+    initiator_id: string;
+    /** User IDs of players who have voted yes */
+    voters: string[];
+    /** Total votes needed to pass */
+    votes_needed: number;
+  }
+  /** Timer is actively counting down */
+  | {
+    status: "timer_active";
+    /** When the timer expires */
+    expires_at: string;
+    /** Target player ID (user ID) */
+    target_player_id: string;
+  }
+  /** Vote failed, in cooldown before another can start */
+  | {
+    status: "cooldown";
+    /** When the cooldown expires */
+    expires_at: string;
+  }
+  /** Feature disabled (not enough players) */
+  | {
+    status: "disabled";
+  };
+
+/** Who won a game an admin ends early. */
+export type WinnerPolicy =
+  /** Whoever leads on score (ties share the win) */
+  | {
+    policy: "highest_score";
+  }
+  /** Nobody; the game is recorded as abandoned */
+  | {
+    policy: "no_winner";
+  }
+  /** A specific player in the game */
+  | {
+    policy: "player";
+    player_id: string;
+  };
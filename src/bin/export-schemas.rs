@@ -0,0 +1,26 @@
+//! Write JSON Schemas for every wire type.
+//!
+//! ```text
+//! cargo run --features schema --bin export-schemas -- [out-dir]
+//! ```
+//!
+//! `out-dir` defaults to `schemas/`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let dir = std::env::args_os()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("schemas"), PathBuf::from);
+    match runecast_protocol::protocol::schema::write_schemas(&dir) {
+        Ok(paths) => {
+            println!("wrote {} schemas to {}", paths.len(), dir.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("failed to write schemas to {}: {e}", dir.display());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Write the AsyncAPI document for the WebSocket protocol.
+//!
+//! ```text
+//! cargo run --features asyncapi --bin gen-asyncapi -- [out-file]
+//! ```
+//!
+//! `out-file` defaults to `asyncapi.json`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use runecast_protocol::protocol::asyncapi::generate;
+
+fn main() -> ExitCode {
+    let path = std::env::args_os()
+        .nth(1)
+        .map_or_else(|| PathBuf::from("asyncapi.json"), PathBuf::from);
+    let mut text = serde_json::to_string_pretty(&generate()).expect("document serializes");
+    text.push('\n');
+    match std::fs::write(&path, text) {
+        Ok(()) => {
+            println!("wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("failed to write {}: {e}", path.display());
+            ExitCode::FAILURE
+        }
+    }
+}
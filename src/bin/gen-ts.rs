@@ -0,0 +1,33 @@
+//! Regenerate the checked-in TypeScript bindings.
+//!
+//! ```text
+//! cargo run --features typescript --bin gen-ts -- [out-file]
+//! ```
+//!
+//! `out-file` defaults to `bindings/runecast-protocol.ts`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use runecast_protocol::protocol::typescript::{generate, BINDINGS_PATH};
+
+fn main() -> ExitCode {
+    let path = std::env::args_os()
+        .nth(1)
+        .map_or_else(|| PathBuf::from(BINDINGS_PATH), PathBuf::from);
+    let result = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, generate()));
+    match result {
+        Ok(()) => {
+            println!("wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("failed to write {}: {e}", path.display());
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! One simulated player.
+
+use std::time::Duration;
+
+use runecast_protocol::client::{ClientConfig, ClientError, ClientEvent, Connect, RuneCastClient};
+use runecast_protocol::game::{board, replay, GameRng};
+use runecast_protocol::protocol::{
+    ClientMessage, GameSnapshot, GameState, GameType, Grid, Position, ServerMessage,
+};
+use tokio::time::{sleep, Instant};
+
+use crate::stats::Stats;
+
+/// What one bot does.
+#[derive(Debug, Clone)]
+pub struct BotConfig {
+    /// Channel lobby to join.
+    pub channel_id: String,
+    /// Pause between actions; each pause is jittered by ±50%.
+    pub think: Duration,
+    /// How often to time an explicit `heartbeat` round trip.
+    pub heartbeat_every: Duration,
+    /// When to disconnect.
+    pub deadline: Instant,
+    pub seed: u64,
+}
+
+struct Bot {
+    client: RuneCastClient,
+    config: BotConfig,
+    rng: GameRng,
+    stats: Stats,
+    game: Option<GameSnapshot>,
+}
+
+/// Connect, join the lobby and its game pool, and play until the deadline.
+pub async fn run<C: Connect>(
+    connector: C,
+    client_config: ClientConfig,
+    config: BotConfig,
+) -> Stats {
+    let Ok((client, mut events)) = RuneCastClient::connect(connector, client_config).await else {
+        return Stats {
+            failed_connects: 1,
+            ..Stats::default()
+        };
+    };
+    let mut bot = Bot {
+        client,
+        rng: GameRng::new(config.seed),
+        config,
+        stats: Stats::default(),
+        game: None,
+    };
+
+    let join = ClientMessage::JoinChannelLobby {
+        channel_id: bot.config.channel_id.clone(),
+        guild_id: None,
+    };
+    if bot.timed(join).await.is_some() {
+        bot.join_pool().await;
+    }
+
+    let deadline = bot.config.deadline;
+    let mut heartbeat = tokio::time::interval(bot.config.heartbeat_every);
+    heartbeat.reset();
+    loop {
+        tokio::select! {
+            () = tokio::time::sleep_until(deadline) => break,
+            _ = heartbeat.tick() => {
+                bot.timed(ClientMessage::Heartbeat).await;
+            }
+            event = events.recv() => match event {
+                Some(ClientEvent::Message(msg)) => bot.on_message(*msg).await,
+                Some(ClientEvent::Disconnected) => bot.stats.disconnects += 1,
+                Some(ClientEvent::Reconnected { .. }) => bot.stats.reconnects += 1,
+                Some(ClientEvent::Closed) | None => {
+                    bot.stats.lost += 1;
+                    break;
+                }
+            },
+        }
+    }
+    bot.client.close();
+    bot.stats
+}
+
+impl Bot {
+    /// Send `msg` and record how long its reply took. Returns the reply, or
+    /// `None` if the request failed.
+    async fn timed(&mut self, msg: ClientMessage) -> Option<ServerMessage> {
+        let message_type = msg.message_type();
+        let start = Instant::now();
+        match self.client.request(msg).await {
+            Ok(Some(reply)) => {
+                self.stats.answered(message_type, start.elapsed());
+                Some(reply)
+            }
+            Ok(None) => {
+                self.stats.sent(message_type);
+                None
+            }
+            // The code itself is counted when the `Error` event arrives.
+            Err(ClientError::Server { .. }) => {
+                self.stats.answered(message_type, start.elapsed());
+                None
+            }
+            Err(_) => {
+                self.stats.unanswered(message_type);
+                None
+            }
+        }
+    }
+
+    async fn pause(&mut self) {
+        let jitter = 0.5 + self.rng.next_f64();
+        sleep(self.config.think.mul_f64(jitter)).await;
+    }
+
+    async fn join_pool(&mut self) {
+        self.timed(ClientMessage::JoinGamePool {
+            game_type: GameType::Open,
+        })
+        .await;
+    }
+
+    async fn on_message(&mut self, msg: ServerMessage) {
+        let me = self.client.player_id();
+        let my_turn = match &msg {
+            ServerMessage::Error { code, .. } => {
+                self.stats.error(*code);
+                false
+            }
+            ServerMessage::GameStarted {
+                game_id,
+                current_turn,
+                ..
+            } => {
+                let mut game = GameSnapshot::empty(game_id);
+                replay::apply(&mut game, &msg);
+                self.game = Some(game);
+                *current_turn == me
+            }
+            ServerMessage::GameOver { .. } | ServerMessage::GameCancelled { .. } => {
+                if matches!(msg, ServerMessage::GameOver { .. }) {
+                    self.stats.games_finished += 1;
+                }
+                self.game = None;
+                self.pause().await;
+                self.join_pool().await;
+                false
+            }
+            ServerMessage::GameSnapshot { game, .. } => {
+                self.game = Some(game.clone());
+                game.state == GameState::InProgress && game.current_turn == me
+            }
+            _ => {
+                if let Some(game) = &mut self.game {
+                    replay::apply(game, &msg);
+                }
+                matches!(msg, ServerMessage::TurnChanged { player_id, .. } if player_id == me)
+            }
+        };
+        if my_turn {
+            self.play_turn().await;
+        }
+    }
+
+    /// Trace a random path, streaming the selection as it grows, then
+    /// submit it; pass if the server rejects the word.
+    async fn play_turn(&mut self) {
+        let Some((game_id, path)) = self.game.as_ref().map(|g| {
+            let len = 3 + self.rng.below(3);
+            (g.game_id.clone(), random_path(&g.grid, len, &mut self.rng))
+        }) else {
+            return;
+        };
+        self.pause().await;
+        for end in 1..=path.len() {
+            self.timed(ClientMessage::SelectionUpdate {
+                game_id: game_id.clone(),
+                positions: path[..end].to_vec(),
+            })
+            .await;
+            sleep(self.config.think / 4).await;
+        }
+        let word = self.game.as_ref().map_or_else(String::new, |g| {
+            path.iter()
+                .filter_map(|p| board::cell(&g.grid, *p))
+                .map(|c| c.letter)
+                .collect()
+        });
+        let scored = self
+            .timed(ClientMessage::SubmitWord {
+                game_id: game_id.clone(),
+                word,
+                positions: path,
+            })
+            .await;
+        if scored.is_none() {
+            self.timed(ClientMessage::PassTurn { game_id }).await;
+        }
+    }
+}
+
+/// A self-avoiding random walk of up to `len` playable tiles.
+fn random_path(grid: &Grid, len: usize, rng: &mut GameRng) -> Vec<Position> {
+    let playable =
+        |p: &Position| board::cell(grid, *p).is_some_and(|c| !c.is_hole && !c.is_abducted);
+    let starts: Vec<Position> = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(row, cells)| (0..cells.len()).map(move |col| Position { row, col }))
+        .filter(playable)
+        .collect();
+    let Some(&start) = rng.choose(&starts) else {
+        return Vec::new();
+    };
+    let mut path = vec![start];
+    while path.len() < len {
+        let last = *path.last().expect("path starts non-empty");
+        let next: Vec<Position> = starts
+            .iter()
+            .copied()
+            .filter(|p| board::is_adjacent(last, *p) && !path.contains(p))
+            .collect();
+        let Some(&step) = rng.choose(&next) else {
+            break;
+        };
+        path.push(step);
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use runecast_protocol::protocol::GridCell;
+
+    use super::*;
+
+    #[test]
+    fn random_paths_are_adjacent_and_avoid_holes() {
+        let grid: Grid = (0..4)
+            .map(|row| {
+                (0..4)
+                    .map(|col| GridCell {
+                        letter: 'A',
+                        value: 1,
+                        multiplier: None,
+                        has_gem: false,
+                        is_hole: (row + col) % 3 == 0,
+                        is_poisoned: false,
+                        is_abducted: false,
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut rng = GameRng::new(3);
+        for _ in 0..50 {
+            let path = random_path(&grid, 5, &mut rng);
+            assert!(!path.is_empty());
+            assert!(path.windows(2).all(|w| board::is_adjacent(w[0], w[1])));
+            assert!(path
+                .iter()
+                .all(|p| !board::cell(&grid, *p).unwrap().is_hole));
+            for (i, p) in path.iter().enumerate() {
+                assert!(!path[i + 1..].contains(p));
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Load-test a RuneCast server with a swarm of bot clients.
+//!
+//! ```text
+//! cargo run --features loadgen --bin runecast-loadgen -- (<ws-url> | --stub) [options]
+//!
+//!   --clients N           simulated players (default 50)
+//!   --channels N          channel lobbies to spread them over (default 5)
+//!   --duration SECS       how long to play (default 60)
+//!   --ramp-ms MS          delay between client starts (default 20)
+//!   --think-ms MS         pause between bot actions (default 500)
+//!   --heartbeat-ms MS     timed heartbeat interval (default 5000)
+//!   --players-per-game N  pool size that starts a game, --stub only (default 2)
+//!   --seed N              bot and stub randomness (default 1)
+//! ```
+//!
+//! Each bot handshakes, joins a channel lobby and its open game pool, and on
+//! its turn streams a random selection before submitting it (passing if the
+//! word is rejected). With `--stub` the swarm plays against an in-process
+//! server instead of the network.
+//!
+//! The report lists round-trip percentiles per `message_type()`, the error
+//! codes received and how many connections dropped.
+
+mod bot;
+mod stats;
+mod stub;
+
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::Duration;
+
+use runecast_protocol::client::{ClientConfig, Connect};
+use runecast_protocol::transport::{TransportError, WsTransport};
+use tokio::time::Instant;
+
+use crate::bot::BotConfig;
+use crate::stats::Stats;
+use crate::stub::StubServer;
+
+/// Swarm shape and pacing.
+#[derive(Debug, Clone)]
+struct SwarmConfig {
+    clients: usize,
+    channels: usize,
+    duration: Duration,
+    ramp: Duration,
+    think: Duration,
+    heartbeat_every: Duration,
+    seed: u64,
+}
+
+impl Default for SwarmConfig {
+    fn default() -> Self {
+        Self {
+            clients: 50,
+            channels: 5,
+            duration: Duration::from_secs(60),
+            ramp: Duration::from_millis(20),
+            think: Duration::from_millis(500),
+            heartbeat_every: Duration::from_secs(5),
+            seed: 1,
+        }
+    }
+}
+
+enum Target {
+    Url(String),
+    Stub { players_per_game: usize },
+}
+
+fn parse_args() -> Result<(Target, SwarmConfig), String> {
+    fn value<T: FromStr>(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<T, String> {
+        args.next()
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| format!("{flag} needs a number"))
+    }
+
+    let mut args = std::env::args().skip(1);
+    let mut config = SwarmConfig::default();
+    let mut url = None;
+    let mut stub = false;
+    let mut players_per_game = 2;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stub" => stub = true,
+            "--clients" => config.clients = value(&mut args, &arg)?,
+            "--channels" => config.channels = value::<usize>(&mut args, &arg)?.max(1),
+            "--duration" => config.duration = Duration::from_secs(value(&mut args, &arg)?),
+            "--ramp-ms" => config.ramp = Duration::from_millis(value(&mut args, &arg)?),
+            "--think-ms" => config.think = Duration::from_millis(value(&mut args, &arg)?),
+            "--heartbeat-ms" => {
+                config.heartbeat_every =
+                    Duration::from_millis(value::<u64>(&mut args, &arg)?.max(1));
+            }
+            "--players-per-game" => players_per_game = value(&mut args, &arg)?,
+            "--seed" => config.seed = value(&mut args, &arg)?,
+            _ if url.is_none() && !arg.starts_with("--") => url = Some(arg),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    let target = match (url, stub) {
+        (Some(url), false) => Target::Url(url),
+        (None, true) => Target::Stub { players_per_game },
+        _ => return Err("give either a <ws-url> or --stub".into()),
+    };
+    Ok((target, config))
+}
+
+fn main() -> ExitCode {
+    let (target, config) = match parse_args() {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{e}\nusage: runecast-loadgen (<ws-url> | --stub) [options]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let runtime = tokio::runtime::Runtime::new().expect("tokio runtime starts");
+    let started = std::time::Instant::now();
+    let stats = runtime.block_on(async {
+        match target {
+            Target::Url(url) => {
+                let connector = move || {
+                    let url = url.clone();
+                    async move {
+                        let (ws, _) = tokio_tungstenite::connect_async(url)
+                            .await
+                            .map_err(|e| TransportError::Io(Box::new(e)))?;
+                        Ok(WsTransport::new(ws))
+                    }
+                };
+                swarm(connector, &config).await
+            }
+            Target::Stub { players_per_game } => {
+                let stub = StubServer::new(players_per_game, config.seed);
+                swarm(move || std::future::ready(Ok(stub.connect())), &config).await
+            }
+        }
+    });
+    println!(
+        "{} clients over {} channels, {:.1}s\n",
+        config.clients,
+        config.channels,
+        started.elapsed().as_secs_f64()
+    );
+    println!("{stats}");
+    ExitCode::SUCCESS
+}
+
+/// Run `config.clients` bots through `connector` and merge what they saw.
+async fn swarm<C: Connect + Clone>(connector: C, config: &SwarmConfig) -> Stats {
+    let deadline = Instant::now() + config.duration;
+    let mut bots = Vec::with_capacity(config.clients);
+    for i in 0..config.clients {
+        if i > 0 {
+            tokio::time::sleep(config.ramp).await;
+        }
+        let bot = BotConfig {
+            channel_id: format!("loadgen-{}", i % config.channels),
+            think: config.think,
+            heartbeat_every: config.heartbeat_every,
+            deadline,
+            seed: config.seed.wrapping_add(i as u64),
+        };
+        bots.push(tokio::spawn(bot::run(
+            connector.clone(),
+            ClientConfig::default(),
+            bot,
+        )));
+    }
+    let mut stats = Stats::default();
+    for bot in bots {
+        match bot.await {
+            Ok(bot_stats) => stats.merge(bot_stats),
+            Err(_) => stats.lost += 1,
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn swarm_plays_against_the_stub() {
+        let stub = StubServer::new(2, 9);
+        let config = SwarmConfig {
+            clients: 6,
+            channels: 2,
+            duration: Duration::from_millis(600),
+            ramp: Duration::from_millis(1),
+            think: Duration::from_millis(4),
+            heartbeat_every: Duration::from_millis(50),
+            seed: 9,
+        };
+        let stats = swarm(move || std::future::ready(Ok(stub.connect())), &config).await;
+
+        assert_eq!(stats.failed_connects, 0);
+        assert_eq!(stats.disconnects, 0);
+        let answered = |t: &str| stats.by_type.get(t).map_or(0, |s| s.latencies.len());
+        assert_eq!(answered("join_channel_lobby"), 6, "{stats}");
+        assert!(answered("join_game_pool") >= 6, "{stats}");
+        assert!(answered("heartbeat") > 0, "{stats}");
+        assert!(answered("submit_word") > 0, "{stats}");
+        assert!(stats.by_type["selection_update"].sent >= 3, "{stats}");
+        assert!(stats.by_type["submit_word"].percentile(50.0).is_some());
+    }
+}
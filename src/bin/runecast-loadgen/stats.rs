@@ -0,0 +1,186 @@
+//! What the swarm measured.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+use runecast_protocol::protocol::ErrorCode;
+
+/// Counts and round-trip times for one client message type.
+#[derive(Debug, Default, Clone)]
+pub struct TypeStats {
+    /// Messages sent, including ones that got no reply.
+    pub sent: u64,
+    /// Round trips of requests that were answered, successfully or not.
+    pub latencies: Vec<Duration>,
+    /// Requests that timed out or were cut off by a disconnect.
+    pub unanswered: u64,
+}
+
+impl TypeStats {
+    /// The `p`th percentile (0–100) latency, nearest-rank.
+    #[must_use]
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        let mut sorted = self.latencies.clone();
+        sorted.sort_unstable();
+        if sorted.is_empty() {
+            return None;
+        }
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+        Some(sorted[rank.clamp(1, sorted.len()) - 1])
+    }
+}
+
+/// Everything one client, or the whole swarm, measured.
+#[derive(Debug, Default, Clone)]
+pub struct Stats {
+    /// Keyed by `ClientMessage::message_type()`.
+    pub by_type: BTreeMap<&'static str, TypeStats>,
+    /// `Error` codes received, whether replies or unsolicited.
+    pub errors: HashMap<ErrorCode, u64>,
+    /// Clients that never completed the handshake.
+    pub failed_connects: u64,
+    /// Connections that dropped mid-run.
+    pub disconnects: u64,
+    /// Dropped connections that came back.
+    pub reconnects: u64,
+    /// Clients that gave up after a drop.
+    pub lost: u64,
+    /// Games that reached `GameOver`, counted once per player.
+    pub games_finished: u64,
+}
+
+impl Stats {
+    /// Count a message sent without waiting for a reply.
+    pub fn sent(&mut self, message_type: &'static str) {
+        self.by_type.entry(message_type).or_default().sent += 1;
+    }
+
+    /// Record an answered request.
+    pub fn answered(&mut self, message_type: &'static str, latency: Duration) {
+        let entry = self.by_type.entry(message_type).or_default();
+        entry.sent += 1;
+        entry.latencies.push(latency);
+    }
+
+    /// Record a request that got no answer.
+    pub fn unanswered(&mut self, message_type: &'static str) {
+        let entry = self.by_type.entry(message_type).or_default();
+        entry.sent += 1;
+        entry.unanswered += 1;
+    }
+
+    pub fn error(&mut self, code: ErrorCode) {
+        *self.errors.entry(code).or_default() += 1;
+    }
+
+    /// Fold `other` into `self`.
+    pub fn merge(&mut self, other: Stats) {
+        for (message_type, theirs) in other.by_type {
+            let ours = self.by_type.entry(message_type).or_default();
+            ours.sent += theirs.sent;
+            ours.unanswered += theirs.unanswered;
+            ours.latencies.extend(theirs.latencies);
+        }
+        for (code, count) in other.errors {
+            *self.errors.entry(code).or_default() += count;
+        }
+        self.failed_connects += other.failed_connects;
+        self.disconnects += other.disconnects;
+        self.reconnects += other.reconnects;
+        self.lost += other.lost;
+        self.games_finished += other.games_finished;
+    }
+}
+
+struct Ms(Option<Duration>);
+
+impl fmt::Display for Ms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(d) => write!(f, "{:>9.2}", d.as_secs_f64() * 1000.0),
+            None => write!(f, "{:>9}", "-"),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<22} {:>8} {:>8} {:>9} {:>9} {:>9} {:>9}",
+            "message_type", "sent", "no reply", "p50 ms", "p90 ms", "p99 ms", "max ms"
+        )?;
+        for (message_type, stats) in &self.by_type {
+            writeln!(
+                f,
+                "{message_type:<22} {:>8} {:>8} {} {} {} {}",
+                stats.sent,
+                stats.unanswered,
+                Ms(stats.percentile(50.0)),
+                Ms(stats.percentile(90.0)),
+                Ms(stats.percentile(99.0)),
+                Ms(stats.latencies.iter().max().copied()),
+            )?;
+        }
+        writeln!(f)?;
+        if self.errors.is_empty() {
+            writeln!(f, "errors: none")?;
+        } else {
+            writeln!(f, "errors:")?;
+            let mut errors: Vec<_> = self
+                .errors
+                .iter()
+                .map(|(code, count)| (format!("{code:?}"), count))
+                .collect();
+            errors.sort();
+            for (code, count) in errors {
+                writeln!(f, "  {code:<24} {count}")?;
+            }
+        }
+        write!(
+            f,
+            "failed connects: {}  disconnects: {}  reconnects: {}  lost: {}  games finished: {}",
+            self.failed_connects, self.disconnects, self.reconnects, self.lost, self.games_finished
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_and_merge() {
+        let mut a = Stats::default();
+        for ms in 1..=100 {
+            a.answered("submit_word", Duration::from_millis(ms));
+        }
+        a.error(ErrorCode::NotYourTurn);
+        let mut b = Stats::default();
+        b.sent("selection_update");
+        b.unanswered("submit_word");
+        b.error(ErrorCode::NotYourTurn);
+        b.disconnects = 1;
+        a.merge(b);
+
+        let submit = &a.by_type["submit_word"];
+        assert_eq!(submit.sent, 101);
+        assert_eq!(submit.unanswered, 1);
+        assert_eq!(submit.percentile(50.0), Some(Duration::from_millis(50)));
+        assert_eq!(submit.percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(submit.percentile(100.0), Some(Duration::from_millis(100)));
+        assert_eq!(a.by_type["selection_update"].percentile(50.0), None);
+        assert_eq!(a.errors[&ErrorCode::NotYourTurn], 2);
+
+        let report = a.to_string();
+        assert!(report.contains("submit_word"), "{report}");
+        assert!(report.contains("NotYourTurn"), "{report}");
+        assert!(report.contains("disconnects: 1"), "{report}");
+    }
+}
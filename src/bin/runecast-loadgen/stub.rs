@@ -0,0 +1,347 @@
+//! A small in-process server for dry runs and tests.
+//!
+//! It covers the slice of the protocol the bots use: channel lobbies, game
+//! pools that start a [`GameEngine`] game once enough players are waiting,
+//! and in-game actions. Connections are in-memory [`loopback`] pairs, so a
+//! swarm against the stub measures the client and engine, not the network.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use runecast_protocol::game::engine::Dictionary;
+use runecast_protocol::game::{Audience, GameEngine, Outbound};
+use runecast_protocol::protocol::{
+    ClientMessage, ErrorCode, GameConfig, GameState, LobbyPlayerInfo, LobbySnapshot, LobbyType,
+    PlayerInfo, ServerMessage,
+};
+use runecast_protocol::transport::{loopback, LoopbackTransport, ServerConnection, TransportError};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+/// Words need a vowel, so some bot guesses are rejected.
+struct NeedsVowel;
+
+impl Dictionary for NeedsVowel {
+    fn contains(&self, word: &str) -> bool {
+        word.chars().any(|c| "AEIOU".contains(c))
+    }
+}
+
+/// Handle for opening connections to the stub.
+#[derive(Clone)]
+pub struct StubServer {
+    hub: Arc<Mutex<Hub>>,
+}
+
+impl StubServer {
+    /// A stub that starts a game whenever `players_per_game` players are
+    /// waiting in one lobby's pool.
+    pub fn new(players_per_game: usize, seed: u64) -> Self {
+        Self {
+            hub: Arc::new(Mutex::new(Hub {
+                players_per_game: players_per_game.max(1),
+                seed,
+                ..Hub::default()
+            })),
+        }
+    }
+
+    /// Open a connection; the server side runs on its own task.
+    pub fn connect(&self) -> LoopbackTransport {
+        let (client, server) = loopback();
+        tokio::spawn(serve(Arc::clone(&self.hub), server));
+        client
+    }
+}
+
+async fn serve(hub: Arc<Mutex<Hub>>, transport: LoopbackTransport) {
+    let mut conn = ServerConnection::new(transport, None);
+    if conn.hello().await.is_err() {
+        return;
+    }
+    let (tx, mut outbox) = unbounded_channel();
+    let player_id = lock(&hub).register(tx);
+    loop {
+        tokio::select! {
+            msg = conn.recv() => match msg {
+                Ok(Some(msg)) => lock(&hub).handle(player_id, msg),
+                Err(TransportError::Parse(_)) => {}
+                Ok(None) | Err(_) => break,
+            },
+            Some(msg) = outbox.recv() => {
+                if conn.send(msg).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    lock(&hub).disconnect(player_id);
+}
+
+fn lock(hub: &Mutex<Hub>) -> std::sync::MutexGuard<'_, Hub> {
+    hub.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Default)]
+struct Hub {
+    players_per_game: usize,
+    seed: u64,
+    next_player: i64,
+    next_game: u64,
+    outboxes: HashMap<i64, UnboundedSender<ServerMessage>>,
+    /// Keyed by channel id.
+    lobbies: HashMap<String, LobbySnapshot>,
+    player_lobby: HashMap<i64, String>,
+    /// Waiting players, keyed by channel id.
+    pools: HashMap<String, Vec<i64>>,
+    games: HashMap<String, GameEngine>,
+    player_game: HashMap<i64, String>,
+}
+
+impl Hub {
+    fn register(&mut self, outbox: UnboundedSender<ServerMessage>) -> i64 {
+        self.next_player += 1;
+        self.outboxes.insert(self.next_player, outbox);
+        self.next_player
+    }
+
+    fn send(&self, player_id: i64, msg: ServerMessage) {
+        if let Some(outbox) = self.outboxes.get(&player_id) {
+            let _ = outbox.send(msg);
+        }
+    }
+
+    fn handle(&mut self, player_id: i64, msg: ClientMessage) {
+        match msg {
+            ClientMessage::Identify { .. } => self.send(
+                player_id,
+                ServerMessage::Ready {
+                    session_id: format!("stub-{player_id}"),
+                    player_id,
+                    lobby: None,
+                    game: None,
+                },
+            ),
+            ClientMessage::JoinChannelLobby { channel_id, .. } => {
+                self.join_lobby(player_id, channel_id);
+            }
+            ClientMessage::LeaveLobby => {
+                let reply = if self.leave_lobby(player_id) {
+                    ServerMessage::LobbyLeft
+                } else {
+                    ServerMessage::error(ErrorCode::NotInLobby)
+                };
+                self.send(player_id, reply);
+            }
+            ClientMessage::JoinGamePool { .. } => self.join_pool(player_id),
+            ClientMessage::LeaveGamePool => {
+                if let Some(pool) = self
+                    .player_lobby
+                    .get(&player_id)
+                    .and_then(|channel| self.pools.get_mut(channel))
+                {
+                    pool.retain(|id| *id != player_id);
+                }
+                self.send(player_id, ServerMessage::PoolLeft);
+            }
+            msg => {
+                let Some(game_id) = self.player_game.get(&player_id).cloned() else {
+                    self.send(player_id, ServerMessage::error(ErrorCode::NotInGame));
+                    return;
+                };
+                let engine = self.games.get_mut(&game_id).expect("tracked game");
+                let out = engine.handle(player_id, &msg);
+                let finished = engine.state() == GameState::Finished;
+                self.route(&game_id, out);
+                if finished {
+                    self.end_game(&game_id);
+                }
+            }
+        }
+    }
+
+    fn join_lobby(&mut self, player_id: i64, channel_id: String) {
+        if self.player_lobby.contains_key(&player_id) {
+            self.send(player_id, ServerMessage::error(ErrorCode::AlreadyInLobby));
+            return;
+        }
+        let lobby = self
+            .lobbies
+            .entry(channel_id.clone())
+            .or_insert_with(|| LobbySnapshot {
+                lobby_id: channel_id.clone(),
+                lobby_type: LobbyType::Channel,
+                lobby_code: None,
+                players: Vec::new(),
+                games: Vec::new(),
+                max_players: u8::MAX,
+                sandbox_config: None,
+                host_id: Some(player_id),
+            });
+        if lobby.players.len() >= usize::from(lobby.max_players) {
+            self.send(player_id, ServerMessage::error(ErrorCode::LobbyFull));
+            return;
+        }
+        let player = LobbyPlayerInfo {
+            user_id: player_id,
+            username: format!("bot{player_id}"),
+            avatar_url: None,
+            banner_url: None,
+            accent_color: None,
+            current_game_pool: None,
+            active_game_id: None,
+            spectate_game_id: None,
+        };
+        lobby.players.push(player.clone());
+        let lobby = lobby.clone();
+        self.player_lobby.insert(player_id, channel_id.clone());
+        for other in lobby.players.iter().filter(|p| p.user_id != player_id) {
+            self.send(
+                other.user_id,
+                ServerMessage::PlayerJoined {
+                    player: player.clone(),
+                },
+            );
+        }
+        self.send(
+            player_id,
+            ServerMessage::LobbyJoined {
+                lobby_id: channel_id,
+                lobby_code: None,
+                lobby,
+            },
+        );
+    }
+
+    /// Take the player out of their lobby and pool. Returns false if they
+    /// were not in one.
+    fn leave_lobby(&mut self, player_id: i64) -> bool {
+        let Some(channel) = self.player_lobby.remove(&player_id) else {
+            return false;
+        };
+        if let Some(pool) = self.pools.get_mut(&channel) {
+            pool.retain(|id| *id != player_id);
+        }
+        if let Some(lobby) = self.lobbies.get_mut(&channel) {
+            lobby.players.retain(|p| p.user_id != player_id);
+            let others: Vec<i64> = lobby.players.iter().map(|p| p.user_id).collect();
+            for other in others {
+                self.send(
+                    other,
+                    ServerMessage::PlayerLeft {
+                        player_id,
+                        reason: None,
+                    },
+                );
+            }
+        }
+        true
+    }
+
+    fn join_pool(&mut self, player_id: i64) {
+        let Some(channel) = self.player_lobby.get(&player_id).cloned() else {
+            self.send(player_id, ServerMessage::error(ErrorCode::NotInLobby));
+            return;
+        };
+        if self.player_game.contains_key(&player_id) {
+            self.send(player_id, ServerMessage::error(ErrorCode::AlreadyInGame));
+            return;
+        }
+        let pool = self.pools.entry(channel).or_default();
+        if !pool.contains(&player_id) {
+            pool.push(player_id);
+        }
+        let total = pool.len();
+        let position = pool.iter().position(|id| *id == player_id).unwrap_or(0) + 1;
+        let starting: Option<Vec<i64>> =
+            (total >= self.players_per_game).then(|| pool.drain(..self.players_per_game).collect());
+        self.send(
+            player_id,
+            ServerMessage::PoolJoined {
+                position: i32::try_from(position).unwrap_or(i32::MAX),
+                total_in_pool: i32::try_from(total).unwrap_or(i32::MAX),
+                game_id: String::new(),
+            },
+        );
+        if let Some(players) = starting {
+            self.start_game(players);
+        }
+    }
+
+    fn start_game(&mut self, player_ids: Vec<i64>) {
+        self.next_game += 1;
+        let game_id = format!("stub-game-{}", self.next_game);
+        let players = player_ids
+            .iter()
+            .map(|&user_id| PlayerInfo {
+                user_id,
+                username: format!("bot{user_id}"),
+                avatar_url: None,
+                score: 0,
+                gems: 0,
+                team: None,
+                is_connected: true,
+            })
+            .collect();
+        let mut engine = GameEngine::new(
+            game_id.clone(),
+            GameConfig::default(),
+            players,
+            self.seed.wrapping_add(self.next_game),
+            Box::new(NeedsVowel),
+        );
+        let out = engine.start();
+        for id in player_ids {
+            self.player_game.insert(id, game_id.clone());
+        }
+        self.games.insert(game_id.clone(), engine);
+        self.route(&game_id, out);
+    }
+
+    fn route(&self, game_id: &str, out: Vec<Outbound>) {
+        let players: Vec<i64> = self
+            .player_game
+            .iter()
+            .filter(|(_, g)| g.as_str() == game_id)
+            .map(|(id, _)| *id)
+            .collect();
+        for outbound in out {
+            match outbound.audience {
+                Audience::Player(id) => self.send(id, outbound.message),
+                _ => {
+                    for &id in players.iter().filter(|id| outbound.is_for(**id)) {
+                        self.send(id, outbound.message.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn end_game(&mut self, game_id: &str) {
+        self.games.remove(game_id);
+        self.player_game.retain(|_, g| g != game_id);
+    }
+
+    fn disconnect(&mut self, player_id: i64) {
+        self.outboxes.remove(&player_id);
+        self.leave_lobby(player_id);
+        // The engine has no notion of leaving, so the game is called off.
+        if let Some(game_id) = self.player_game.remove(&player_id) {
+            let players: Vec<i64> = self
+                .player_game
+                .iter()
+                .filter(|(_, g)| **g == game_id)
+                .map(|(id, _)| *id)
+                .collect();
+            for id in players {
+                self.send(
+                    id,
+                    ServerMessage::GameCancelled {
+                        game_id: game_id.clone(),
+                        reason: "a player disconnected".into(),
+                    },
+                );
+            }
+            self.end_game(&game_id);
+        }
+    }
+}
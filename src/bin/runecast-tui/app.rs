@@ -0,0 +1,377 @@
+//! Client state and key handling, independent of the terminal.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use runecast_protocol::game::{board, replay};
+use runecast_protocol::protocol::{
+    ClientMessage, GameSnapshot, GameState, LobbySnapshot, Position, ServerMessage,
+};
+
+/// Everything the screen shows.
+#[derive(Debug)]
+pub struct App {
+    pub player_id: i64,
+    pub lobby: Option<LobbySnapshot>,
+    pub game: Option<GameSnapshot>,
+    pub cursor: Position,
+    /// Our path, in selection order.
+    pub selection: Vec<Position>,
+    /// The current player's live selection, when it is not us.
+    pub others_selection: Vec<Position>,
+    /// Waiting for a letter to swap the tile under the cursor.
+    pub swap_mode: bool,
+    pub status: String,
+    pub quit: bool,
+}
+
+impl App {
+    #[must_use]
+    pub fn new(player_id: i64) -> Self {
+        Self {
+            player_id,
+            lobby: None,
+            game: None,
+            cursor: Position { row: 0, col: 0 },
+            selection: Vec::new(),
+            others_selection: Vec::new(),
+            swap_mode: false,
+            status: "Connected. n: start game  q: quit".into(),
+            quit: false,
+        }
+    }
+
+    fn game_id(&self) -> Option<String> {
+        self.game.as_ref().map(|g| g.game_id.clone())
+    }
+
+    fn in_game(&self) -> bool {
+        self.game
+            .as_ref()
+            .is_some_and(|g| g.state == GameState::InProgress)
+    }
+
+    #[must_use]
+    pub fn is_my_turn(&self) -> bool {
+        self.game
+            .as_ref()
+            .is_some_and(|g| g.state == GameState::InProgress && g.current_turn == self.player_id)
+    }
+
+    /// Fold a server message into the state.
+    pub fn on_message(&mut self, msg: &ServerMessage) {
+        match msg {
+            ServerMessage::Ready {
+                player_id,
+                lobby,
+                game,
+                ..
+            } => {
+                self.player_id = *player_id;
+                self.lobby.clone_from(lobby);
+                self.game.clone_from(game);
+            }
+            ServerMessage::LobbyJoined { lobby, .. } | ServerMessage::LobbySnapshot { lobby } => {
+                self.lobby = Some(lobby.clone());
+            }
+            ServerMessage::LobbyDelta { changes } => {
+                if let Some(lobby) = &mut self.lobby {
+                    for change in changes {
+                        lobby.apply_change(change);
+                    }
+                }
+            }
+            ServerMessage::LobbyLeft => self.lobby = None,
+            ServerMessage::GameStarted { game_id, .. } => {
+                let mut game = GameSnapshot::empty(game_id);
+                replay::apply(&mut game, msg);
+                self.game = Some(game);
+                self.reset_selection();
+                self.status = "Game started.".into();
+            }
+            ServerMessage::GameSnapshot { game, .. }
+            | ServerMessage::SpectatorJoined { game, .. } => {
+                self.game = Some(game.clone());
+            }
+            ServerMessage::SelectionUpdate {
+                player_id,
+                positions,
+                ..
+            } if *player_id != self.player_id => {
+                self.others_selection.clone_from(positions);
+            }
+            ServerMessage::Error { code, message, .. } => {
+                self.status = format!("Error ({code}): {message}");
+            }
+            _ => {
+                if let Some(game) = &mut self.game {
+                    replay::apply(game, msg);
+                }
+                match msg {
+                    ServerMessage::WordScored {
+                        player_id,
+                        word,
+                        score,
+                        ..
+                    } => {
+                        self.status =
+                            format!("{} scored {word} for {score}", self.name(*player_id));
+                        self.reset_selection();
+                    }
+                    ServerMessage::TurnChanged { .. } => {
+                        self.reset_selection();
+                        self.status = if self.is_my_turn() {
+                            "Your turn.".into()
+                        } else {
+                            "Waiting for the other players.".into()
+                        };
+                    }
+                    ServerMessage::GameOver { winner_id, .. } => {
+                        self.status = format!("Game over. {} wins.", self.name(*winner_id));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if let Some(game) = &self.game {
+            let rows = game.grid.len();
+            let cols = game.grid.first().map_or(0, Vec::len);
+            self.cursor.row = self.cursor.row.min(rows.saturating_sub(1));
+            self.cursor.col = self.cursor.col.min(cols.saturating_sub(1));
+        }
+    }
+
+    fn name(&self, player_id: i64) -> String {
+        self.game
+            .as_ref()
+            .and_then(|g| g.players.iter().find(|p| p.user_id == player_id))
+            .map_or_else(|| player_id.to_string(), |p| p.username.clone())
+    }
+
+    fn reset_selection(&mut self) {
+        self.selection.clear();
+        self.others_selection.clear();
+        self.swap_mode = false;
+    }
+
+    /// Handle a key press; returns the messages to send.
+    pub fn on_key(&mut self, key: KeyEvent) -> Vec<ClientMessage> {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.quit = true;
+            return Vec::new();
+        }
+        let Some(game_id) = self.game_id().filter(|_| self.is_my_turn()) else {
+            return self.on_lobby_key(key);
+        };
+        if self.swap_mode {
+            return self.on_swap_key(key, game_id);
+        }
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            KeyCode::Char(' ') => return self.toggle_cursor(game_id),
+            KeyCode::Enter => return self.submit(game_id),
+            KeyCode::Esc | KeyCode::Backspace if !self.selection.is_empty() => {
+                self.selection.clear();
+                return vec![ClientMessage::SelectionUpdate {
+                    game_id,
+                    positions: Vec::new(),
+                }];
+            }
+            KeyCode::Char('s') => return vec![ClientMessage::ShuffleBoard { game_id }],
+            KeyCode::Char('p') => {
+                self.selection.clear();
+                return vec![ClientMessage::PassTurn { game_id }];
+            }
+            KeyCode::Char('w') => {
+                self.swap_mode = true;
+                self.status = "Swap: type the new letter for the tile, Esc to cancel.".into();
+                return vec![ClientMessage::EnterSwapMode { game_id }];
+            }
+            KeyCode::Char('q') => self.quit = true,
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn on_lobby_key(&mut self, key: KeyEvent) -> Vec<ClientMessage> {
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char('n') if self.lobby.is_some() && !self.in_game() => {
+                return vec![ClientMessage::StartGame { config: None }];
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_cursor(0, 1),
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn on_swap_key(&mut self, key: KeyEvent, game_id: String) -> Vec<ClientMessage> {
+        match key.code {
+            KeyCode::Esc => {
+                self.swap_mode = false;
+                self.status = "Swap cancelled.".into();
+                vec![ClientMessage::ExitSwapMode { game_id }]
+            }
+            KeyCode::Char(c) if c.is_ascii_alphabetic() => {
+                self.swap_mode = false;
+                vec![ClientMessage::SwapTile {
+                    game_id,
+                    row: self.cursor.row,
+                    col: self.cursor.col,
+                    new_letter: c.to_ascii_uppercase(),
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn move_cursor(&mut self, d_row: isize, d_col: isize) {
+        let Some(grid) = self.game.as_ref().map(|g| &g.grid) else {
+            return;
+        };
+        let rows = grid.len();
+        let cols = grid.first().map_or(0, Vec::len);
+        if let (Some(row), Some(col)) = (
+            self.cursor
+                .row
+                .checked_add_signed(d_row)
+                .filter(|r| *r < rows),
+            self.cursor
+                .col
+                .checked_add_signed(d_col)
+                .filter(|c| *c < cols),
+        ) {
+            self.cursor = Position { row, col };
+        }
+    }
+
+    /// Extend the path with the cursor tile, or drop the path back to it if
+    /// it is already selected.
+    fn toggle_cursor(&mut self, game_id: String) -> Vec<ClientMessage> {
+        let pos = self.cursor;
+        if let Some(index) = self.selection.iter().position(|p| *p == pos) {
+            self.selection.truncate(index);
+        } else {
+            let grid = &self.game.as_ref().expect("in a game").grid;
+            let selectable = board::cell(grid, pos).is_some_and(|c| !c.is_hole && !c.is_abducted);
+            let connected = self
+                .selection
+                .last()
+                .is_none_or(|last| board::is_adjacent(*last, pos));
+            if !selectable || !connected {
+                return Vec::new();
+            }
+            self.selection.push(pos);
+        }
+        vec![ClientMessage::SelectionUpdate {
+            game_id,
+            positions: self.selection.clone(),
+        }]
+    }
+
+    fn submit(&mut self, game_id: String) -> Vec<ClientMessage> {
+        if self.selection.is_empty() {
+            return Vec::new();
+        }
+        let grid = &self.game.as_ref().expect("in a game").grid;
+        let word = self
+            .selection
+            .iter()
+            .filter_map(|p| board::cell(grid, *p))
+            .map(|c| c.letter)
+            .collect();
+        vec![ClientMessage::SubmitWord {
+            game_id,
+            word,
+            positions: std::mem::take(&mut self.selection),
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use runecast_protocol::protocol::GridCell;
+
+    use super::*;
+
+    fn app_with_board() -> App {
+        let mut app = App::new(1);
+        let mut game = GameSnapshot::empty("g1");
+        game.state = GameState::InProgress;
+        game.current_turn = 1;
+        game.grid = (0..3)
+            .map(|row| {
+                (0..3)
+                    .map(|col| GridCell {
+                        letter: char::from(b'A' + u8::try_from(row * 3 + col).unwrap()),
+                        value: 1,
+                        multiplier: None,
+                        has_gem: false,
+                        is_hole: row == 1 && col == 1,
+                        is_poisoned: false,
+                        is_abducted: false,
+                    })
+                    .collect()
+            })
+            .collect();
+        app.game = Some(game);
+        app
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Vec<ClientMessage> {
+        app.on_key(KeyEvent::from(code))
+    }
+
+    #[test]
+    fn selection_follows_adjacent_playable_tiles() {
+        let mut app = app_with_board();
+        assert_eq!(press(&mut app, KeyCode::Char(' ')).len(), 1);
+        // The hole in the middle cannot be selected.
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Right);
+        assert!(press(&mut app, KeyCode::Char(' ')).is_empty());
+        // Neither can a tile that does not touch the path.
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Down);
+        assert!(press(&mut app, KeyCode::Char(' ')).is_empty());
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Left);
+        press(&mut app, KeyCode::Char(' '));
+
+        let sent = press(&mut app, KeyCode::Enter);
+        assert!(
+            matches!(&sent[..], [ClientMessage::SubmitWord { word, .. }] if word == "AB"),
+            "{sent:?}"
+        );
+        assert!(app.selection.is_empty());
+    }
+
+    #[test]
+    fn swap_mode_takes_the_next_letter() {
+        let mut app = app_with_board();
+        press(&mut app, KeyCode::Right);
+        assert!(matches!(
+            &press(&mut app, KeyCode::Char('w'))[..],
+            [ClientMessage::EnterSwapMode { .. }]
+        ));
+        let sent = press(&mut app, KeyCode::Char('z'));
+        assert!(
+            matches!(
+                &sent[..],
+                [ClientMessage::SwapTile {
+                    row: 0,
+                    col: 1,
+                    new_letter: 'Z',
+                    ..
+                }]
+            ),
+            "{sent:?}"
+        );
+        assert!(!app.swap_mode);
+    }
+}
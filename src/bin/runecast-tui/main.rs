@@ -0,0 +1,271 @@
+//! Play RuneCast from a terminal.
+//!
+//! ```text
+//! cargo run --features tui --bin runecast-tui -- <ws-url> [--channel ID] [--guild ID]
+//! ```
+//!
+//! Connects with [`RuneCastClient`], optionally joins the lobby for a Discord
+//! channel, and draws the lobby and board. Keys are listed at the bottom of
+//! the screen.
+
+mod app;
+mod ui;
+
+use std::process::ExitCode;
+
+use crossterm::event::{Event, KeyEventKind};
+use runecast_protocol::client::{ClientConfig, ClientError, ClientEvent, Events, RuneCastClient};
+use runecast_protocol::transport::{TransportError, WsTransport};
+use tokio::sync::mpsc;
+
+use crate::app::App;
+
+struct Args {
+    url: String,
+    channel: Option<String>,
+    guild: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    let mut url = None;
+    let mut channel = None;
+    let mut guild = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--channel" => channel = Some(args.next().ok_or("--channel needs a value")?),
+            "--guild" => guild = Some(args.next().ok_or("--guild needs a value")?),
+            _ if url.is_none() && !arg.starts_with("--") => url = Some(arg),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(Args {
+        url: url.ok_or("missing <ws-url>")?,
+        channel,
+        guild,
+    })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\nusage: runecast-tui <ws-url> [--channel ID] [--guild ID]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let runtime = tokio::runtime::Runtime::new().expect("tokio runtime starts");
+    match runtime.block_on(run(args)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("runecast-tui: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(args: Args) -> Result<(), ClientError> {
+    let url = args.url;
+    let connector = move || {
+        let url = url.clone();
+        async move {
+            let (ws, _) = tokio_tungstenite::connect_async(url)
+                .await
+                .map_err(|e| TransportError::Io(Box::new(e)))?;
+            Ok(WsTransport::new(ws))
+        }
+    };
+    let (client, events) = RuneCastClient::connect(connector, ClientConfig::default()).await?;
+    if let Some(channel) = args.channel {
+        client.join_channel_lobby(channel, args.guild).await?;
+    }
+
+    // crossterm's reader blocks, so it gets its own thread.
+    let (key_tx, keys) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while let Ok(event) = crossterm::event::read() {
+            if key_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut terminal = ratatui::init();
+    let result = session(&client, events, keys, |app| {
+        // A failed draw leaves the previous frame on screen.
+        let _ = terminal.draw(|frame| ui::draw(frame, app));
+    })
+    .await;
+    ratatui::restore();
+    result
+}
+
+/// Feed terminal events and server events into an [`App`], redrawing after
+/// each, until the user quits or the client closes.
+async fn session(
+    client: &RuneCastClient,
+    mut events: Events,
+    mut terminal_events: mpsc::UnboundedReceiver<Event>,
+    mut draw: impl FnMut(&App),
+) -> Result<(), ClientError> {
+    let mut app = App::new(client.player_id());
+    loop {
+        draw(&app);
+        tokio::select! {
+            event = terminal_events.recv() => match event {
+                Some(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    for msg in app.on_key(key) {
+                        client.send(msg)?;
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            },
+            event = events.recv() => match event {
+                Some(ClientEvent::Message(msg)) => app.on_message(&msg),
+                Some(ClientEvent::Disconnected) => app.status = "Disconnected; reconnecting...".into(),
+                Some(ClientEvent::Reconnected { resumed }) => {
+                    app.status = if resumed {
+                        "Reconnected.".into()
+                    } else {
+                        "Reconnected; session could not be resumed.".into()
+                    };
+                }
+                Some(ClientEvent::Closed) | None => break,
+            },
+        }
+        if app.quit {
+            break;
+        }
+    }
+    client.close();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent};
+    use runecast_protocol::game::engine::AcceptAllWords;
+    use runecast_protocol::game::GameEngine;
+    use runecast_protocol::protocol::{ClientMessage, GameConfig, LobbySnapshot, ServerMessage};
+    use runecast_protocol::testing::{lobby_snapshot, player_info};
+    use runecast_protocol::transport::{loopback, LoopbackTransport, ServerConnection};
+    use tokio::sync::watch;
+
+    use super::*;
+
+    /// A stub server for player 1: `Ready` in a lobby, then a real engine
+    /// once the client starts a game. Every game message it receives is
+    /// forwarded to `seen`.
+    async fn stub_server(transport: LoopbackTransport, seen: mpsc::UnboundedSender<ClientMessage>) {
+        let mut conn = ServerConnection::new(transport, None);
+        conn.hello().await.unwrap();
+        let mut engine = GameEngine::new(
+            "g1",
+            GameConfig::default(),
+            vec![player_info(1, "ann"), player_info(2, "bob")],
+            7,
+            Box::new(AcceptAllWords),
+        );
+        while let Ok(Some(msg)) = conn.recv().await {
+            let outbound = match &msg {
+                ClientMessage::Identify { .. } => {
+                    let lobby = LobbySnapshot {
+                        host_id: Some(1),
+                        ..lobby_snapshot("123")
+                    };
+                    conn.send(ServerMessage::Ready {
+                        session_id: "s1".into(),
+                        player_id: 1,
+                        lobby: Some(lobby),
+                        game: None,
+                    })
+                    .await
+                    .unwrap();
+                    continue;
+                }
+                ClientMessage::StartGame { .. } => engine.start(),
+                other => {
+                    seen.send(other.clone()).unwrap();
+                    engine.handle(1, other)
+                }
+            };
+            for out in outbound.into_iter().filter(|o| o.is_for(1)) {
+                conn.send(out.message).await.unwrap();
+            }
+        }
+    }
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::from(code))
+    }
+
+    #[tokio::test]
+    async fn keys_select_and_submit_against_a_stub_server() {
+        let (client_end, server_end) = loopback();
+        let (seen_tx, mut seen) = mpsc::unbounded_channel();
+        tokio::spawn(stub_server(server_end, seen_tx));
+        let client_end = std::sync::Mutex::new(Some(client_end));
+        let connector = move || {
+            let transport = client_end.lock().unwrap().take();
+            async move { transport.ok_or(TransportError::Closed(None)) }
+        };
+        let config = ClientConfig {
+            reconnect_attempts: 0,
+            ..ClientConfig::default()
+        };
+        let (client, events) = RuneCastClient::connect(connector, config).await.unwrap();
+
+        let (keys, terminal_events) = mpsc::unbounded_channel();
+        let (state_tx, mut state) = watch::channel((false, false, 0));
+        let session = tokio::spawn(async move {
+            session(&client, events, terminal_events, |app| {
+                let score = app
+                    .game
+                    .as_ref()
+                    .and_then(|g| g.players.iter().find(|p| p.user_id == 1))
+                    .map_or(0, |p| p.score);
+                state_tx.send_replace((app.lobby.is_some(), app.is_my_turn(), score));
+            })
+            .await
+        });
+
+        state.wait_for(|(in_lobby, _, _)| *in_lobby).await.unwrap();
+        keys.send(key(KeyCode::Char('n'))).unwrap();
+        state.wait_for(|(_, my_turn, _)| *my_turn).await.unwrap();
+
+        // Select the first three tiles of the top row, then submit.
+        for code in [
+            KeyCode::Char(' '),
+            KeyCode::Right,
+            KeyCode::Char(' '),
+            KeyCode::Char('l'),
+            KeyCode::Char(' '),
+            KeyCode::Enter,
+        ] {
+            keys.send(key(code)).unwrap();
+        }
+        for len in 1..=3 {
+            let msg = seen.recv().await.unwrap();
+            assert!(
+                matches!(&msg, ClientMessage::SelectionUpdate { positions, .. } if positions.len() == len),
+                "{msg:?}"
+            );
+        }
+        let ClientMessage::SubmitWord {
+            game_id,
+            word,
+            positions,
+        } = seen.recv().await.unwrap()
+        else {
+            panic!("expected submit_word");
+        };
+        assert_eq!(game_id, "g1");
+        assert_eq!(word.chars().count(), 3);
+        assert_eq!(positions.len(), 3);
+
+        state.wait_for(|(_, _, score)| *score > 0).await.unwrap();
+        keys.send(key(KeyCode::Char('q'))).unwrap();
+        session.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,335 @@
+//! Drawing the [`App`] with ratatui.
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::Frame;
+use runecast_protocol::protocol::{GameSnapshot, GridCell, LobbySnapshot, Multiplier, Position};
+
+use crate::app::App;
+
+const KEYS_IN_GAME: &str =
+    "arrows/hjkl move  space select  enter submit  esc clear  s shuffle  w swap  p pass  q quit";
+const KEYS_IN_LOBBY: &str = "n start game  q quit";
+
+/// Draw the whole screen.
+pub fn draw(frame: &mut Frame<'_>, app: &App) {
+    let [main, status, keys] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let in_game = match &app.game {
+        Some(game) => {
+            draw_game(frame, main, app, game);
+            true
+        }
+        None => {
+            draw_lobby(frame, main, app.lobby.as_ref());
+            false
+        }
+    };
+    frame.render_widget(Paragraph::new(app.status.as_str()), status);
+    let help = if in_game { KEYS_IN_GAME } else { KEYS_IN_LOBBY };
+    frame.render_widget(
+        Paragraph::new(help).style(Style::new().fg(Color::DarkGray)),
+        keys,
+    );
+}
+
+fn draw_lobby(frame: &mut Frame<'_>, area: Rect, lobby: Option<&LobbySnapshot>) {
+    let Some(lobby) = lobby else {
+        frame.render_widget(
+            Paragraph::new("Not in a lobby.").block(Block::bordered().title(" Lobby ")),
+            area,
+        );
+        return;
+    };
+    let [players, games] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+
+    let title = match &lobby.lobby_code {
+        Some(code) => format!(" Lobby {code} "),
+        None => format!(" Lobby {} ", lobby.lobby_id),
+    };
+    let lines: Vec<Line<'_>> = lobby
+        .players
+        .iter()
+        .map(|p| {
+            let host = if Some(p.user_id) == lobby.host_id {
+                " (host)"
+            } else {
+                ""
+            };
+            Line::from(format!("{}{host}", p.username))
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(format!(
+            "{title}{}/{} ",
+            lobby.players.len(),
+            lobby.max_players
+        ))),
+        players,
+    );
+
+    let lines: Vec<Line<'_>> = lobby
+        .games
+        .iter()
+        .map(|g| {
+            Line::from(format!(
+                "{} {:?} round {}/{} ({} players)",
+                g.game_id,
+                g.game_type,
+                g.current_round,
+                g.max_rounds,
+                g.players.len()
+            ))
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Games ")),
+        games,
+    );
+}
+
+fn draw_game(frame: &mut Frame<'_>, area: Rect, app: &App, game: &GameSnapshot) {
+    let [board, side] =
+        Layout::horizontal([Constraint::Min(20), Constraint::Length(28)]).areas(area);
+
+    let rows: Vec<Line<'_>> = game
+        .grid
+        .iter()
+        .enumerate()
+        .map(|(row, cells)| {
+            Line::from(
+                cells
+                    .iter()
+                    .enumerate()
+                    .map(|(col, cell)| tile(app, cell, Position { row, col }))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect();
+    let title = format!(
+        " {} round {}/{} ",
+        game.game_id, game.round, game.max_rounds
+    );
+    frame.render_widget(
+        Paragraph::new(rows).block(Block::bordered().title(title)),
+        board,
+    );
+
+    let mut lines: Vec<Line<'_>> = game
+        .players
+        .iter()
+        .map(|p| {
+            let turn = if p.user_id == game.current_turn {
+                "> "
+            } else {
+                "  "
+            };
+            let style = if p.is_connected {
+                Style::new()
+            } else {
+                Style::new().fg(Color::DarkGray)
+            };
+            Line::styled(
+                format!("{turn}{:<12} {:>4} ◆{}", p.username, p.score, p.gems),
+                style,
+            )
+        })
+        .collect();
+    if !app.selection.is_empty() {
+        let word: String = app
+            .selection
+            .iter()
+            .filter_map(|p| game.grid.get(p.row).and_then(|r| r.get(p.col)))
+            .map(|c| c.letter)
+            .collect();
+        lines.push(Line::default());
+        lines.push(Line::from(format!("Word: {word}")));
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Players ")),
+        side,
+    );
+}
+
+/// One board tile, three columns wide: a gem marker, the letter and a
+/// multiplier marker.
+fn tile(app: &App, cell: &GridCell, pos: Position) -> Span<'static> {
+    if cell.is_hole {
+        return Span::raw("   ");
+    }
+    let letter = if cell.is_abducted { '?' } else { cell.letter };
+    let gem = if cell.has_gem { '◆' } else { ' ' };
+    let marker = match cell.multiplier {
+        Some(Multiplier::DoubleLetter) => '²',
+        Some(Multiplier::TripleLetter) => '³',
+        Some(Multiplier::DoubleWord) => '*',
+        None => ' ',
+    };
+
+    let mut style = match cell.multiplier {
+        Some(Multiplier::DoubleLetter) => Style::new().fg(Color::Cyan),
+        Some(Multiplier::TripleLetter) => Style::new().fg(Color::Blue),
+        Some(Multiplier::DoubleWord) => Style::new().fg(Color::Magenta),
+        None => Style::new(),
+    };
+    if cell.is_poisoned {
+        style = style.fg(Color::Red);
+    }
+    if cell.is_abducted {
+        style = style.add_modifier(Modifier::DIM);
+    }
+    if app.selection.contains(&pos) {
+        style = style.bg(Color::Yellow).fg(Color::Black);
+    } else if app.others_selection.contains(&pos) {
+        style = style.bg(Color::DarkGray);
+    }
+    if app.cursor == pos && app.is_my_turn() {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    if app.swap_mode && app.cursor == pos {
+        style = style.add_modifier(Modifier::SLOW_BLINK);
+    }
+    Span::styled(format!("{gem}{letter}{marker}"), style)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+    use runecast_protocol::protocol::{
+        GameSnapshot, GameState, PlayerInfo, ServerMessage, TimerVoteState,
+    };
+
+    use super::*;
+
+    fn cell(letter: char) -> GridCell {
+        GridCell {
+            letter,
+            value: 1,
+            multiplier: None,
+            has_gem: false,
+            is_hole: false,
+            is_poisoned: false,
+            is_abducted: false,
+        }
+    }
+
+    fn render(app: &App) -> (Vec<String>, ratatui::buffer::Buffer) {
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        let rows = (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect();
+        (rows, buffer)
+    }
+
+    #[test]
+    fn board_marks_special_tiles() {
+        let mut app = App::new(1);
+        let grid = vec![vec![
+            GridCell {
+                has_gem: true,
+                ..cell('A')
+            },
+            GridCell {
+                multiplier: Some(Multiplier::TripleLetter),
+                ..cell('B')
+            },
+            GridCell {
+                is_hole: true,
+                ..cell('C')
+            },
+            GridCell {
+                is_poisoned: true,
+                ..cell('D')
+            },
+            GridCell {
+                is_abducted: true,
+                ..cell('E')
+            },
+        ]];
+        app.on_message(&ServerMessage::GameSnapshot {
+            game_id: "g1".into(),
+            game: GameSnapshot {
+                game_id: "g1".into(),
+                state: GameState::InProgress,
+                grid,
+                players: vec![PlayerInfo {
+                    user_id: 1,
+                    username: "ann".into(),
+                    avatar_url: None,
+                    score: 12,
+                    gems: 3,
+                    team: None,
+                    is_connected: true,
+                }],
+                spectators: Vec::new(),
+                current_turn: 1,
+                round: 1,
+                max_rounds: 5,
+                used_words: Vec::new(),
+                timer_vote_state: TimerVoteState::Idle,
+                your_player: None,
+                timer_expiration_time: None,
+                custom: None,
+                time_remaining_ms: None,
+            },
+        });
+
+        let (rows, buffer) = render(&app);
+        assert!(rows[1].starts_with("│◆A  B³    D  ?"), "{}", rows[1]);
+        assert!(rows[1].contains("> ann"), "{}", rows[1]);
+        assert!(rows[0].contains("g1 round 1/5"), "{}", rows[0]);
+        // The cursor starts on the gem tile; poison is red.
+        assert!(buffer[(2, 1)].modifier.contains(Modifier::REVERSED));
+        assert_eq!(buffer[(11, 1)].fg, Color::Red);
+        assert!(buffer[(14, 1)].modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn lobby_lists_players_and_host() {
+        let mut app = App::new(1);
+        app.on_message(&ServerMessage::Ready {
+            session_id: "s1".into(),
+            player_id: 1,
+            lobby: Some(LobbySnapshot {
+                lobby_id: "123".into(),
+                lobby_type: runecast_protocol::protocol::LobbyType::Custom,
+                lobby_code: Some("ABCD".into()),
+                players: vec![runecast_protocol::protocol::LobbyPlayerInfo {
+                    user_id: 1,
+                    username: "ann".into(),
+                    avatar_url: None,
+                    banner_url: None,
+                    accent_color: None,
+                    current_game_pool: None,
+                    active_game_id: None,
+                    spectate_game_id: None,
+                }],
+                games: Vec::new(),
+                max_players: 6,
+                sandbox_config: None,
+                host_id: Some(1),
+            }),
+            game: None,
+        });
+
+        let (rows, _) = render(&app);
+        assert!(rows[0].contains("Lobby ABCD 1/6"), "{}", rows[0]);
+        assert!(rows[1].contains("ann (host)"), "{}", rows[1]);
+        assert!(rows[7].starts_with("n start game"), "{}", rows[7]);
+    }
+}
@@ -0,0 +1,896 @@
+//! Async Rust client.
+//!
+//! [`RuneCastClient`] connects through a [`Connect`] implementation, runs the
+//! `Hello` → `Identify` → `Ready` handshake, heartbeats on the interval from
+//! `Hello`, and reconnects with `Identify { resume_seq }` when the transport
+//! drops. Requests resolve when a reply listed in
+//! [`EXCHANGES`](crate::protocol::EXCHANGES) or an `Error` arrives; every
+//! other server message is delivered through [`Events`].
+//!
+//! ```ignore
+//! let (client, mut events) = RuneCastClient::connect(connector, ClientConfig::default()).await?;
+//! let lobby = client.join_channel_lobby("123", None).await?;
+//! while let Some(event) = events.recv().await {
+//!     // render
+//! }
+//! ```
+//!
+//! The protocol has no request ids, so replies are matched to the oldest
+//! outstanding request whose exchange lists them. An error whose code no
+//! pending exchange lists, such as one answering a fire-and-forget `send`,
+//! is only an event.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::protocol::{
+    ClientMessage, Envelope, ErrorCode, Exchange, GameSnapshot, Grid, LobbySnapshot,
+    MaybeEnveloped, Position, ServerMessage,
+};
+use crate::transport::{CloseReason, Frame, Transport, TransportError};
+
+/// Opens a new transport to the server, for the first connection and each
+/// reconnect.
+#[async_trait::async_trait]
+pub trait Connect: Send + Sync + 'static {
+    /// The transport this connector produces.
+    type Transport: Transport + 'static;
+
+    /// Open a connection.
+    async fn connect(&self) -> Result<Self::Transport, TransportError>;
+}
+
+#[async_trait::async_trait]
+impl<F, Fut, T> Connect for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<T, TransportError>> + Send,
+    T: Transport + 'static,
+{
+    type Transport = T;
+
+    async fn connect(&self) -> Result<T, TransportError> {
+        self().await
+    }
+}
+
+/// Timeouts and reconnect policy for [`RuneCastClient`].
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// How long to wait for `Hello` and then `Ready`/`Resumed`.
+    pub handshake_timeout: Duration,
+    /// How long a request waits for its reply.
+    pub request_timeout: Duration,
+    /// Reconnect attempts after the connection drops; 0 disables resume.
+    pub reconnect_attempts: u32,
+    /// Delay before each reconnect attempt.
+    pub reconnect_delay: Duration,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            handshake_timeout: Duration::from_secs(10),
+            request_timeout: Duration::from_secs(10),
+            reconnect_attempts: 5,
+            reconnect_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Errors from [`RuneCastClient`].
+#[derive(Debug)]
+pub enum ClientError {
+    /// The server rejected the request.
+    Server { code: ErrorCode, message: String },
+    /// No reply within [`ClientConfig::request_timeout`] or the handshake
+    /// timeout.
+    Timeout,
+    /// The connection dropped before the reply arrived. The server may or
+    /// may not have handled the request.
+    Disconnected,
+    /// The client has shut down and will not reconnect.
+    Closed,
+    /// A message arrived that the handshake or request did not expect.
+    Unexpected(Box<ServerMessage>),
+    /// The transport failed.
+    Transport(TransportError),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Server { code, message } => write!(f, "server error {code}: {message}"),
+            Self::Timeout => write!(f, "timed out waiting for the server"),
+            Self::Disconnected => write!(f, "disconnected before the reply arrived"),
+            Self::Closed => write!(f, "client is closed"),
+            Self::Unexpected(msg) => write!(f, "unexpected {}", msg.message_type()),
+            Self::Transport(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Transport(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<TransportError> for ClientError {
+    fn from(e: TransportError) -> Self {
+        Self::Transport(e)
+    }
+}
+
+/// Something that happened on the connection.
+#[derive(Debug, Clone)]
+pub enum ClientEvent {
+    /// A server message, including replies that also resolved a request.
+    /// `Hello` and `HeartbeatAck` are not delivered.
+    Message(Box<ServerMessage>),
+    /// The connection dropped; the client is reconnecting.
+    Disconnected,
+    /// The client reconnected. `resumed` is false when the server could not
+    /// resume the session and a fresh `Ready` followed.
+    Reconnected { resumed: bool },
+    /// The client gave up or was closed; no more events follow.
+    Closed,
+}
+
+/// The stream of [`ClientEvent`]s from a [`RuneCastClient`].
+#[derive(Debug)]
+pub struct Events {
+    rx: mpsc::UnboundedReceiver<ClientEvent>,
+}
+
+impl Events {
+    /// Wait for the next event; `None` once the client has shut down.
+    pub async fn recv(&mut self) -> Option<ClientEvent> {
+        self.rx.recv().await
+    }
+
+    /// The next event if one is queued.
+    pub fn try_recv(&mut self) -> Option<ClientEvent> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl futures_util::Stream for Events {
+    type Item = ClientEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ClientEvent>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// The result of a successful `SubmitWord`.
+#[derive(Debug, Clone)]
+pub struct WordScore {
+    pub word: String,
+    pub score: i32,
+    pub total_score: i32,
+    pub gems_earned: i32,
+    pub total_gems: i32,
+    pub new_grid: Grid,
+}
+
+type Reply = oneshot::Sender<Result<ServerMessage, ClientError>>;
+
+enum Command {
+    Request { msg: ClientMessage, reply: Reply },
+    Send(ClientMessage),
+    Close,
+}
+
+/// A connected client. Cheap to clone; all clones share one connection.
+#[derive(Debug, Clone)]
+pub struct RuneCastClient {
+    commands: mpsc::UnboundedSender<Command>,
+    player_id: Arc<AtomicI64>,
+    request_timeout: Duration,
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request { msg, .. } => write!(f, "Request({})", msg.message_type()),
+            Self::Send(msg) => write!(f, "Send({})", msg.message_type()),
+            Self::Close => write!(f, "Close"),
+        }
+    }
+}
+
+impl RuneCastClient {
+    /// Connect, complete the handshake, and start the background task.
+    ///
+    /// The `Ready` message is the first event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if connecting fails or the handshake does not end in
+    /// `Ready`.
+    pub async fn connect<C: Connect>(
+        connector: C,
+        config: ClientConfig,
+    ) -> Result<(Self, Events), ClientError> {
+        let mut transport = connector.connect().await?;
+        let mut link = Link::default();
+        let handshake = link.handshake(&mut transport, &config).await?;
+        let ServerMessage::Ready { player_id, .. } = &handshake.reply else {
+            return Err(ClientError::Unexpected(Box::new(handshake.reply)));
+        };
+
+        let (commands, command_rx) = mpsc::unbounded_channel();
+        let (event_tx, rx) = mpsc::unbounded_channel();
+        let player_id = Arc::new(AtomicI64::new(*player_id));
+        let _ = event_tx.send(ClientEvent::Message(Box::new(handshake.reply)));
+
+        let driver = Driver {
+            connector,
+            config: config.clone(),
+            commands: command_rx,
+            events: event_tx,
+            pending: VecDeque::new(),
+            link,
+            heartbeat: handshake.heartbeat,
+            player_id: Arc::clone(&player_id),
+        };
+        tokio::spawn(driver.run(transport));
+
+        Ok((
+            Self {
+                commands,
+                player_id,
+                request_timeout: config.request_timeout,
+            },
+            Events { rx },
+        ))
+    }
+
+    /// The authenticated player's id, from the latest `Ready`.
+    #[must_use]
+    pub fn player_id(&self) -> i64 {
+        self.player_id.load(Ordering::Relaxed)
+    }
+
+    /// Send `msg` and wait for the reply its [`Exchange`] names.
+    ///
+    /// Messages with no replies (e.g. `SelectionUpdate`) resolve to
+    /// `Ok(None)` once sent.
+    ///
+    /// # Errors
+    ///
+    /// [`ClientError::Server`] when the server answers with `Error`, or
+    /// [`Timeout`](ClientError::Timeout), [`Disconnected`](ClientError::Disconnected)
+    /// or [`Closed`](ClientError::Closed).
+    pub async fn request(&self, msg: ClientMessage) -> Result<Option<ServerMessage>, ClientError> {
+        if !msg.exchange().expects_reply() {
+            self.send(msg)?;
+            return Ok(None);
+        }
+        let (reply, rx) = oneshot::channel();
+        self.commands
+            .send(Command::Request { msg, reply })
+            .map_err(|_| ClientError::Closed)?;
+        match tokio::time::timeout(self.request_timeout, rx).await {
+            Err(_) => Err(ClientError::Timeout),
+            Ok(Err(_)) => Err(ClientError::Closed),
+            Ok(Ok(result)) => result.map(Some),
+        }
+    }
+
+    /// Send `msg` without waiting for a reply.
+    ///
+    /// # Errors
+    ///
+    /// [`ClientError::Closed`] if the client has shut down.
+    pub fn send(&self, msg: ClientMessage) -> Result<(), ClientError> {
+        self.commands
+            .send(Command::Send(msg))
+            .map_err(|_| ClientError::Closed)
+    }
+
+    /// Join the lobby for a Discord channel.
+    ///
+    /// # Errors
+    ///
+    /// As for [`request`](Self::request).
+    pub async fn join_channel_lobby(
+        &self,
+        channel_id: impl Into<String>,
+        guild_id: Option<String>,
+    ) -> Result<LobbySnapshot, ClientError> {
+        let reply = self
+            .request_reply(ClientMessage::JoinChannelLobby {
+                channel_id: channel_id.into(),
+                guild_id,
+            })
+            .await?;
+        match reply {
+            ServerMessage::LobbyJoined { lobby, .. } => Ok(lobby),
+            other => Err(ClientError::Unexpected(Box::new(other))),
+        }
+    }
+
+    /// Submit a word along `positions`.
+    ///
+    /// # Errors
+    ///
+    /// As for [`request`](Self::request); invalid words fail with
+    /// [`ClientError::Server`].
+    pub async fn submit_word(
+        &self,
+        game_id: impl Into<String>,
+        word: impl Into<String>,
+        positions: Vec<Position>,
+    ) -> Result<WordScore, ClientError> {
+        let reply = self
+            .request_reply(ClientMessage::SubmitWord {
+                game_id: game_id.into(),
+                word: word.into(),
+                positions,
+            })
+            .await?;
+        match reply {
+            ServerMessage::WordScored {
+                word,
+                score,
+                total_score,
+                gems_earned,
+                total_gems,
+                new_grid,
+                ..
+            } => Ok(WordScore {
+                word,
+                score,
+                total_score,
+                gems_earned,
+                total_gems,
+                new_grid,
+            }),
+            other => Err(ClientError::Unexpected(Box::new(other))),
+        }
+    }
+
+    /// Start spectating a game; returns its current state.
+    ///
+    /// # Errors
+    ///
+    /// As for [`request`](Self::request).
+    pub async fn spectate_game(
+        &self,
+        game_id: impl Into<String>,
+    ) -> Result<GameSnapshot, ClientError> {
+        let reply = self
+            .request_reply(ClientMessage::SpectateGame {
+                game_id: game_id.into(),
+            })
+            .await?;
+        match reply {
+            ServerMessage::SpectatorJoined { game, .. } => Ok(game),
+            other => Err(ClientError::Unexpected(Box::new(other))),
+        }
+    }
+
+    /// Close the connection; the event stream ends with
+    /// [`ClientEvent::Closed`].
+    pub fn close(&self) {
+        let _ = self.commands.send(Command::Close);
+    }
+
+    async fn request_reply(&self, msg: ClientMessage) -> Result<ServerMessage, ClientError> {
+        self.request(msg).await?.ok_or(ClientError::Closed)
+    }
+}
+
+/// Sequence state that survives reconnects.
+#[derive(Debug, Default)]
+struct Link {
+    next_seq: u64,
+    last_server_seq: Option<u64>,
+}
+
+struct Handshake {
+    heartbeat: Duration,
+    /// `Ready` or `Resumed`.
+    reply: ServerMessage,
+}
+
+impl Link {
+    fn encode(&mut self, msg: &ClientMessage) -> Result<Frame, TransportError> {
+        self.next_seq += 1;
+        let envelope = match self.last_server_seq {
+            Some(ack) => Envelope::with_ack(self.next_seq, ack, msg),
+            None => Envelope::new(self.next_seq, msg),
+        };
+        let json = serde_json::to_string(&envelope)?;
+        Ok(Frame::Text(json))
+    }
+
+    /// Decode a frame; `None` for frames that are not server messages.
+    fn decode(&mut self, frame: Frame) -> Result<Option<ServerMessage>, TransportError> {
+        let text = match frame {
+            Frame::Text(text) => text,
+            Frame::Binary(bytes) => {
+                String::from_utf8(bytes).map_err(|_| TransportError::InvalidUtf8)?
+            }
+            Frame::Close(reason) => return Err(TransportError::Closed(reason)),
+        };
+        let Ok(parsed) = serde_json::from_str::<MaybeEnveloped<ServerMessage>>(&text) else {
+            return Ok(None);
+        };
+        Ok(Some(match parsed {
+            MaybeEnveloped::Enveloped(envelope) => {
+                self.last_server_seq = Some(envelope.seq);
+                envelope.payload
+            }
+            MaybeEnveloped::Raw(msg) => msg,
+        }))
+    }
+
+    async fn next<T: Transport>(
+        &mut self,
+        transport: &mut T,
+        deadline: Instant,
+    ) -> Result<ServerMessage, ClientError> {
+        loop {
+            let frame = tokio::time::timeout_at(deadline, transport.recv())
+                .await
+                .map_err(|_| ClientError::Timeout)??;
+            if let Some(msg) = self.decode(frame)? {
+                return Ok(msg);
+            }
+        }
+    }
+
+    /// Wait for `Hello`, identify (resuming if we have seen a `seq`), and
+    /// wait for `Ready` or `Resumed`.
+    async fn handshake<T: Transport>(
+        &mut self,
+        transport: &mut T,
+        config: &ClientConfig,
+    ) -> Result<Handshake, ClientError> {
+        // The `seq` seen on the previous connection, not this one's `Hello`.
+        let mut resume_seq = self.last_server_seq;
+        let deadline = Instant::now() + config.handshake_timeout;
+        let heartbeat = match self.next(transport, deadline).await? {
+            ServerMessage::Hello {
+                heartbeat_interval_ms,
+                ..
+            } => Duration::from_millis(u64::from(heartbeat_interval_ms)),
+            other => return Err(ClientError::Unexpected(Box::new(other))),
+        };
+
+        loop {
+            let identify = self.encode(&ClientMessage::Identify { resume_seq })?;
+            transport.send(identify).await?;
+            match self.next(transport, deadline).await? {
+                reply @ (ServerMessage::Ready { .. } | ServerMessage::Resumed { .. }) => {
+                    return Ok(Handshake { heartbeat, reply });
+                }
+                ServerMessage::InvalidSession { .. } if resume_seq.is_some() => {
+                    resume_seq = None;
+                    self.last_server_seq = None;
+                }
+                ServerMessage::Error { code, message, .. } => {
+                    return Err(ClientError::Server { code, message });
+                }
+                other => return Err(ClientError::Unexpected(Box::new(other))),
+            }
+        }
+    }
+}
+
+struct Pending {
+    exchange: &'static Exchange,
+    reply: Reply,
+}
+
+/// Why the connection loop stopped.
+enum Exit {
+    Dropped,
+    Closed,
+}
+
+struct Driver<C> {
+    connector: C,
+    config: ClientConfig,
+    commands: mpsc::UnboundedReceiver<Command>,
+    events: mpsc::UnboundedSender<ClientEvent>,
+    pending: VecDeque<Pending>,
+    link: Link,
+    heartbeat: Duration,
+    player_id: Arc<AtomicI64>,
+}
+
+impl<C: Connect> Driver<C> {
+    async fn run(mut self, mut transport: C::Transport) {
+        loop {
+            if let Exit::Closed = self.serve(&mut transport).await {
+                break;
+            }
+            for pending in self.pending.drain(..) {
+                let _ = pending.reply.send(Err(ClientError::Disconnected));
+            }
+            let _ = self.events.send(ClientEvent::Disconnected);
+            match self.reconnect().await {
+                Some(next) => transport = next,
+                None => break,
+            }
+        }
+        for pending in self.pending.drain(..) {
+            let _ = pending.reply.send(Err(ClientError::Closed));
+        }
+        let _ = self.events.send(ClientEvent::Closed);
+    }
+
+    /// Pump one connection until it drops or the client closes.
+    async fn serve(&mut self, transport: &mut C::Transport) -> Exit {
+        let mut ticker = tokio::time::interval_at(Instant::now() + self.heartbeat, self.heartbeat);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_heard = Instant::now();
+        loop {
+            tokio::select! {
+                command = self.commands.recv() => {
+                    let Some(command) = command else {
+                        let _ = transport.close(CloseReason::new(CloseReason::NORMAL, "client closed")).await;
+                        return Exit::Closed;
+                    };
+                    let msg = match command {
+                        Command::Request { msg, reply } => {
+                            self.pending.push_back(Pending { exchange: msg.exchange(), reply });
+                            msg
+                        }
+                        Command::Send(msg) => msg,
+                        Command::Close => {
+                            let _ = transport.close(CloseReason::new(CloseReason::NORMAL, "client closed")).await;
+                            return Exit::Closed;
+                        }
+                    };
+                    if self.send(transport, &msg).await.is_err() {
+                        return Exit::Dropped;
+                    }
+                }
+                _ = ticker.tick() => {
+                    // Two silent intervals: assume the connection is dead.
+                    if last_heard.elapsed() > self.heartbeat * 2 {
+                        return Exit::Dropped;
+                    }
+                    if self.send(transport, &ClientMessage::Heartbeat).await.is_err() {
+                        return Exit::Dropped;
+                    }
+                }
+                frame = transport.recv() => {
+                    last_heard = Instant::now();
+                    match frame.and_then(|f| self.link.decode(f)) {
+                        Ok(Some(msg)) => self.dispatch(msg),
+                        Ok(None) => {}
+                        Err(_) => return Exit::Dropped,
+                    }
+                }
+            }
+        }
+    }
+
+    async fn send(
+        &mut self,
+        transport: &mut C::Transport,
+        msg: &ClientMessage,
+    ) -> Result<(), TransportError> {
+        let frame = self.link.encode(msg)?;
+        transport.send(frame).await
+    }
+
+    /// Resolve a pending request if `msg` answers one, then emit it.
+    fn dispatch(&mut self, msg: ServerMessage) {
+        match &msg {
+            ServerMessage::Hello { .. } => return,
+            ServerMessage::Ready { player_id, .. } => {
+                self.player_id.store(*player_id, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+        self.pending.retain(|p| !p.reply.is_closed());
+        let resolved = match &msg {
+            ServerMessage::Error { code, message, .. } => {
+                self.take_pending(|e| e.errors.contains(code)).map(|p| {
+                    (
+                        p,
+                        Err(ClientError::Server {
+                            code: *code,
+                            message: message.clone(),
+                        }),
+                    )
+                })
+            }
+            other => {
+                let message_type = other.message_type();
+                self.take_pending(|e| e.replies.contains(&message_type))
+                    .map(|p| (p, Ok(msg.clone())))
+            }
+        };
+        if let Some((pending, result)) = resolved {
+            let _ = pending.reply.send(result);
+        }
+        // Acks still answer an explicit `heartbeat` request.
+        if !matches!(msg, ServerMessage::HeartbeatAck { .. }) {
+            let _ = self.events.send(ClientEvent::Message(Box::new(msg)));
+        }
+    }
+
+    fn take_pending(&mut self, accepts: impl Fn(&Exchange) -> bool) -> Option<Pending> {
+        let index = self.pending.iter().position(|p| accepts(p.exchange))?;
+        self.pending.remove(index)
+    }
+
+    /// Reconnect and resume, or `None` after the configured attempts.
+    async fn reconnect(&mut self) -> Option<C::Transport> {
+        for _ in 0..self.config.reconnect_attempts {
+            tokio::time::sleep(self.config.reconnect_delay).await;
+            let Ok(mut transport) = self.connector.connect().await else {
+                continue;
+            };
+            let Ok(handshake) = self.link.handshake(&mut transport, &self.config).await else {
+                continue;
+            };
+            self.heartbeat = handshake.heartbeat;
+            let resumed = match handshake.reply {
+                ServerMessage::Resumed { missed_events } => {
+                    for event in missed_events {
+                        self.dispatch(event);
+                    }
+                    true
+                }
+                ready => {
+                    self.dispatch(ready);
+                    false
+                }
+            };
+            let _ = self.events.send(ClientEvent::Reconnected { resumed });
+            return Some(transport);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use super::*;
+    use crate::protocol::LobbyPlayerInfo;
+    use crate::testing::lobby_snapshot;
+    use crate::transport::{loopback, LoopbackTransport, ServerConnection};
+
+    fn newcomer() -> ServerMessage {
+        ServerMessage::PlayerJoined {
+            player: LobbyPlayerInfo {
+                user_id: 7,
+                username: "bo".into(),
+                avatar_url: None,
+                banner_url: None,
+                accent_color: None,
+                current_game_pool: None,
+                active_game_id: None,
+                spectate_game_id: None,
+            },
+        }
+    }
+
+    /// A stub server. The first connection drops after `leave_lobby`; a
+    /// resumed session gets one missed event.
+    async fn stub_server(transport: LoopbackTransport) {
+        let mut conn = ServerConnection::new(transport, None);
+        conn.hello().await.unwrap();
+        while let Ok(Some(msg)) = conn.recv().await {
+            let reply = match msg {
+                ClientMessage::Identify {
+                    resume_seq: Some(_),
+                } => ServerMessage::Resumed {
+                    missed_events: vec![newcomer()],
+                },
+                ClientMessage::Identify { resume_seq: None } => ServerMessage::Ready {
+                    session_id: "s1".into(),
+                    player_id: 42,
+                    lobby: None,
+                    game: None,
+                },
+                // A stray error for an earlier `send` arrives first.
+                ClientMessage::JoinChannelLobby { channel_id, .. } if channel_id == "456" => {
+                    let stray = ServerMessage::Error {
+                        code: ErrorCode::NotYourTurn,
+                        message: "not your turn".into(),
+                        details: None,
+                    };
+                    conn.send(stray).await.unwrap();
+                    ServerMessage::LobbyJoined {
+                        lobby_id: "456".into(),
+                        lobby_code: None,
+                        lobby: lobby_snapshot("123"),
+                    }
+                }
+                ClientMessage::JoinChannelLobby { .. } => ServerMessage::LobbyJoined {
+                    lobby_id: "123".into(),
+                    lobby_code: None,
+                    lobby: lobby_snapshot("123"),
+                },
+                ClientMessage::SubmitWord { word, .. } if word == "QZX" => ServerMessage::Error {
+                    code: ErrorCode::WordNotInDictionary,
+                    message: "not a word".into(),
+                    details: None,
+                },
+                ClientMessage::SubmitWord { word, game_id, .. } => ServerMessage::WordScored {
+                    player_id: 42,
+                    game_id,
+                    word,
+                    score: 6,
+                    path: Vec::new(),
+                    total_score: 6,
+                    gems_earned: 0,
+                    total_gems: 0,
+                    new_grid: Vec::new(),
+                },
+                ClientMessage::LeaveLobby => return,
+                _ => continue,
+            };
+            conn.send(reply).await.unwrap();
+        }
+    }
+
+    fn connector() -> impl Connect<Transport = LoopbackTransport> {
+        || async {
+            let (client, server) = loopback();
+            tokio::spawn(stub_server(server));
+            Ok(client)
+        }
+    }
+
+    fn fast() -> ClientConfig {
+        ClientConfig {
+            reconnect_delay: Duration::from_millis(1),
+            ..ClientConfig::default()
+        }
+    }
+
+    async fn next_message(events: &mut Events) -> ServerMessage {
+        loop {
+            if let ClientEvent::Message(msg) = events.recv().await.unwrap() {
+                return *msg;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn typed_requests_resolve_to_replies_or_errors() {
+        let (client, mut events) = RuneCastClient::connect(connector(), fast()).await.unwrap();
+        assert_eq!(client.player_id(), 42);
+        assert_eq!(next_message(&mut events).await.message_type(), "ready");
+
+        let lobby = client.join_channel_lobby("123", None).await.unwrap();
+        assert_eq!(lobby.lobby_id, "123");
+
+        let scored = client.submit_word("g1", "RUNE", Vec::new()).await.unwrap();
+        assert_eq!(scored.score, 6);
+
+        let err = client
+            .submit_word("g1", "QZX", Vec::new())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            ClientError::Server {
+                code: ErrorCode::WordNotInDictionary,
+                ..
+            }
+        ));
+
+        // Replies are also events.
+        assert_eq!(
+            next_message(&mut events).await.message_type(),
+            "lobby_joined"
+        );
+
+        // An explicit heartbeat resolves to its ack, which is not an event.
+        let ack = client.request(ClientMessage::Heartbeat).await.unwrap();
+        assert!(matches!(ack, Some(ServerMessage::HeartbeatAck { .. })));
+
+        client.close();
+        while let Some(event) = events.recv().await {
+            match event {
+                ClientEvent::Closed => return,
+                ClientEvent::Message(msg) => assert_ne!(msg.message_type(), "heartbeat_ack"),
+                _ => {}
+            }
+        }
+        panic!("event stream ended without Closed");
+    }
+
+    #[tokio::test]
+    async fn unrelated_error_is_only_an_event() {
+        let (client, mut events) = RuneCastClient::connect(connector(), fast()).await.unwrap();
+        assert_eq!(next_message(&mut events).await.message_type(), "ready");
+
+        client.join_channel_lobby("456", None).await.unwrap();
+        assert!(matches!(
+            next_message(&mut events).await,
+            ServerMessage::Error {
+                code: ErrorCode::NotYourTurn,
+                ..
+            }
+        ));
+        assert_eq!(
+            next_message(&mut events).await.message_type(),
+            "lobby_joined"
+        );
+    }
+
+    #[tokio::test]
+    async fn reconnects_and_resumes() {
+        let (client, mut events) = RuneCastClient::connect(connector(), fast()).await.unwrap();
+        client.join_channel_lobby("123", None).await.unwrap();
+        // The stub drops the connection on leave_lobby.
+        client.send(ClientMessage::LeaveLobby).unwrap();
+
+        let mut seen = Vec::new();
+        while let Some(event) = events.recv().await {
+            let done = matches!(event, ClientEvent::Reconnected { .. });
+            seen.push(event);
+            if done {
+                break;
+            }
+        }
+        assert!(seen.iter().any(|e| matches!(e, ClientEvent::Disconnected)));
+        assert!(seen.iter().any(
+            |e| matches!(e, ClientEvent::Message(m) if matches!(&**m, ServerMessage::PlayerJoined { player } if player.user_id == 7))
+        ));
+        assert!(matches!(
+            seen.last(),
+            Some(ClientEvent::Reconnected { resumed: true })
+        ));
+
+        // Still usable after resuming.
+        client.join_channel_lobby("123", None).await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn heartbeats_follow_hello_interval() {
+        let heartbeats = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&heartbeats);
+        let connect = move || {
+            let counted = Arc::clone(&counted);
+            async move {
+                let (client, mut server) = loopback();
+                tokio::spawn(async move {
+                    let hello = r#"{"type":"hello","heartbeat_interval_ms":1000}"#;
+                    server.send(Frame::Text(hello.into())).await.unwrap();
+                    let _identify = server.recv().await.unwrap();
+                    let ready = r#"{"type":"ready","session_id":"s","player_id":"1"}"#;
+                    server.send(Frame::Text(ready.into())).await.unwrap();
+                    while let Ok(Frame::Text(text)) = server.recv().await {
+                        if text.contains(r#""type":"heartbeat""#) {
+                            counted.fetch_add(1, Ordering::SeqCst);
+                            let ack = r#"{"type":"heartbeat_ack","server_time":0}"#;
+                            server.send(Frame::Text(ack.into())).await.unwrap();
+                        }
+                    }
+                });
+                Ok::<_, TransportError>(client)
+            }
+        };
+        let (_client, _events) = RuneCastClient::connect(connect, fast()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(3500)).await;
+        assert_eq!(heartbeats.load(Ordering::SeqCst), 3);
+    }
+}
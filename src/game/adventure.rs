@@ -0,0 +1,489 @@
+//! Adventure Mode random events.
+//!
+//! [`apply_event`] is the single place that knows what each
+//! [`AdventureEventKind`] does to a board. It is pure — it never mutates the
+//! input grid and draws all randomness from the caller's [`GameRng`] — so the
+//! server's event scheduler and the sandbox "fire now" preview produce the
+//! exact same result for the same seed.
+//!
+//! Some effects outlive the event itself: Snake poison wears off after a
+//! couple of rounds and UFO-abducted cells come back on a later round. The
+//! outcome lists those as [`LingeringEffect`]s; feed it to an
+//! [`EffectTracker`] and call [`EffectTracker::advance_round`] on every round
+//! change to expire them.
+//!
+//! # Adding a kind
+//!
+//! Add the variant to [`AdventureEventKind`], a match arm in [`apply_event`]
+//! and a label in [`default_label`]. Older clients decode the new kind as
+//! `Unknown` and still receive the authoritative `new_grid`.
+
+use super::board;
+use super::rng::GameRng;
+use crate::protocol::{AdventureEventKind, Grid, Position, ServerMessage};
+
+/// Bomb blast radius around the centre cell (1 = 3×3).
+pub const BOMB_RADIUS: usize = 1;
+
+/// Maximum number of cells a Snake poisons.
+pub const SNAKE_LENGTH: usize = 4;
+
+/// How many rounds Snake poison lasts.
+pub const POISON_ROUNDS: u8 = 2;
+
+/// How many rounds UFO-abducted cells stay unselectable.
+pub const ABDUCTION_ROUNDS: u8 = 1;
+
+/// An effect that persists on a cell after the event that caused it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LingeringKind {
+    /// Cell is `is_poisoned`; the flag clears on expiry.
+    Poison,
+    /// Cell is `is_abducted`; the tile rerolls on expiry.
+    Abduction,
+}
+
+/// A lingering effect produced by an event, with its duration in rounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LingeringEffect {
+    pub position: Position,
+    pub kind: LingeringKind,
+    /// Number of round changes the effect survives.
+    pub rounds: u8,
+}
+
+/// Result of applying one adventure event to a grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdventureEventOutcome {
+    pub kind: AdventureEventKind,
+    /// Cells the client should animate, in row-major order.
+    pub affected_positions: Vec<Position>,
+    /// Full post-effect grid.
+    pub new_grid: Grid,
+    /// Effects to register with an [`EffectTracker`].
+    pub lingering: Vec<LingeringEffect>,
+}
+
+impl AdventureEventOutcome {
+    /// True when the event had nothing to act on (e.g. UFO on a board with
+    /// no multipliers, or an `Unknown` kind).
+    #[must_use]
+    pub fn is_noop(&self) -> bool {
+        self.affected_positions.is_empty()
+    }
+
+    /// Build the `AdventureEvent` broadcast for this outcome.
+    #[must_use]
+    pub fn to_message(
+        &self,
+        game_id: impl Into<String>,
+        label: impl Into<String>,
+    ) -> ServerMessage {
+        ServerMessage::AdventureEvent {
+            game_id: game_id.into(),
+            kind: self.kind,
+            affected_positions: self.affected_positions.clone(),
+            new_grid: self.new_grid.clone(),
+            label: label.into(),
+        }
+    }
+}
+
+/// Default English toast label for an event kind.
+#[must_use]
+pub fn default_label(kind: AdventureEventKind) -> &'static str {
+    match kind {
+        AdventureEventKind::Bomb => "💣 Bomb!",
+        AdventureEventKind::Snake => "🐍 Snake!",
+        AdventureEventKind::Ufo => "🛸 UFO!",
+        AdventureEventKind::Unknown => "Something happened!",
+    }
+}
+
+/// Apply an adventure event to `grid`, returning the new board and metadata.
+///
+/// - **Bomb**: picks a random non-hole centre and rerolls every non-hole cell
+///   within [`BOMB_RADIUS`]. Rerolled cells lose poison and abduction.
+/// - **Snake**: random-walks up to [`SNAKE_LENGTH`] adjacent healthy cells
+///   and poisons them for [`POISON_ROUNDS`] rounds.
+/// - **Ufo**: strips every multiplier on the board and abducts those cells for
+///   [`ABDUCTION_ROUNDS`] rounds.
+/// - **Unknown**: no-op.
+#[must_use]
+pub fn apply_event(
+    kind: AdventureEventKind,
+    grid: &Grid,
+    rng: &mut GameRng,
+) -> AdventureEventOutcome {
+    let mut new_grid = grid.clone();
+    let (mut affected_positions, lingering) = match kind {
+        AdventureEventKind::Bomb => (bomb(&mut new_grid, rng), Vec::new()),
+        AdventureEventKind::Snake => {
+            let cells = snake(&mut new_grid, rng);
+            let lingering = linger(&cells, LingeringKind::Poison, POISON_ROUNDS);
+            (cells, lingering)
+        }
+        AdventureEventKind::Ufo => {
+            let cells = ufo(&mut new_grid);
+            let lingering = linger(&cells, LingeringKind::Abduction, ABDUCTION_ROUNDS);
+            (cells, lingering)
+        }
+        AdventureEventKind::Unknown => (Vec::new(), Vec::new()),
+    };
+    affected_positions.sort_by_key(|p| (p.row, p.col));
+
+    AdventureEventOutcome {
+        kind,
+        affected_positions,
+        new_grid,
+        lingering,
+    }
+}
+
+fn linger(cells: &[Position], kind: LingeringKind, rounds: u8) -> Vec<LingeringEffect> {
+    cells
+        .iter()
+        .map(|&position| LingeringEffect {
+            position,
+            kind,
+            rounds,
+        })
+        .collect()
+}
+
+fn bomb(grid: &mut Grid, rng: &mut GameRng) -> Vec<Position> {
+    let candidates: Vec<Position> = board::positions(grid)
+        .into_iter()
+        .filter(|&p| board::cell(grid, p).is_some_and(|c| !c.is_hole))
+        .collect();
+    let Some(&center) = rng.choose(&candidates) else {
+        return Vec::new();
+    };
+
+    let blast: Vec<Position> = candidates
+        .into_iter()
+        .filter(|p| p.row.abs_diff(center.row) <= BOMB_RADIUS)
+        .filter(|p| p.col.abs_diff(center.col) <= BOMB_RADIUS)
+        .collect();
+    for &pos in &blast {
+        board::reroll(grid, pos, rng);
+    }
+    blast
+}
+
+fn snake(grid: &mut Grid, rng: &mut GameRng) -> Vec<Position> {
+    let healthy = |grid: &Grid, p: Position| {
+        board::cell(grid, p).is_some_and(|c| !c.is_hole && !c.is_abducted && !c.is_poisoned)
+    };
+
+    let starts: Vec<Position> = board::positions(grid)
+        .into_iter()
+        .filter(|&p| healthy(grid, p))
+        .collect();
+    let Some(&start) = rng.choose(&starts) else {
+        return Vec::new();
+    };
+
+    let mut body = vec![start];
+    while body.len() < SNAKE_LENGTH {
+        let head = body[body.len() - 1];
+        let next: Vec<Position> = board::neighbors(grid, head)
+            .into_iter()
+            .filter(|&p| healthy(grid, p) && !body.contains(&p))
+            .collect();
+        match rng.choose(&next) {
+            Some(&p) => body.push(p),
+            None => break,
+        }
+    }
+
+    for &pos in &body {
+        if let Some(cell) = board::cell_mut(grid, pos) {
+            cell.is_poisoned = true;
+        }
+    }
+    body
+}
+
+fn ufo(grid: &mut Grid) -> Vec<Position> {
+    let mut taken = Vec::new();
+    for pos in board::positions(grid) {
+        if let Some(cell) = board::cell_mut(grid, pos) {
+            if !cell.is_hole && cell.multiplier.is_some() {
+                cell.multiplier = None;
+                cell.is_abducted = true;
+                taken.push(pos);
+            }
+        }
+    }
+    taken
+}
+
+/// A lingering effect pinned to the round it expires on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackedEffect {
+    pub position: Position,
+    pub kind: LingeringKind,
+    /// The effect is removed when the game reaches this round.
+    pub expires_at_round: u8,
+}
+
+/// Tracks poison and abduction expiry across rounds.
+#[derive(Debug, Clone, Default)]
+pub struct EffectTracker {
+    effects: Vec<TrackedEffect>,
+}
+
+impl EffectTracker {
+    /// Create an empty tracker.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the lingering effects of `outcome`, which fired during
+    /// `current_round`.
+    ///
+    /// Any affected cell forgets its previous effects first — a Bomb that
+    /// rerolls a poisoned cell also cures it.
+    pub fn record(&mut self, outcome: &AdventureEventOutcome, current_round: u8) {
+        self.forget(&outcome.affected_positions);
+        self.effects
+            .extend(outcome.lingering.iter().map(|e| TrackedEffect {
+                position: e.position,
+                kind: e.kind,
+                expires_at_round: current_round.saturating_add(e.rounds),
+            }));
+    }
+
+    /// Drop effects on cells that were rerolled by something else (word
+    /// consumption, shuffle, swap).
+    pub fn forget(&mut self, positions: &[Position]) {
+        self.effects.retain(|e| !positions.contains(&e.position));
+    }
+
+    /// Drop every effect of `kind`.
+    pub fn clear_kind(&mut self, kind: LingeringKind) {
+        self.effects.retain(|e| e.kind != kind);
+    }
+
+    /// Follow tiles that moved from `.0` to `.1` (a shuffle), so their
+    /// effects still expire on the right cell.
+    pub fn relocate(&mut self, moves: &[(Position, Position)]) {
+        for effect in &mut self.effects {
+            if let Some(&(_, to)) = moves.iter().find(|(from, _)| *from == effect.position) {
+                effect.position = to;
+            }
+        }
+    }
+
+    /// Expire effects due at `new_round`, updating `grid` in place.
+    ///
+    /// Poison simply clears; abducted cells reroll to a fresh tile. Returns
+    /// the positions that changed, in row-major order.
+    pub fn advance_round(
+        &mut self,
+        new_round: u8,
+        grid: &mut Grid,
+        rng: &mut GameRng,
+    ) -> Vec<Position> {
+        let (due, keep): (Vec<_>, Vec<_>) = self
+            .effects
+            .drain(..)
+            .partition(|e| e.expires_at_round <= new_round);
+        self.effects = keep;
+
+        let mut changed = Vec::with_capacity(due.len());
+        for effect in due {
+            match effect.kind {
+                LingeringKind::Poison => {
+                    if let Some(cell) = board::cell_mut(grid, effect.position) {
+                        cell.is_poisoned = false;
+                    }
+                }
+                LingeringKind::Abduction => {
+                    board::reroll(grid, effect.position, rng);
+                }
+            }
+            changed.push(effect.position);
+        }
+        changed.sort_by_key(|p| (p.row, p.col));
+        changed.dedup();
+        changed
+    }
+
+    /// Effects still active.
+    #[must_use]
+    pub fn active(&self) -> &[TrackedEffect] {
+        &self.effects
+    }
+
+    /// True when nothing is pending.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Multiplier;
+
+    fn grid(seed: u64) -> Grid {
+        board::generate_grid(5, &mut GameRng::new(seed))
+    }
+
+    #[test]
+    fn apply_event_is_pure_and_seeded() {
+        let before = grid(1);
+        let a = apply_event(AdventureEventKind::Bomb, &before, &mut GameRng::new(9));
+        let b = apply_event(AdventureEventKind::Bomb, &before, &mut GameRng::new(9));
+        assert_eq!(a, b);
+        assert_eq!(before, grid(1), "input grid must not be mutated");
+    }
+
+    #[test]
+    fn bomb_hits_at_most_a_three_by_three_block() {
+        let before = grid(2);
+        let out = apply_event(AdventureEventKind::Bomb, &before, &mut GameRng::new(4));
+        assert!((4..=9).contains(&out.affected_positions.len()));
+        let rows: Vec<usize> = out.affected_positions.iter().map(|p| p.row).collect();
+        let cols: Vec<usize> = out.affected_positions.iter().map(|p| p.col).collect();
+        assert!(rows.iter().max().unwrap() - rows.iter().min().unwrap() <= 2);
+        assert!(cols.iter().max().unwrap() - cols.iter().min().unwrap() <= 2);
+        assert!(out.lingering.is_empty());
+    }
+
+    #[test]
+    fn bomb_skips_holes_and_cures_poison() {
+        let mut before = grid(3);
+        for row in &mut before {
+            for cell in row {
+                cell.is_poisoned = true;
+            }
+        }
+        before[0][0].is_hole = true;
+        let out = apply_event(AdventureEventKind::Bomb, &before, &mut GameRng::new(5));
+        for p in &out.affected_positions {
+            let cell = board::cell(&out.new_grid, *p).unwrap();
+            assert!(!cell.is_hole);
+            assert!(!cell.is_poisoned);
+        }
+        assert!(!out
+            .affected_positions
+            .contains(&Position { row: 0, col: 0 }));
+    }
+
+    #[test]
+    fn snake_poisons_an_adjacent_chain() {
+        let out = apply_event(AdventureEventKind::Snake, &grid(4), &mut GameRng::new(6));
+        assert!(!out.affected_positions.is_empty());
+        assert!(out.affected_positions.len() <= SNAKE_LENGTH);
+        for p in &out.affected_positions {
+            assert!(board::cell(&out.new_grid, *p).unwrap().is_poisoned);
+        }
+        assert_eq!(out.lingering.len(), out.affected_positions.len());
+        assert!(out
+            .lingering
+            .iter()
+            .all(|e| e.kind == LingeringKind::Poison && e.rounds == POISON_ROUNDS));
+    }
+
+    #[test]
+    fn ufo_steals_every_multiplier() {
+        let mut before = grid(5);
+        before[1][2].multiplier = Some(Multiplier::DoubleLetter);
+        before[3][4].multiplier = Some(Multiplier::DoubleWord);
+        let out = apply_event(AdventureEventKind::Ufo, &before, &mut GameRng::new(0));
+        assert_eq!(
+            out.affected_positions,
+            vec![Position { row: 1, col: 2 }, Position { row: 3, col: 4 }]
+        );
+        assert!(out
+            .new_grid
+            .iter()
+            .flatten()
+            .all(|c| c.multiplier.is_none()));
+        assert!(out.new_grid[1][2].is_abducted);
+    }
+
+    #[test]
+    fn ufo_without_multipliers_is_noop() {
+        let before = grid(6);
+        let out = apply_event(AdventureEventKind::Ufo, &before, &mut GameRng::new(0));
+        assert!(out.is_noop());
+        assert_eq!(out.new_grid, before);
+    }
+
+    #[test]
+    fn unknown_kind_is_noop() {
+        let before = grid(7);
+        let out = apply_event(AdventureEventKind::Unknown, &before, &mut GameRng::new(0));
+        assert!(out.is_noop());
+        assert_eq!(out.new_grid, before);
+    }
+
+    #[test]
+    fn poison_expires_after_configured_rounds() {
+        let mut rng = GameRng::new(8);
+        let out = apply_event(AdventureEventKind::Snake, &grid(8), &mut rng);
+        let mut board = out.new_grid.clone();
+        let mut tracker = EffectTracker::new();
+        tracker.record(&out, 1);
+
+        assert!(tracker.advance_round(2, &mut board, &mut rng).is_empty());
+        assert!(board.iter().flatten().any(|c| c.is_poisoned));
+
+        let cleared = tracker.advance_round(3, &mut board, &mut rng);
+        assert_eq!(cleared, out.affected_positions);
+        assert!(board.iter().flatten().all(|c| !c.is_poisoned));
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn abducted_cells_reroll_on_expiry() {
+        let mut rng = GameRng::new(9);
+        let mut before = grid(9);
+        before[2][2].multiplier = Some(Multiplier::TripleLetter);
+        let out = apply_event(AdventureEventKind::Ufo, &before, &mut rng);
+        let mut board = out.new_grid.clone();
+        let mut tracker = EffectTracker::new();
+        tracker.record(&out, 4);
+
+        let changed = tracker.advance_round(5, &mut board, &mut rng);
+        assert_eq!(changed, vec![Position { row: 2, col: 2 }]);
+        assert!(!board[2][2].is_abducted);
+    }
+
+    #[test]
+    fn rerolled_cells_are_forgotten() {
+        let mut rng = GameRng::new(10);
+        let out = apply_event(AdventureEventKind::Snake, &grid(10), &mut rng);
+        let mut tracker = EffectTracker::new();
+        tracker.record(&out, 1);
+        tracker.forget(&out.affected_positions);
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn outcome_builds_adventure_event_message() {
+        let out = apply_event(AdventureEventKind::Bomb, &grid(11), &mut GameRng::new(1));
+        let msg = out.to_message("g1", default_label(out.kind));
+        match msg {
+            ServerMessage::AdventureEvent {
+                game_id,
+                kind,
+                affected_positions,
+                label,
+                ..
+            } => {
+                assert_eq!(game_id, "g1");
+                assert_eq!(kind, AdventureEventKind::Bomb);
+                assert_eq!(affected_positions, out.affected_positions);
+                assert_eq!(label, "💣 Bomb!");
+            }
+            other => panic!("expected AdventureEvent, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Board generation helpers: letter distribution, tile values and rerolls.
+//!
+//! Shared by the adventure event engine and the headless game engine so a
+//! rerolled tile looks the same no matter which code path produced it.
+
+use super::rng::GameRng;
+use crate::protocol::{Grid, GridCell, Multiplier, Position};
+
+/// Letter weights (Scrabble tile counts) and point values, `A..=Z`.
+const LETTERS: [(char, u32, u8); 26] = [
+    ('A', 9, 1),
+    ('B', 2, 3),
+    ('C', 2, 3),
+    ('D', 4, 2),
+    ('E', 12, 1),
+    ('F', 2, 4),
+    ('G', 3, 2),
+    ('H', 2, 4),
+    ('I', 9, 1),
+    ('J', 1, 8),
+    ('K', 1, 5),
+    ('L', 4, 1),
+    ('M', 2, 3),
+    ('N', 6, 1),
+    ('O', 8, 1),
+    ('P', 2, 3),
+    ('Q', 1, 10),
+    ('R', 6, 1),
+    ('S', 4, 1),
+    ('T', 6, 1),
+    ('U', 4, 1),
+    ('V', 2, 4),
+    ('W', 2, 4),
+    ('X', 1, 8),
+    ('Y', 2, 4),
+    ('Z', 1, 10),
+];
+
+/// Probability that a freshly rolled tile carries a gem.
+pub const GEM_CHANCE: f64 = 0.1;
+
+/// Point value of a letter (case-insensitive). Non-letters are worth 0.
+#[must_use]
+pub fn letter_value(letter: char) -> u8 {
+    let upper = letter.to_ascii_uppercase();
+    LETTERS
+        .iter()
+        .find(|(c, _, _)| *c == upper)
+        .map_or(0, |(_, _, v)| *v)
+}
+
+/// Draw a letter from the weighted distribution.
+pub fn random_letter(rng: &mut GameRng) -> char {
+    let total: u32 = LETTERS.iter().map(|(_, w, _)| w).sum();
+    let mut pick = u32::try_from(rng.below(total as usize)).unwrap_or(0);
+    for (c, weight, _) in LETTERS {
+        if pick < weight {
+            return c;
+        }
+        pick -= weight;
+    }
+    'E'
+}
+
+/// Roll a brand-new plain tile: random letter, maybe a gem, no multiplier
+/// and none of the adventure flags.
+pub fn random_cell(rng: &mut GameRng) -> GridCell {
+    let letter = random_letter(rng);
+    GridCell {
+        letter,
+        value: letter_value(letter),
+        multiplier: None,
+        has_gem: rng.chance(GEM_CHANCE),
+        is_hole: false,
+        is_poisoned: false,
+        is_abducted: false,
+    }
+}
+
+/// Generate a full `size`×`size` grid of fresh tiles.
+pub fn generate_grid(size: usize, rng: &mut GameRng) -> Grid {
+    (0..size)
+        .map(|_| (0..size).map(|_| random_cell(rng)).collect())
+        .collect()
+}
+
+/// Multipliers placed on a freshly generated board.
+pub const BOARD_MULTIPLIERS: [Multiplier; 3] = [
+    Multiplier::DoubleLetter,
+    Multiplier::TripleLetter,
+    Multiplier::DoubleWord,
+];
+
+/// Place each of [`BOARD_MULTIPLIERS`] on a distinct random playable cell
+/// that doesn't already carry one.
+pub fn scatter_multipliers(grid: &mut Grid, rng: &mut GameRng) {
+    let mut free: Vec<Position> = positions(grid)
+        .into_iter()
+        .filter(|&p| cell(grid, p).is_some_and(|c| !c.is_hole && c.multiplier.is_none()))
+        .collect();
+    rng.shuffle(&mut free);
+    for (pos, multiplier) in free.into_iter().zip(BOARD_MULTIPLIERS) {
+        if let Some(c) = cell_mut(grid, pos) {
+            c.multiplier = Some(multiplier);
+        }
+    }
+}
+
+/// Replace the tile at `pos` with a fresh roll. Holes are left untouched.
+///
+/// Returns `false` if the position is out of bounds or a hole.
+pub fn reroll(grid: &mut Grid, pos: Position, rng: &mut GameRng) -> bool {
+    match cell_mut(grid, pos) {
+        Some(cell) if !cell.is_hole => {
+            *cell = random_cell(rng);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Borrow the cell at `pos`, if it exists.
+#[must_use]
+pub fn cell(grid: &Grid, pos: Position) -> Option<&GridCell> {
+    grid.get(pos.row).and_then(|row| row.get(pos.col))
+}
+
+/// Mutably borrow the cell at `pos`, if it exists.
+pub fn cell_mut(grid: &mut Grid, pos: Position) -> Option<&mut GridCell> {
+    grid.get_mut(pos.row).and_then(|row| row.get_mut(pos.col))
+}
+
+/// All positions in row-major order.
+#[must_use]
+pub fn positions(grid: &Grid) -> Vec<Position> {
+    grid.iter()
+        .enumerate()
+        .flat_map(|(row, cells)| (0..cells.len()).map(move |col| Position { row, col }))
+        .collect()
+}
+
+/// The up-to-eight in-bounds neighbours of `pos` (diagonals included).
+#[must_use]
+pub fn neighbors(grid: &Grid, pos: Position) -> Vec<Position> {
+    let mut out = Vec::with_capacity(8);
+    for dr in -1isize..=1 {
+        for dc in -1isize..=1 {
+            if dr == 0 && dc == 0 {
+                continue;
+            }
+            let (Some(row), Some(col)) = (
+                pos.row.checked_add_signed(dr),
+                pos.col.checked_add_signed(dc),
+            ) else {
+                continue;
+            };
+            let p = Position { row, col };
+            if cell(grid, p).is_some() {
+                out.push(p);
+            }
+        }
+    }
+    out
+}
+
+/// Whether two positions touch (including diagonally).
+#[must_use]
+pub fn is_adjacent(a: Position, b: Position) -> bool {
+    a != b && a.row.abs_diff(b.row) <= 1 && a.col.abs_diff(b.col) <= 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letter_values_match_table() {
+        assert_eq!(letter_value('E'), 1);
+        assert_eq!(letter_value('q'), 10);
+        assert_eq!(letter_value('?'), 0);
+    }
+
+    #[test]
+    fn generate_grid_is_seeded() {
+        let a = generate_grid(5, &mut GameRng::new(11));
+        let b = generate_grid(5, &mut GameRng::new(11));
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 5);
+        assert!(a.iter().all(|row| row.len() == 5));
+    }
+
+    #[test]
+    fn reroll_skips_holes() {
+        let mut rng = GameRng::new(1);
+        let mut grid = generate_grid(3, &mut rng);
+        grid[1][1].is_hole = true;
+        assert!(!reroll(&mut grid, Position { row: 1, col: 1 }, &mut rng));
+        assert!(grid[1][1].is_hole);
+        assert!(!reroll(&mut grid, Position { row: 9, col: 9 }, &mut rng));
+    }
+
+    #[test]
+    fn scatter_multipliers_places_one_of_each() {
+        let mut rng = GameRng::new(2);
+        let mut grid = generate_grid(5, &mut rng);
+        scatter_multipliers(&mut grid, &mut rng);
+        let placed: Vec<Multiplier> = grid.iter().flatten().filter_map(|c| c.multiplier).collect();
+        assert_eq!(placed.len(), BOARD_MULTIPLIERS.len());
+    }
+
+    #[test]
+    fn corner_has_three_neighbors() {
+        let grid = generate_grid(5, &mut GameRng::new(0));
+        assert_eq!(neighbors(&grid, Position { row: 0, col: 0 }).len(), 3);
+        assert_eq!(neighbors(&grid, Position { row: 2, col: 2 }).len(), 8);
+    }
+}
@@ -0,0 +1,113 @@
+//! Injectable time source.
+//!
+//! State machines with deadlines (timer votes, rematch countdowns) read the
+//! time through [`Clock`] instead of calling `Utc::now()` directly, so tests
+//! can drive them with a [`ManualClock`] and never sleep.
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::protocol::{ErrorCode, ServerMessage};
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    /// The current instant.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Wall-clock time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    /// Create a clock frozen at `start`.
+    #[must_use]
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(start),
+        }
+    }
+
+    /// Move the clock forward by `by`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().expect("ManualClock mutex poisoned");
+        *now += by;
+    }
+
+    /// Jump to an absolute instant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal mutex is poisoned.
+    pub fn set(&self, to: DateTime<Utc>) {
+        *self.now.lock().expect("ManualClock mutex poisoned") = to;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().expect("ManualClock mutex poisoned")
+    }
+}
+
+/// A state machine whose deadlines are applied by `tick`, and by every
+/// action before it runs.
+pub(crate) trait Ticking: Sized {
+    /// Apply due expiries, returning their messages after any `pending`.
+    fn tick(&mut self) -> Vec<ServerMessage>;
+
+    /// Expiry messages waiting for the next `tick`.
+    fn pending(&mut self) -> &mut Vec<ServerMessage>;
+
+    /// Run `action` after applying due expiries. On failure the expiry
+    /// messages are kept for the next `tick` rather than lost.
+    fn after_tick(
+        &mut self,
+        action: impl FnOnce(&mut Self) -> Result<Vec<ServerMessage>, ErrorCode>,
+    ) -> Result<Vec<ServerMessage>, ErrorCode> {
+        let mut out = Ticking::tick(self);
+        match action(self) {
+            Ok(msgs) => {
+                out.extend(msgs);
+                Ok(out)
+            }
+            Err(code) => {
+                *self.pending() = out;
+                Err(code)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_moves_only_when_told() {
+        let start = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let clock = ManualClock::new(start);
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::seconds(5));
+        assert_eq!(clock.now(), start + Duration::seconds(5));
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
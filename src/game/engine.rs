@@ -0,0 +1,1063 @@
+//! Headless, authoritative `RuneCast` game engine.
+//!
+//! [`GameEngine`] is a pure reducer: feed it `(player_id, ClientMessage)`
+//! pairs and it returns the [`Outbound`] server messages a real server would
+//! send, without sockets, timers or a database. Integration tests can run a
+//! whole game in-process and assert on the exact broadcast sequence.
+//!
+//! Everything random (board, rerolls, shuffles, ambient events) is drawn from
+//! a [`GameRng`] seeded at construction, so the same seed and the same
+//! inputs always produce the same messages.
+//!
+//! ```rust
+//! use runecast_protocol::game::engine::{AcceptAllWords, GameEngine};
+//! use runecast_protocol::{ClientMessage, GameConfig, PlayerInfo};
+//!
+//! let player = |id: i64, name: &str| PlayerInfo {
+//!     user_id: id,
+//!     username: name.to_string(),
+//!     avatar_url: None,
+//!     score: 0,
+//!     gems: 0,
+//!     team: None,
+//!     is_connected: true,
+//! };
+//! let mut engine = GameEngine::new(
+//!     "game-1",
+//!     GameConfig::default(),
+//!     vec![player(1, "ann"), player(2, "bob")],
+//!     42,
+//!     Box::new(AcceptAllWords),
+//! );
+//! let started = engine.start();
+//! assert_eq!(started.len(), 2); // one personalised GameStarted per player
+//!
+//! let out = engine.handle(1, &ClientMessage::PassTurn { game_id: "game-1".into() });
+//! assert_eq!(out.len(), 2); // TurnPassed + TurnChanged
+//! ```
+
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+use super::adventure::{self, EffectTracker, LingeringKind};
+use super::board;
+use super::rng::GameRng;
+use crate::protocol::{
+    AdventureEventKind, ClientMessage, ErrorCode, GameConfig, GamePlayerInfo, GameSnapshot,
+    GameState, Grid, Multiplier, PlayerInfo, Position, ScoreInfo, ServerMessage, TimerVoteState,
+};
+
+/// Rounds played when the config doesn't say otherwise.
+pub const DEFAULT_ROUNDS: u8 = 5;
+
+/// Shortest word that can be submitted.
+pub const MIN_WORD_LENGTH: usize = 3;
+
+/// Gem cap per player.
+pub const MAX_GEMS: i32 = 10;
+
+/// Gem cost of `ShuffleBoard`.
+pub const SHUFFLE_COST: i32 = 1;
+
+/// Gem cost of `SwapTile`.
+pub const SWAP_COST: i32 = 3;
+
+/// Word list used to accept or reject submissions.
+pub trait Dictionary: Send + Sync {
+    /// Whether `word` (uppercase) is playable.
+    fn contains(&self, word: &str) -> bool;
+}
+
+/// Dictionary that accepts every word. Handy for tests that only care
+/// about turn flow.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AcceptAllWords;
+
+impl Dictionary for AcceptAllWords {
+    fn contains(&self, _word: &str) -> bool {
+        true
+    }
+}
+
+impl<S: BuildHasher + Send + Sync> Dictionary for HashSet<String, S> {
+    fn contains(&self, word: &str) -> bool {
+        HashSet::contains(self, word)
+    }
+}
+
+/// Who a message produced by the engine should be delivered to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Audience {
+    /// Every player in the game.
+    All,
+    /// A single player (errors, personalised messages).
+    Player(i64),
+    /// Everyone but the given player (live selection relay).
+    AllExcept(i64),
+}
+
+/// A server message plus its intended recipients.
+#[derive(Debug, Clone)]
+pub struct Outbound {
+    pub audience: Audience,
+    pub message: ServerMessage,
+}
+
+impl Outbound {
+    fn all(message: ServerMessage) -> Self {
+        Self {
+            audience: Audience::All,
+            message,
+        }
+    }
+
+    fn to(player_id: i64, message: ServerMessage) -> Self {
+        Self {
+            audience: Audience::Player(player_id),
+            message,
+        }
+    }
+
+    fn error(player_id: i64, code: ErrorCode) -> Vec<Self> {
+        vec![Self::to(player_id, ServerMessage::error(code))]
+    }
+
+    /// Whether `player_id` should receive this message.
+    #[must_use]
+    pub fn is_for(&self, player_id: i64) -> bool {
+        match self.audience {
+            Audience::All => true,
+            Audience::Player(id) => id == player_id,
+            Audience::AllExcept(id) => id != player_id,
+        }
+    }
+}
+
+/// In-process authoritative game.
+pub struct GameEngine {
+    game_id: String,
+    config: GameConfig,
+    rng: GameRng,
+    dictionary: Box<dyn Dictionary>,
+    state: GameState,
+    grid: Grid,
+    players: Vec<PlayerInfo>,
+    current: usize,
+    round: u8,
+    max_rounds: u8,
+    used_words: Vec<String>,
+    effects: EffectTracker,
+}
+
+impl std::fmt::Debug for GameEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GameEngine")
+            .field("game_id", &self.game_id)
+            .field("state", &self.state)
+            .field("round", &self.round)
+            .field("current", &self.current)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GameEngine {
+    /// Create an idle game. Turn order follows `players`; their starting
+    /// scores and gems are kept as given (usually zero).
+    #[must_use]
+    pub fn new(
+        game_id: impl Into<String>,
+        config: GameConfig,
+        players: Vec<PlayerInfo>,
+        seed: u64,
+        dictionary: Box<dyn Dictionary>,
+    ) -> Self {
+        let max_rounds = Self::rounds_for(&config);
+        Self {
+            game_id: game_id.into(),
+            config,
+            rng: GameRng::new(seed),
+            dictionary,
+            state: GameState::Idle,
+            grid: Grid::new(),
+            players,
+            current: 0,
+            round: 0,
+            max_rounds,
+            used_words: Vec::new(),
+            effects: EffectTracker::new(),
+        }
+    }
+
+    /// `num_rounds` is only honoured for custom (sandbox) games.
+    fn rounds_for(config: &GameConfig) -> u8 {
+        match (&config.custom, config.num_rounds) {
+            (Some(_), Some(n)) if n > 0 => n,
+            _ => DEFAULT_ROUNDS,
+        }
+    }
+
+    /// The game's id.
+    #[must_use]
+    pub fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    /// Current lifecycle state.
+    #[must_use]
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// The current board.
+    #[must_use]
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// User id of the player whose turn it is, if the game is running.
+    #[must_use]
+    pub fn current_player(&self) -> Option<i64> {
+        (self.state == GameState::InProgress).then(|| self.players[self.current].user_id)
+    }
+
+    /// Full state as the protocol's snapshot type.
+    #[must_use]
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            game_id: self.game_id.clone(),
+            state: self.state,
+            grid: self.grid.clone(),
+            players: self.players.clone(),
+            spectators: Vec::new(),
+            current_turn: self.players.get(self.current).map_or(0, |p| p.user_id),
+            round: self.round,
+            max_rounds: self.max_rounds,
+            used_words: self.used_words.clone(),
+            timer_vote_state: TimerVoteState::default(),
+            your_player: None,
+            timer_expiration_time: None,
+            custom: self.config.custom.clone(),
+            time_remaining_ms: None,
+        }
+    }
+
+    /// Snapshot personalised for `player_id` (`your_player` filled in).
+    #[must_use]
+    pub fn snapshot_for(&self, player_id: i64) -> GameSnapshot {
+        GameSnapshot {
+            your_player: self.player(player_id).cloned(),
+            ..self.snapshot()
+        }
+    }
+
+    /// Deal the board and start round 1. Emits one `GameStarted` per player
+    /// (each with its own `your_turn_order`). Does nothing unless idle.
+    pub fn start(&mut self) -> Vec<Outbound> {
+        if self.state != GameState::Idle || self.players.is_empty() {
+            return Vec::new();
+        }
+        self.grid = self.deal();
+        self.state = GameState::InProgress;
+        self.round = 1;
+        self.current = 0;
+
+        let players: Vec<GamePlayerInfo> = self
+            .players
+            .iter()
+            .enumerate()
+            .map(|(i, p)| GamePlayerInfo {
+                user_id: p.user_id,
+                username: p.username.clone(),
+                avatar_url: p.avatar_url.clone(),
+                turn_order: u8::try_from(i).unwrap_or(u8::MAX),
+                score: p.score,
+                gems: p.gems,
+                is_connected: p.is_connected,
+                team: p.team,
+            })
+            .collect();
+
+        players
+            .iter()
+            .map(|me| {
+                Outbound::to(
+                    me.user_id,
+                    ServerMessage::GameStarted {
+                        game_id: self.game_id.clone(),
+                        grid: self.grid.clone(),
+                        players: players.clone(),
+                        your_turn_order: me.turn_order,
+                        current_turn: self.players[0].user_id,
+                        round: self.round,
+                        max_rounds: self.max_rounds,
+                        turn_time_limit: None,
+                        custom: self.config.custom.clone(),
+                        time_remaining_ms: None,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Apply one client message from `player_id` and return the resulting
+    /// messages. Rule violations come back as a single `Error` addressed to
+    /// the sender; the game state is left untouched.
+    pub fn handle(&mut self, player_id: i64, msg: &ClientMessage) -> Vec<Outbound> {
+        if self.player(player_id).is_none() {
+            return Outbound::error(player_id, ErrorCode::NotInGame);
+        }
+
+        if let ClientMessage::StartGame { config } = msg {
+            if self.state != GameState::Idle {
+                return Outbound::error(player_id, ErrorCode::GameInProgress);
+            }
+            if let Some(config) = config {
+                self.max_rounds = Self::rounds_for(config);
+                self.config = config.clone();
+            }
+            return self.start();
+        }
+
+        match game_id_of(msg) {
+            Some(game_id) if game_id != self.game_id => {
+                return Outbound::error(player_id, ErrorCode::GameNotFound);
+            }
+            Some(_) => {}
+            // Fired against "the current custom game", so no id on the wire.
+            None if matches!(msg, ClientMessage::TriggerAdventureEvent { .. }) => {}
+            None => return Outbound::error(player_id, ErrorCode::InvalidRequest),
+        }
+        if self.state != GameState::InProgress {
+            return Outbound::error(player_id, ErrorCode::GameNotActive);
+        }
+        if msg.requires_turn() && self.current_player() != Some(player_id) {
+            return Outbound::error(player_id, ErrorCode::NotYourTurn);
+        }
+
+        match msg {
+            ClientMessage::SubmitWord {
+                word, positions, ..
+            } => self.submit_word(player_id, word, positions),
+            ClientMessage::PassTurn { .. } => {
+                let mut out = vec![Outbound::all(ServerMessage::TurnPassed {
+                    player_id,
+                    game_id: self.game_id.clone(),
+                })];
+                out.extend(self.advance_turn());
+                out
+            }
+            ClientMessage::ShuffleBoard { .. } => self.shuffle(player_id),
+            ClientMessage::SwapTile {
+                row,
+                col,
+                new_letter,
+                ..
+            } => self.swap(
+                player_id,
+                Position {
+                    row: *row,
+                    col: *col,
+                },
+                *new_letter,
+            ),
+            // Relayed UI state, which only the player on turn has; anyone
+            // else's is dropped.
+            ClientMessage::EnterSwapMode { .. }
+            | ClientMessage::ExitSwapMode { .. }
+            | ClientMessage::SelectionUpdate { .. }
+                if self.current_player() != Some(player_id) =>
+            {
+                Vec::new()
+            }
+            ClientMessage::EnterSwapMode { .. } => {
+                vec![Outbound::all(ServerMessage::SwapModeEntered {
+                    player_id,
+                    game_id: self.game_id.clone(),
+                })]
+            }
+            ClientMessage::ExitSwapMode { .. } => {
+                vec![Outbound::all(ServerMessage::SwapModeExited {
+                    player_id,
+                    game_id: self.game_id.clone(),
+                })]
+            }
+            ClientMessage::SelectionUpdate { positions, .. } => {
+                vec![Outbound {
+                    audience: Audience::AllExcept(player_id),
+                    message: ServerMessage::SelectionUpdate {
+                        player_id,
+                        game_id: self.game_id.clone(),
+                        positions: positions.clone(),
+                    },
+                }]
+            }
+            ClientMessage::TriggerAdventureEvent { kind } => self.trigger_event(player_id, *kind),
+            _ => Outbound::error(player_id, ErrorCode::InvalidRequest),
+        }
+    }
+
+    fn player(&self, player_id: i64) -> Option<&PlayerInfo> {
+        self.players.iter().find(|p| p.user_id == player_id)
+    }
+
+    fn player_mut(&mut self, player_id: i64) -> Option<&mut PlayerInfo> {
+        self.players.iter_mut().find(|p| p.user_id == player_id)
+    }
+
+    fn deal(&mut self) -> Grid {
+        let mut grid = board::generate_grid(usize::from(self.config.grid_size), &mut self.rng);
+        board::scatter_multipliers(&mut grid, &mut self.rng);
+        grid
+    }
+
+    fn validate_path(&self, positions: &[Position]) -> Result<String, ErrorCode> {
+        if positions.len() < MIN_WORD_LENGTH {
+            return Err(ErrorCode::PathTooShort);
+        }
+        let mut letters = String::with_capacity(positions.len());
+        for (i, &pos) in positions.iter().enumerate() {
+            let cell = board::cell(&self.grid, pos).ok_or(ErrorCode::InvalidPath)?;
+            if cell.is_hole || cell.is_abducted || positions[..i].contains(&pos) {
+                return Err(ErrorCode::InvalidPath);
+            }
+            if i > 0 && !board::is_adjacent(positions[i - 1], pos) {
+                return Err(ErrorCode::InvalidPath);
+            }
+            letters.push(cell.letter.to_ascii_uppercase());
+        }
+        Ok(letters)
+    }
+
+    fn score_path(&self, positions: &[Position]) -> (i32, i32) {
+        let mut total = 0;
+        let mut word_multiplier = 1;
+        let mut gems = 0;
+        for &pos in positions {
+            let Some(cell) = board::cell(&self.grid, pos) else {
+                continue;
+            };
+            let mut value = i32::from(cell.value);
+            match cell.multiplier {
+                Some(Multiplier::DoubleLetter) => value *= 2,
+                Some(Multiplier::TripleLetter) => value *= 3,
+                Some(Multiplier::DoubleWord) => word_multiplier *= 2,
+                None => {}
+            }
+            if cell.is_poisoned {
+                value = -value;
+            }
+            total += value;
+            if cell.has_gem {
+                gems += 1;
+            }
+        }
+        (total * word_multiplier, gems)
+    }
+
+    fn submit_word(&mut self, player_id: i64, word: &str, positions: &[Position]) -> Vec<Outbound> {
+        let letters = match self.validate_path(positions) {
+            Ok(letters) => letters,
+            Err(code) => return Outbound::error(player_id, code),
+        };
+        let word = word.to_ascii_uppercase();
+        if word != letters {
+            return Outbound::error(player_id, ErrorCode::InvalidPath);
+        }
+        if self.used_words.contains(&word) {
+            return Outbound::error(player_id, ErrorCode::WordAlreadyUsed);
+        }
+        if !self.dictionary.contains(&word) {
+            return Outbound::error(player_id, ErrorCode::WordNotInDictionary);
+        }
+
+        let (score, found_gems) = self.score_path(positions);
+        for &pos in positions {
+            board::reroll(&mut self.grid, pos, &mut self.rng);
+        }
+        self.effects.forget(positions);
+        self.used_words.push(word.clone());
+
+        let Some(player) = self.player_mut(player_id) else {
+            return Outbound::error(player_id, ErrorCode::NotInGame);
+        };
+        let gems_before = player.gems;
+        player.score += score;
+        player.gems = (player.gems + found_gems).min(MAX_GEMS);
+        let (total_score, total_gems) = (player.score, player.gems);
+
+        let mut out = vec![Outbound::all(ServerMessage::WordScored {
+            player_id,
+            game_id: self.game_id.clone(),
+            word,
+            score,
+            path: positions.to_vec(),
+            total_score,
+            gems_earned: total_gems - gems_before,
+            total_gems,
+            new_grid: self.grid.clone(),
+        })];
+        out.extend(self.advance_turn());
+        out
+    }
+
+    fn spend_gems(&mut self, player_id: i64, cost: i32) -> Result<i32, ErrorCode> {
+        let player = self.player_mut(player_id).ok_or(ErrorCode::NotInGame)?;
+        if player.gems < cost {
+            return Err(ErrorCode::InsufficientGems);
+        }
+        player.gems -= cost;
+        Ok(player.gems)
+    }
+
+    fn shuffle(&mut self, player_id: i64) -> Vec<Outbound> {
+        let total_gems = match self.spend_gems(player_id, SHUFFLE_COST) {
+            Ok(gems) => gems,
+            Err(code) => return Outbound::error(player_id, code),
+        };
+
+        // Holes keep their place; every other tile moves with its
+        // properties intact, and a pending abduction follows its tile.
+        // Shuffling cures poison.
+        let slots: Vec<Position> = board::positions(&self.grid)
+            .into_iter()
+            .filter(|&p| board::cell(&self.grid, p).is_some_and(|c| !c.is_hole))
+            .collect();
+        let mut tiles: Vec<_> = slots
+            .iter()
+            .filter_map(|&p| board::cell(&self.grid, p).cloned().map(|c| (p, c)))
+            .collect();
+        self.rng.shuffle(&mut tiles);
+        let mut moves = Vec::with_capacity(tiles.len());
+        for (&pos, (from, tile)) in slots.iter().zip(tiles) {
+            if let Some(cell) = board::cell_mut(&mut self.grid, pos) {
+                *cell = tile;
+                cell.is_poisoned = false;
+            }
+            moves.push((from, pos));
+        }
+        self.effects.clear_kind(LingeringKind::Poison);
+        self.effects.relocate(&moves);
+
+        vec![Outbound::all(ServerMessage::BoardShuffled {
+            player_id,
+            game_id: self.game_id.clone(),
+            new_grid: self.grid.clone(),
+            gems_spent: SHUFFLE_COST,
+            total_gems,
+        })]
+    }
+
+    fn swap(&mut self, player_id: i64, pos: Position, new_letter: char) -> Vec<Outbound> {
+        let new_letter = new_letter.to_ascii_uppercase();
+        if !new_letter.is_ascii_uppercase() {
+            return Outbound::error(player_id, ErrorCode::InvalidRequest);
+        }
+        let old_letter = match board::cell(&self.grid, pos) {
+            Some(cell) if !cell.is_hole && !cell.is_abducted => cell.letter,
+            _ => return Outbound::error(player_id, ErrorCode::InvalidRequest),
+        };
+        let total_gems = match self.spend_gems(player_id, SWAP_COST) {
+            Ok(gems) => gems,
+            Err(code) => return Outbound::error(player_id, code),
+        };
+        if let Some(cell) = board::cell_mut(&mut self.grid, pos) {
+            cell.letter = new_letter;
+            cell.value = board::letter_value(new_letter);
+        }
+
+        vec![Outbound::all(ServerMessage::TileSwapped {
+            player_id,
+            game_id: self.game_id.clone(),
+            row: pos.row,
+            col: pos.col,
+            old_letter,
+            new_letter,
+            gems_spent: SWAP_COST,
+            total_gems,
+        })]
+    }
+
+    fn trigger_event(&mut self, player_id: i64, kind: AdventureEventKind) -> Vec<Outbound> {
+        let enabled = self.config.custom.is_some()
+            && self
+                .config
+                .events
+                .as_ref()
+                .is_some_and(|e| e.enabled_kinds.contains(&kind));
+        if !enabled {
+            return Outbound::error(player_id, ErrorCode::InvalidAction);
+        }
+        self.fire_event(kind).into_iter().collect()
+    }
+
+    fn fire_event(&mut self, kind: AdventureEventKind) -> Option<Outbound> {
+        let outcome = adventure::apply_event(kind, &self.grid, &mut self.rng);
+        if outcome.is_noop() {
+            return None;
+        }
+        self.effects.record(&outcome, self.round);
+        self.grid.clone_from(&outcome.new_grid);
+        Some(Outbound::all(outcome.to_message(
+            self.game_id.clone(),
+            adventure::default_label(kind),
+        )))
+    }
+
+    /// Roll for an ambient event from `config.events` (custom games only).
+    fn ambient_event(&mut self) -> Option<Outbound> {
+        self.config.custom.as_ref()?;
+        let events = self.config.events.clone()?;
+        if events.enabled_kinds.is_empty() || !self.rng.chance(events.frequency) {
+            return None;
+        }
+        let kind = *self.rng.choose(&events.enabled_kinds)?;
+        self.fire_event(kind)
+    }
+
+    fn advance_turn(&mut self) -> Vec<Outbound> {
+        let mut out = Vec::new();
+        let next = (self.current + 1) % self.players.len();
+
+        if next == 0 && self.round >= self.max_rounds {
+            // The last mover stays current in the final snapshot.
+            out.push(self.finish());
+            return out;
+        }
+        self.current = next;
+
+        if self.current == 0 {
+            self.round += 1;
+            let board_changed = if self.config.regenerate_board_each_round {
+                self.grid = self.deal();
+                self.effects = EffectTracker::new();
+                true
+            } else {
+                !self
+                    .effects
+                    .advance_round(self.round, &mut self.grid, &mut self.rng)
+                    .is_empty()
+            };
+            out.push(Outbound::all(ServerMessage::RoundChanged {
+                game_id: self.game_id.clone(),
+                round: self.round,
+                max_rounds: self.max_rounds,
+                new_grid: board_changed.then(|| self.grid.clone()),
+            }));
+        }
+
+        out.extend(self.ambient_event());
+        out.push(Outbound::all(ServerMessage::TurnChanged {
+            player_id: self.players[self.current].user_id,
+            game_id: self.game_id.clone(),
+            round: self.round,
+            time_remaining: None,
+        }));
+        out
+    }
+
+    fn finish(&mut self) -> Outbound {
+        self.state = GameState::Finished;
+        let mut ranked: Vec<&PlayerInfo> = self.players.iter().collect();
+        // Stable sort keeps turn order as the tie-breaker.
+        ranked.sort_by_key(|p| std::cmp::Reverse(p.score));
+        let is_draw = ranked.len() > 1 && ranked[0].score == ranked[1].score;
+
+        Outbound::all(ServerMessage::GameOver {
+            game_id: self.game_id.clone(),
+            final_scores: ranked
+                .iter()
+                .map(|p| ScoreInfo {
+                    user_id: p.user_id,
+                    username: p.username.clone(),
+                    score: p.score,
+                })
+                .collect(),
+            winner_id: ranked[0].user_id,
+            is_draw,
+        })
+    }
+}
+
+/// The `game_id` carried by an in-game client message.
+fn game_id_of(msg: &ClientMessage) -> Option<&str> {
+    match msg {
+        ClientMessage::SubmitWord { game_id, .. }
+        | ClientMessage::PassTurn { game_id }
+        | ClientMessage::ShuffleBoard { game_id }
+        | ClientMessage::EnterSwapMode { game_id }
+        | ClientMessage::ExitSwapMode { game_id }
+        | ClientMessage::SwapTile { game_id, .. }
+        | ClientMessage::SelectionUpdate { game_id, .. } => Some(game_id),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{CustomMeta, EventConfig};
+    use crate::testing::player_info;
+
+    fn engine(config: GameConfig, seed: u64) -> GameEngine {
+        let mut engine = GameEngine::new(
+            "g1",
+            config,
+            vec![player_info(1, "ann"), player_info(2, "bob")],
+            seed,
+            Box::new(AcceptAllWords),
+        );
+        engine.start();
+        engine
+    }
+
+    fn pass() -> ClientMessage {
+        ClientMessage::PassTurn {
+            game_id: "g1".to_string(),
+        }
+    }
+
+    /// First three-letter path along the top row.
+    fn top_row_word(engine: &GameEngine) -> (String, Vec<Position>) {
+        let positions: Vec<Position> = (0..3).map(|col| Position { row: 0, col }).collect();
+        let word = positions
+            .iter()
+            .map(|&p| board::cell(engine.grid(), p).unwrap().letter)
+            .collect();
+        (word, positions)
+    }
+
+    #[test]
+    fn start_emits_personalised_game_started() {
+        let mut engine = GameEngine::new(
+            "g1",
+            GameConfig::default(),
+            vec![player_info(1, "ann"), player_info(2, "bob")],
+            7,
+            Box::new(AcceptAllWords),
+        );
+        let out = engine.start();
+        assert_eq!(out.len(), 2);
+        for (i, o) in out.iter().enumerate() {
+            match &o.message {
+                ServerMessage::GameStarted {
+                    your_turn_order,
+                    current_turn,
+                    round,
+                    max_rounds,
+                    grid,
+                    ..
+                } => {
+                    assert_eq!(usize::from(*your_turn_order), i);
+                    assert_eq!(*current_turn, 1);
+                    assert_eq!(*round, 1);
+                    assert_eq!(*max_rounds, DEFAULT_ROUNDS);
+                    assert_eq!(grid.len(), 5);
+                }
+                other => panic!("expected GameStarted, got {other:?}"),
+            }
+        }
+        assert!(engine.start().is_empty(), "second start is a no-op");
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let a = engine(GameConfig::default(), 3);
+        let b = engine(GameConfig::default(), 3);
+        assert_eq!(a.grid(), b.grid());
+        let c = engine(GameConfig::default(), 4);
+        assert_ne!(a.grid(), c.grid());
+    }
+
+    #[test]
+    fn out_of_turn_is_rejected() {
+        let mut engine = engine(GameConfig::default(), 1);
+        let out = engine.handle(2, &pass());
+        assert!(matches!(
+            out[0].message,
+            ServerMessage::Error {
+                code: ErrorCode::NotYourTurn,
+                ..
+            }
+        ));
+        assert_eq!(out[0].audience, Audience::Player(2));
+    }
+
+    #[test]
+    fn submit_word_scores_and_advances() {
+        let mut engine = engine(GameConfig::default(), 5);
+        let (word, positions) = top_row_word(&engine);
+        let out = engine.handle(
+            1,
+            &ClientMessage::SubmitWord {
+                game_id: "g1".into(),
+                word: word.clone(),
+                positions: positions.clone(),
+            },
+        );
+        assert!(matches!(
+            &out[0].message,
+            ServerMessage::WordScored { player_id: 1, path, .. } if *path == positions
+        ));
+        assert!(matches!(
+            out.last().unwrap().message,
+            ServerMessage::TurnChanged { player_id: 2, .. }
+        ));
+        assert_eq!(
+            engine.snapshot().used_words,
+            vec![word.to_ascii_uppercase()]
+        );
+    }
+
+    #[test]
+    fn word_rules_are_enforced() {
+        let mut engine = engine(GameConfig::default(), 6);
+        let submit = |word: &str, positions: Vec<Position>| ClientMessage::SubmitWord {
+            game_id: "g1".into(),
+            word: word.into(),
+            positions,
+        };
+        let code = |out: Vec<Outbound>| match out[0].message {
+            ServerMessage::Error { code, .. } => code,
+            ref other => panic!("expected error, got {other:?}"),
+        };
+
+        let short = vec![Position { row: 0, col: 0 }];
+        assert_eq!(
+            code(engine.handle(1, &submit("A", short))),
+            ErrorCode::PathTooShort
+        );
+
+        let gap = vec![
+            Position { row: 0, col: 0 },
+            Position { row: 0, col: 2 },
+            Position { row: 0, col: 3 },
+        ];
+        assert_eq!(
+            code(engine.handle(1, &submit("XYZ", gap))),
+            ErrorCode::InvalidPath
+        );
+
+        let (word, positions) = top_row_word(&engine);
+        let mut dict = HashSet::new();
+        dict.insert("NOPE".to_string());
+        engine.dictionary = Box::new(dict);
+        assert_eq!(
+            code(engine.handle(1, &submit(&word, positions))),
+            ErrorCode::WordNotInDictionary
+        );
+    }
+
+    #[test]
+    fn full_game_ends_with_game_over() {
+        let mut engine = engine(
+            GameConfig {
+                custom: Some(CustomMeta {}),
+                num_rounds: Some(2),
+                ..GameConfig::default()
+            },
+            9,
+        );
+        let mut all = Vec::new();
+        while let Some(id) = engine.current_player() {
+            all.extend(engine.handle(id, &pass()));
+        }
+        assert_eq!(engine.state(), GameState::Finished);
+        let rounds = all
+            .iter()
+            .filter(|o| matches!(o.message, ServerMessage::RoundChanged { .. }))
+            .count();
+        assert_eq!(rounds, 1);
+        match &all.last().unwrap().message {
+            ServerMessage::GameOver {
+                is_draw, winner_id, ..
+            } => {
+                assert!(*is_draw, "nobody scored");
+                assert_eq!(*winner_id, 1);
+            }
+            other => panic!("expected GameOver, got {other:?}"),
+        }
+        assert_eq!(
+            engine.snapshot().current_turn,
+            2,
+            "last mover stays current"
+        );
+        assert!(matches!(
+            engine.handle(1, &pass())[0].message,
+            ServerMessage::Error {
+                code: ErrorCode::GameNotActive,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn shuffle_costs_a_gem() {
+        let mut engine = engine(GameConfig::default(), 2);
+        let msg = ClientMessage::ShuffleBoard {
+            game_id: "g1".into(),
+        };
+        assert!(matches!(
+            engine.handle(1, &msg)[0].message,
+            ServerMessage::Error {
+                code: ErrorCode::InsufficientGems,
+                ..
+            }
+        ));
+        engine.player_mut(1).unwrap().gems = 2;
+        assert!(matches!(
+            engine.handle(1, &msg)[0].message,
+            ServerMessage::BoardShuffled { total_gems: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn shuffled_abductions_still_expire() {
+        let config = GameConfig {
+            custom: Some(CustomMeta {}),
+            events: Some(EventConfig {
+                enabled_kinds: vec![AdventureEventKind::Ufo],
+                frequency: 0.0,
+            }),
+            ..GameConfig::default()
+        };
+        let mut engine = engine(config, 11);
+        engine.grid[1][1].multiplier = Some(Multiplier::DoubleWord);
+        let ufo = ClientMessage::TriggerAdventureEvent {
+            kind: AdventureEventKind::Ufo,
+        };
+        assert!(matches!(
+            engine.handle(1, &ufo)[0].message,
+            ServerMessage::AdventureEvent { .. }
+        ));
+        let abducted = |e: &GameEngine| e.grid.iter().flatten().filter(|c| c.is_abducted).count();
+        let taken = abducted(&engine);
+        assert!(taken > 0);
+
+        engine.player_mut(1).unwrap().gems = 1;
+        let shuffle = ClientMessage::ShuffleBoard {
+            game_id: "g1".into(),
+        };
+        assert!(matches!(
+            engine.handle(1, &shuffle)[0].message,
+            ServerMessage::BoardShuffled { .. }
+        ));
+        assert_eq!(abducted(&engine), taken);
+
+        engine.handle(1, &pass());
+        engine.handle(2, &pass());
+        assert_eq!(engine.round, 2);
+        assert_eq!(abducted(&engine), 0);
+    }
+
+    #[test]
+    fn swap_tile_replaces_letter() {
+        let mut engine = engine(GameConfig::default(), 2);
+        engine.player_mut(1).unwrap().gems = 3;
+        let out = engine.handle(
+            1,
+            &ClientMessage::SwapTile {
+                game_id: "g1".into(),
+                row: 1,
+                col: 1,
+                new_letter: 'q',
+            },
+        );
+        assert!(matches!(
+            out[0].message,
+            ServerMessage::TileSwapped {
+                new_letter: 'Q',
+                total_gems: 0,
+                ..
+            }
+        ));
+        assert_eq!(engine.grid()[1][1].letter, 'Q');
+        assert_eq!(engine.grid()[1][1].value, 10);
+    }
+
+    #[test]
+    fn selection_is_relayed_to_others_only() {
+        let mut engine = engine(GameConfig::default(), 2);
+        let out = engine.handle(
+            1,
+            &ClientMessage::SelectionUpdate {
+                game_id: "g1".into(),
+                positions: vec![Position { row: 0, col: 0 }],
+            },
+        );
+        assert_eq!(out[0].audience, Audience::AllExcept(1));
+        assert!(!out[0].is_for(1));
+        assert!(out[0].is_for(2));
+    }
+
+    #[test]
+    fn swap_mode_out_of_turn_is_dropped() {
+        let mut engine = engine(GameConfig::default(), 2);
+        let enter = ClientMessage::EnterSwapMode {
+            game_id: "g1".into(),
+        };
+        assert!(engine.handle(2, &enter).is_empty());
+        assert!(matches!(
+            engine.handle(1, &enter)[0].message,
+            ServerMessage::SwapModeEntered { player_id: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn snapshot_mirrors_state() {
+        let engine = engine(GameConfig::default(), 2);
+        let snap = engine.snapshot_for(2);
+        assert_eq!(snap.state, GameState::InProgress);
+        assert_eq!(snap.current_turn, 1);
+        assert_eq!(snap.round, 1);
+        assert_eq!(snap.your_player.unwrap().user_id, 2);
+        assert_eq!(&snap.grid, engine.grid());
+    }
+
+    #[test]
+    fn trigger_event_requires_enabled_kind() {
+        let config = GameConfig {
+            custom: Some(CustomMeta {}),
+            events: Some(EventConfig {
+                enabled_kinds: vec![AdventureEventKind::Bomb],
+                frequency: 0.0,
+            }),
+            ..GameConfig::default()
+        };
+        let mut engine = engine(config, 4);
+        let bomb = engine.handle(
+            2,
+            &ClientMessage::TriggerAdventureEvent {
+                kind: AdventureEventKind::Bomb,
+            },
+        );
+        assert!(matches!(
+            bomb[0].message,
+            ServerMessage::AdventureEvent { .. }
+        ));
+        let snake = engine.handle(
+            2,
+            &ClientMessage::TriggerAdventureEvent {
+                kind: AdventureEventKind::Snake,
+            },
+        );
+        assert!(matches!(
+            snake[0].message,
+            ServerMessage::Error {
+                code: ErrorCode::InvalidAction,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn strangers_are_rejected() {
+        let mut engine = engine(GameConfig::default(), 1);
+        assert!(matches!(
+            engine.handle(99, &pass())[0].message,
+            ServerMessage::Error {
+                code: ErrorCode::NotInGame,
+                ..
+            }
+        ));
+    }
+}
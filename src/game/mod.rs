@@ -0,0 +1,40 @@
+//! Shared game logic built on the protocol types.
+//!
+//! Everything here is deterministic: randomness comes from a caller-supplied
+//! [`GameRng`], so the server, sandbox previews and tests all agree on the
+//! outcome for a given seed.
+//!
+//! ```text
+//! ┌─────────────────────────────────────────────────────────────────────┐
+//! │                             Game Layer                               │
+//! ├─────────────────────────────────────────────────────────────────────┤
+//! │  rng.rs        - Seeded, version-stable random generator            │
+//! │  board.rs      - Letter distribution, tile rolls, grid geometry     │
+//! │  adventure.rs  - Adventure event effects (Bomb, Snake, UFO)         │
+//! │  engine.rs     - Headless authoritative game reducer                │
+//! │  clock.rs      - Injectable time source for deadline-driven logic   │
+//! │  timer_vote.rs - Timer vote state machine                           │
+//! │  rematch.rs    - Rematch countdown coordinator                      │
+//! │  record.rs     - Versioned JSONL game recording format              │
+//! │  replay.rs     - Rebuild snapshots from a recording                 │
+//! └─────────────────────────────────────────────────────────────────────┘
+//! ```
+
+pub mod adventure;
+pub mod board;
+pub mod clock;
+pub mod engine;
+pub mod record;
+pub mod rematch;
+pub mod replay;
+pub mod rng;
+pub mod timer_vote;
+
+pub use adventure::{apply_event, AdventureEventOutcome, EffectTracker};
+pub use clock::{Clock, ManualClock, SystemClock};
+pub use engine::{Audience, GameEngine, Outbound};
+pub use record::{GameRecord, RecordEntry, RecordError, RecordHeader, RecordReader, RecordWriter};
+pub use rematch::{RematchConfig, RematchCountdown};
+pub use replay::Replayer;
+pub use rng::GameRng;
+pub use timer_vote::{TimerVote, TimerVoteConfig};
@@ -0,0 +1,431 @@
+//! Game recording format.
+//!
+//! A record is JSON Lines: one [`RecordHeader`] line followed by one
+//! [`RecordEntry`] per stored message.
+//!
+//! ```text
+//! {"version":1,"game_id":"g1","recorded_at":"…","seed":"42","config":{…},"players":[…]}
+//! {"at":"…","message":{"type":"game_started",…}}
+//! {"at":"…","message":{"type":"word_scored",…}}
+//! ```
+//!
+//! Only messages passing [`ServerMessage::should_store_for_replay`] are
+//! written, so heartbeats, live selections and other transient traffic
+//! never reach disk. Records are read back with [`RecordReader`] (streaming)
+//! or [`GameRecord::read_from`] (all at once) and replayed with
+//! [`Replayer`](super::replay::Replayer).
+
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::clock::Clock;
+use super::engine::{Audience, Outbound};
+use crate::protocol::{GameConfig, PlayerInfo, ServerMessage};
+
+/// Current record format version. Bump when the header or entry layout
+/// changes incompatibly; readers reject anything newer.
+pub const RECORD_VERSION: u32 = 1;
+
+/// First line of a record: what is needed to identify and re-run the game.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordHeader {
+    /// Format version ([`RECORD_VERSION`] when written by this crate).
+    pub version: u32,
+    pub game_id: String,
+    /// When recording began.
+    pub recorded_at: DateTime<Utc>,
+    /// Seed the game's [`GameRng`](super::GameRng) was created with
+    /// (string to preserve JS number precision).
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub seed: u64,
+    pub config: GameConfig,
+    /// Players at game start, in turn order.
+    pub players: Vec<PlayerInfo>,
+}
+
+impl RecordHeader {
+    /// Header for a game starting now, at the current format version.
+    pub fn new(
+        game_id: impl Into<String>,
+        config: GameConfig,
+        players: Vec<PlayerInfo>,
+        seed: u64,
+        recorded_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            version: RECORD_VERSION,
+            game_id: game_id.into(),
+            recorded_at,
+            seed,
+            config,
+            players,
+        }
+    }
+}
+
+/// One stored message and when it was sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordEntry {
+    pub at: DateTime<Utc>,
+    pub message: ServerMessage,
+}
+
+/// Errors reading or writing a record.
+#[derive(Debug)]
+pub enum RecordError {
+    /// The underlying reader or writer failed.
+    Io(io::Error),
+    /// A line wasn't valid JSON for its position (1-based line number).
+    Json {
+        line: usize,
+        source: serde_json::Error,
+    },
+    /// The input was empty.
+    MissingHeader,
+    /// The record was written by a newer format version.
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "record I/O error: {e}"),
+            Self::Json { line, source } => write!(f, "invalid record line {line}: {source}"),
+            Self::MissingHeader => f.write_str("record is empty (missing header)"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "record version {v} is newer than supported version {RECORD_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json { source, .. } => Some(source),
+            Self::MissingHeader | Self::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for RecordError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Streams a record to any [`Write`]r, timestamping entries from a [`Clock`].
+pub struct RecordWriter<W: Write> {
+    out: W,
+    clock: Arc<dyn Clock>,
+}
+
+impl<W: Write> RecordWriter<W> {
+    /// Write `header` and return a writer ready for entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordError::Io`] if the header can't be written.
+    pub fn new(
+        mut out: W,
+        header: &RecordHeader,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self, RecordError> {
+        write_line(&mut out, header)?;
+        Ok(Self { out, clock })
+    }
+
+    /// Append `message` stamped with the current time. Returns `false`
+    /// (writing nothing) for messages that aren't stored for replay.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordError::Io`] if the entry can't be written.
+    pub fn record(&mut self, message: &ServerMessage) -> Result<bool, RecordError> {
+        let at = self.clock.now();
+        self.record_at(at, message)
+    }
+
+    /// Like [`record`](Self::record) with an explicit timestamp.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordError::Io`] if the entry can't be written.
+    pub fn record_at(
+        &mut self,
+        at: DateTime<Utc>,
+        message: &ServerMessage,
+    ) -> Result<bool, RecordError> {
+        if !message.should_store_for_replay() {
+            return Ok(false);
+        }
+        write_line(&mut self.out, &EntryRef { at, message })?;
+        Ok(true)
+    }
+
+    /// Record a batch from [`GameEngine`](super::GameEngine) as a spectator
+    /// would see it: broadcasts are kept, personal messages (errors) are
+    /// dropped, and of the per-player `GameStarted` copies only the first
+    /// is kept. Returns how many entries were written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordError::Io`] if an entry can't be written.
+    pub fn record_outbound(&mut self, batch: &[Outbound]) -> Result<usize, RecordError> {
+        let mut written = 0;
+        let mut game_started = false;
+        for outbound in batch {
+            let keep = match (&outbound.audience, &outbound.message) {
+                (Audience::All | Audience::AllExcept(_), _) => true,
+                (Audience::Player(_), ServerMessage::GameStarted { .. }) => !game_started,
+                (Audience::Player(_), _) => false,
+            };
+            if keep && self.record(&outbound.message)? {
+                written += 1;
+                game_started |= matches!(outbound.message, ServerMessage::GameStarted { .. });
+            }
+        }
+        Ok(written)
+    }
+
+    /// Flush buffered output.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordError::Io`] if the flush fails.
+    pub fn flush(&mut self) -> Result<(), RecordError> {
+        Ok(self.out.flush()?)
+    }
+
+    /// Flush and return the underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordError::Io`] if the flush fails.
+    pub fn into_inner(mut self) -> Result<W, RecordError> {
+        self.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Borrowing twin of [`RecordEntry`] so writing doesn't clone the message.
+#[derive(Serialize)]
+struct EntryRef<'a> {
+    at: DateTime<Utc>,
+    message: &'a ServerMessage,
+}
+
+fn write_line<W: Write, T: Serialize>(out: &mut W, value: &T) -> Result<(), RecordError> {
+    serde_json::to_writer(&mut *out, value).map_err(io::Error::from)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Reads a record line by line. The header is parsed up front; entries are
+/// yielded by iterating.
+pub struct RecordReader<R: BufRead> {
+    lines: std::io::Lines<R>,
+    header: RecordHeader,
+    line: usize,
+}
+
+impl<R: BufRead> RecordReader<R> {
+    /// Parse the header from `input`.
+    ///
+    /// # Errors
+    ///
+    /// - [`RecordError::MissingHeader`] if `input` is empty
+    /// - [`RecordError::Json`] if the first line isn't a header
+    /// - [`RecordError::UnsupportedVersion`] if it was written by a newer format
+    pub fn new(input: R) -> Result<Self, RecordError> {
+        let mut lines = input.lines();
+        let first = lines.next().ok_or(RecordError::MissingHeader)??;
+        let header: RecordHeader =
+            serde_json::from_str(&first).map_err(|source| RecordError::Json { line: 1, source })?;
+        if header.version > RECORD_VERSION {
+            return Err(RecordError::UnsupportedVersion(header.version));
+        }
+        Ok(Self {
+            lines,
+            header,
+            line: 1,
+        })
+    }
+
+    #[must_use]
+    pub fn header(&self) -> &RecordHeader {
+        &self.header
+    }
+}
+
+impl<R: BufRead> Iterator for RecordReader<R> {
+    type Item = Result<RecordEntry, RecordError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let text = match self.lines.next()? {
+                Ok(text) => text,
+                Err(e) => return Some(Err(e.into())),
+            };
+            self.line += 1;
+            // Tolerate blank lines (e.g. a trailing newline added by hand).
+            if text.trim().is_empty() {
+                continue;
+            }
+            let line = self.line;
+            return Some(
+                serde_json::from_str(&text).map_err(|source| RecordError::Json { line, source }),
+            );
+        }
+    }
+}
+
+/// A fully loaded record.
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub header: RecordHeader,
+    pub entries: Vec<RecordEntry>,
+}
+
+impl GameRecord {
+    /// Read a whole record.
+    ///
+    /// # Errors
+    ///
+    /// See [`RecordReader::new`]; also fails on the first bad entry line.
+    pub fn read_from<R: BufRead>(input: R) -> Result<Self, RecordError> {
+        let reader = RecordReader::new(input)?;
+        let header = reader.header().clone();
+        let entries = reader.collect::<Result<_, _>>()?;
+        Ok(Self { header, entries })
+    }
+
+    /// Write the record as JSON Lines. Entries are written as-is (already
+    /// filtered when they were recorded).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RecordError::Io`] if writing fails.
+    pub fn write_to<W: Write>(&self, mut out: W) -> Result<(), RecordError> {
+        write_line(&mut out, &self.header)?;
+        for entry in &self.entries {
+            write_line(&mut out, entry)?;
+        }
+        Ok(out.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::protocol::ErrorCode;
+
+    fn start() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    fn header() -> RecordHeader {
+        RecordHeader::new("g1", GameConfig::default(), Vec::new(), u64::MAX, start())
+    }
+
+    #[test]
+    fn header_seed_is_a_string() {
+        let json = serde_json::to_value(header()).unwrap();
+        assert_eq!(json["seed"], "18446744073709551615");
+        assert_eq!(json["version"], RECORD_VERSION);
+    }
+
+    #[test]
+    fn writer_filters_and_round_trips() {
+        let clock = Arc::new(ManualClock::new(start()));
+        let mut writer = RecordWriter::new(Vec::new(), &header(), clock.clone()).unwrap();
+        assert!(!writer
+            .record(&ServerMessage::HeartbeatAck { server_time: 0 })
+            .unwrap());
+        clock.advance(chrono::Duration::seconds(3));
+        assert!(writer
+            .record(&ServerMessage::TurnPassed {
+                player_id: 1,
+                game_id: "g1".into(),
+            })
+            .unwrap());
+        let bytes = writer.into_inner().unwrap();
+        assert_eq!(bytes.split(|&b| b == b'\n').count(), 3);
+
+        let record = GameRecord::read_from(bytes.as_slice()).unwrap();
+        assert_eq!(record.header.seed, u64::MAX);
+        assert_eq!(record.entries.len(), 1);
+        assert_eq!(record.entries[0].at, start() + chrono::Duration::seconds(3));
+        assert!(matches!(
+            record.entries[0].message,
+            ServerMessage::TurnPassed { player_id: 1, .. }
+        ));
+
+        let mut again = Vec::new();
+        record.write_to(&mut again).unwrap();
+        assert_eq!(again, bytes);
+    }
+
+    #[test]
+    fn record_outbound_keeps_one_game_started() {
+        let started = ServerMessage::GameStarted {
+            game_id: "g1".into(),
+            grid: Vec::new(),
+            players: Vec::new(),
+            your_turn_order: 0,
+            current_turn: 1,
+            round: 1,
+            max_rounds: 5,
+            turn_time_limit: None,
+            custom: None,
+            time_remaining_ms: None,
+        };
+        let batch = [
+            Outbound {
+                audience: Audience::Player(1),
+                message: started.clone(),
+            },
+            Outbound {
+                audience: Audience::Player(2),
+                message: started,
+            },
+            Outbound {
+                audience: Audience::Player(2),
+                message: ServerMessage::error(ErrorCode::NotYourTurn),
+            },
+        ];
+        let clock = Arc::new(ManualClock::new(start()));
+        let mut writer = RecordWriter::new(Vec::new(), &header(), clock).unwrap();
+        assert_eq!(writer.record_outbound(&batch).unwrap(), 1);
+    }
+
+    #[test]
+    fn reader_errors() {
+        assert!(matches!(
+            RecordReader::new(&b""[..]),
+            Err(RecordError::MissingHeader)
+        ));
+
+        let mut future = serde_json::to_value(header()).unwrap();
+        future["version"] = (RECORD_VERSION + 1).into();
+        let text = format!("{future}\n");
+        assert!(matches!(
+            RecordReader::new(text.as_bytes()),
+            Err(RecordError::UnsupportedVersion(v)) if v == RECORD_VERSION + 1
+        ));
+
+        let text = format!(
+            "{}\n\nnot json\n",
+            serde_json::to_string(&header()).unwrap()
+        );
+        let err = GameRecord::read_from(text.as_bytes()).unwrap_err();
+        assert!(matches!(err, RecordError::Json { line: 3, .. }));
+    }
+}
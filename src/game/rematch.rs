@@ -0,0 +1,414 @@
+//! Rematch countdown coordinator.
+//!
+//! After `GameOver` every player is put in the rematch pool and a countdown
+//! starts. Players may leave the pool (`LeaveRematch`), anyone still in it
+//! may start early (`TriggerRematch`), and otherwise the rematch starts when
+//! the countdown runs out:
+//!
+//! ```text
+//!   Active ──── trigger / expiry ────▶ Starting
+//!     │
+//!     └── pool drops below min_players ─▶ Idle (cancelled)
+//! ```
+//!
+//! Every change is announced with `RematchCountdownUpdate`; leaving adds a
+//! preceding `PlayerLeftRematch` and starting adds a trailing
+//! `RematchStarting`. Time comes from an injected [`Clock`]; call
+//! [`RematchCountdown::tick`] whenever [`RematchCountdown::next_deadline`]
+//! passes.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::clock::{Clock, Ticking};
+use crate::protocol::{ErrorCode, GameType, RematchCountdownState, ServerMessage};
+
+/// Tunables for the rematch countdown.
+#[derive(Debug, Clone)]
+pub struct RematchConfig {
+    /// How long the pool waits before starting on its own.
+    pub countdown: Duration,
+    /// Fewest players a rematch can start with. Below this the countdown
+    /// is cancelled and everyone returns to the lobby.
+    pub min_players: usize,
+}
+
+impl Default for RematchConfig {
+    fn default() -> Self {
+        Self {
+            countdown: Duration::seconds(15),
+            min_players: 2,
+        }
+    }
+}
+
+/// Clock-driven rematch pool for one finished game.
+pub struct RematchCountdown {
+    previous_game_id: String,
+    game_type: GameType,
+    clock: Arc<dyn Clock>,
+    config: RematchConfig,
+    players: Vec<i64>,
+    expires_at: DateTime<Utc>,
+    state: RematchCountdownState,
+    /// Expiry messages produced by a call that then failed validation,
+    /// held for the next [`RematchCountdown::tick`].
+    pending: Vec<ServerMessage>,
+}
+
+impl std::fmt::Debug for RematchCountdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RematchCountdown")
+            .field("previous_game_id", &self.previous_game_id)
+            .field("players", &self.players)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RematchCountdown {
+    /// Open the pool for `players` of a game that just ended. The countdown
+    /// starts now; send [`update`](Self::update) right after `GameOver`.
+    ///
+    /// A pool that is already below [`RematchConfig::min_players`] starts
+    /// out `Idle`.
+    pub fn new(
+        previous_game_id: impl Into<String>,
+        game_type: GameType,
+        players: Vec<i64>,
+        clock: Arc<dyn Clock>,
+        config: RematchConfig,
+    ) -> Self {
+        let expires_at = clock.now() + config.countdown;
+        let mut countdown = Self {
+            previous_game_id: previous_game_id.into(),
+            game_type,
+            clock,
+            config,
+            players,
+            expires_at,
+            state: RematchCountdownState::Idle,
+            pending: Vec::new(),
+        };
+        if countdown.enough_players() {
+            countdown.state = countdown.active_state();
+        }
+        countdown
+    }
+
+    /// Current state as last announced.
+    #[must_use]
+    pub fn state(&self) -> &RematchCountdownState {
+        &self.state
+    }
+
+    /// The game the pool was formed from.
+    #[must_use]
+    pub fn previous_game_id(&self) -> &str {
+        &self.previous_game_id
+    }
+
+    /// Players still in the pool.
+    #[must_use]
+    pub fn players(&self) -> &[i64] {
+        &self.players
+    }
+
+    /// The players and game type to start the new game with, once the
+    /// countdown has reached `Starting`.
+    #[must_use]
+    pub fn starting(&self) -> Option<(&[i64], GameType)> {
+        matches!(self.state, RematchCountdownState::Starting { .. })
+            .then_some((self.players.as_slice(), self.game_type))
+    }
+
+    /// The next instant at which [`tick`](Self::tick) has something to emit.
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<DateTime<Utc>> {
+        if !self.pending.is_empty() {
+            return Some(self.clock.now());
+        }
+        match self.state {
+            RematchCountdownState::Active { expires_at, .. } => Some(expires_at),
+            RematchCountdownState::Idle | RematchCountdownState::Starting { .. } => None,
+        }
+    }
+
+    /// `RematchCountdownUpdate` carrying the current state, with
+    /// `seconds_remaining` refreshed. Also suitable for a player who
+    /// reconnects to the results screen.
+    #[must_use]
+    pub fn update(&self) -> ServerMessage {
+        let state = match self.state {
+            RematchCountdownState::Active { .. } => self.active_state(),
+            ref other => other.clone(),
+        };
+        ServerMessage::RematchCountdownUpdate {
+            state,
+            previous_game_id: self.previous_game_id.clone(),
+        }
+    }
+
+    fn enough_players(&self) -> bool {
+        self.players.len() >= self.config.min_players
+    }
+
+    fn active_state(&self) -> RematchCountdownState {
+        let remaining = (self.expires_at - self.clock.now())
+            .num_milliseconds()
+            .max(0);
+        // Round up so the display never shows 0 while still counting.
+        let seconds = u32::try_from((remaining + 999) / 1000).unwrap_or(u32::MAX);
+        RematchCountdownState::Active {
+            expires_at: self.expires_at,
+            seconds_remaining: seconds,
+            player_ids: self.players.clone(),
+            game_type: self.game_type,
+        }
+    }
+
+    fn start(&mut self, triggered_by: Option<i64>) -> Vec<ServerMessage> {
+        self.state = RematchCountdownState::Starting { triggered_by };
+        vec![
+            self.update(),
+            ServerMessage::RematchStarting {
+                triggered_by,
+                previous_game_id: self.previous_game_id.clone(),
+            },
+        ]
+    }
+
+    /// Check a rematch message is aimed at this pool by one of its members
+    /// while the countdown is running.
+    fn check(&self, player_id: i64, previous_game_id: &str) -> Result<(), ErrorCode> {
+        if previous_game_id != self.previous_game_id {
+            return Err(ErrorCode::GameNotFound);
+        }
+        if !self.players.contains(&player_id) {
+            return Err(ErrorCode::NotInGame);
+        }
+        if !matches!(self.state, RematchCountdownState::Active { .. }) {
+            return Err(ErrorCode::InvalidAction);
+        }
+        Ok(())
+    }
+
+    /// Handle `TriggerRematch` from `player_id`: start immediately.
+    ///
+    /// Returns `RematchCountdownUpdate(Starting)` then `RematchStarting`.
+    ///
+    /// # Errors
+    ///
+    /// - `GameNotFound` — `previous_game_id` isn't this pool's game
+    /// - `NotInGame` — player isn't in the pool
+    /// - `InvalidAction` — the countdown was cancelled or already started
+    pub fn trigger(
+        &mut self,
+        player_id: i64,
+        previous_game_id: &str,
+    ) -> Result<Vec<ServerMessage>, ErrorCode> {
+        self.after_tick(|this| {
+            this.check(player_id, previous_game_id)?;
+            Ok(this.start(Some(player_id)))
+        })
+    }
+
+    /// Handle `LeaveRematch` from `player_id`.
+    ///
+    /// Returns `PlayerLeftRematch` then the updated countdown, which is
+    /// `Idle` if the pool dropped below [`RematchConfig::min_players`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`trigger`](Self::trigger).
+    pub fn leave(
+        &mut self,
+        player_id: i64,
+        previous_game_id: &str,
+    ) -> Result<Vec<ServerMessage>, ErrorCode> {
+        self.after_tick(|this| {
+            this.check(player_id, previous_game_id)?;
+            Ok(this.remove(player_id))
+        })
+    }
+
+    /// A pool member disconnected; treated as leaving. Does nothing once
+    /// the countdown is over or if they weren't in the pool.
+    pub fn remove_player(&mut self, player_id: i64) -> Vec<ServerMessage> {
+        let mut out = self.tick();
+        if self.players.contains(&player_id)
+            && matches!(self.state, RematchCountdownState::Active { .. })
+        {
+            out.extend(self.remove(player_id));
+        }
+        out
+    }
+
+    fn remove(&mut self, player_id: i64) -> Vec<ServerMessage> {
+        self.players.retain(|&p| p != player_id);
+        self.state = if self.enough_players() {
+            self.active_state()
+        } else {
+            RematchCountdownState::Idle
+        };
+        vec![
+            ServerMessage::PlayerLeftRematch {
+                player_id,
+                previous_game_id: self.previous_game_id.clone(),
+            },
+            self.update(),
+        ]
+    }
+
+    /// Start the rematch if the countdown has run out.
+    pub fn tick(&mut self) -> Vec<ServerMessage> {
+        let mut out = std::mem::take(&mut self.pending);
+        if matches!(self.state, RematchCountdownState::Active { .. })
+            && self.clock.now() >= self.expires_at
+        {
+            out.extend(self.start(None));
+        }
+        out
+    }
+}
+
+impl Ticking for RematchCountdown {
+    fn tick(&mut self) -> Vec<ServerMessage> {
+        RematchCountdown::tick(self)
+    }
+
+    fn pending(&mut self) -> &mut Vec<ServerMessage> {
+        &mut self.pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+
+    fn setup(players: Vec<i64>) -> (RematchCountdown, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(
+            DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        ));
+        let countdown = RematchCountdown::new(
+            "g1",
+            GameType::Open,
+            players,
+            clock.clone(),
+            RematchConfig::default(),
+        );
+        (countdown, clock)
+    }
+
+    fn describe(msg: &ServerMessage) -> String {
+        match msg {
+            ServerMessage::RematchCountdownUpdate { state, .. } => match state {
+                RematchCountdownState::Idle => "idle".into(),
+                RematchCountdownState::Active {
+                    seconds_remaining,
+                    player_ids,
+                    ..
+                } => format!("active {seconds_remaining}s {player_ids:?}"),
+                RematchCountdownState::Starting { triggered_by } => {
+                    format!("starting {triggered_by:?}")
+                }
+            },
+            ServerMessage::PlayerLeftRematch { player_id, .. } => format!("left {player_id}"),
+            ServerMessage::RematchStarting { triggered_by, .. } => {
+                format!("rematch_starting {triggered_by:?}")
+            }
+            other => other.message_type().into(),
+        }
+    }
+
+    fn describe_all(msgs: &[ServerMessage]) -> Vec<String> {
+        msgs.iter().map(describe).collect()
+    }
+
+    #[test]
+    fn initial_update_lists_pool() {
+        let (countdown, clock) = setup(vec![1, 2, 3]);
+        assert_eq!(describe(&countdown.update()), "active 15s [1, 2, 3]");
+        clock.advance(Duration::milliseconds(4_500));
+        assert_eq!(describe(&countdown.update()), "active 11s [1, 2, 3]");
+    }
+
+    #[test]
+    fn leave_then_expiry() {
+        let (mut countdown, clock) = setup(vec![1, 2, 3]);
+        clock.advance(Duration::seconds(5));
+        let out = countdown.leave(2, "g1").unwrap();
+        assert_eq!(describe_all(&out), ["left 2", "active 10s [1, 3]"]);
+
+        clock.advance(Duration::seconds(9));
+        assert!(countdown.tick().is_empty());
+        clock.advance(Duration::seconds(1));
+        assert_eq!(
+            describe_all(&countdown.tick()),
+            ["starting None", "rematch_starting None"]
+        );
+        assert_eq!(countdown.starting(), Some((&[1, 3][..], GameType::Open)));
+        assert_eq!(countdown.next_deadline(), None);
+    }
+
+    #[test]
+    fn trigger_starts_early() {
+        let (mut countdown, _) = setup(vec![1, 2]);
+        let out = countdown.trigger(2, "g1").unwrap();
+        assert_eq!(
+            describe_all(&out),
+            ["starting Some(2)", "rematch_starting Some(2)"]
+        );
+        assert_eq!(
+            countdown.trigger(1, "g1").unwrap_err(),
+            ErrorCode::InvalidAction
+        );
+        assert_eq!(
+            countdown.leave(1, "g1").unwrap_err(),
+            ErrorCode::InvalidAction
+        );
+    }
+
+    #[test]
+    fn pool_too_small_cancels() {
+        let (mut countdown, _) = setup(vec![1, 2]);
+        let out = countdown.leave(1, "g1").unwrap();
+        assert_eq!(describe_all(&out), ["left 1", "idle"]);
+        assert_eq!(countdown.next_deadline(), None);
+        assert_eq!(
+            countdown.trigger(2, "g1").unwrap_err(),
+            ErrorCode::InvalidAction
+        );
+    }
+
+    #[test]
+    fn validation() {
+        let (mut countdown, _) = setup(vec![1, 2, 3]);
+        assert_eq!(
+            countdown.trigger(1, "other").unwrap_err(),
+            ErrorCode::GameNotFound
+        );
+        assert_eq!(countdown.leave(9, "g1").unwrap_err(), ErrorCode::NotInGame);
+        countdown.leave(3, "g1").unwrap();
+        assert_eq!(countdown.leave(3, "g1").unwrap_err(), ErrorCode::NotInGame);
+        assert!(countdown.remove_player(3).is_empty());
+    }
+
+    #[test]
+    fn expiry_survives_a_rejected_call() {
+        let (mut countdown, clock) = setup(vec![1, 2]);
+        clock.advance(Duration::seconds(15));
+        // The countdown lapses inside leave(), which then refuses to act.
+        assert_eq!(
+            countdown.leave(1, "g1").unwrap_err(),
+            ErrorCode::InvalidAction
+        );
+        assert_eq!(countdown.next_deadline(), Some(clock.now()));
+        assert_eq!(
+            describe_all(&countdown.tick()),
+            ["starting None", "rematch_starting None"]
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Rebuild game state from a [`GameRecord`].
+//!
+//! The replayer folds recorded messages into a [`GameSnapshot`], the same
+//! way a spectating client would. Turn 0 begins at `GameStarted`; each
+//! `TurnChanged` (the last message of a turn transition) begins the next.
+
+use chrono::{DateTime, Utc};
+
+use super::board;
+use super::record::{GameRecord, RecordEntry};
+use crate::protocol::{
+    GameChange, GameSnapshot, GameState, PlayerInfo, ServerMessage, TimerVoteState,
+};
+
+/// Random access to the game state recorded in a [`GameRecord`].
+#[derive(Debug, Clone)]
+pub struct Replayer {
+    initial: GameSnapshot,
+    entries: Vec<RecordEntry>,
+    /// Number of entries applied at the start of each turn.
+    turn_starts: Vec<usize>,
+}
+
+impl Replayer {
+    /// Index the turns in `record`.
+    #[must_use]
+    pub fn new(record: GameRecord) -> Self {
+        let header = record.header;
+        let initial = GameSnapshot {
+            players: header.players,
+            custom: header.config.custom,
+            ..GameSnapshot::empty(header.game_id)
+        };
+        let turn_starts = record
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| {
+                matches!(
+                    e.message,
+                    ServerMessage::GameStarted { .. } | ServerMessage::TurnChanged { .. }
+                )
+            })
+            .map(|(i, _)| i + 1)
+            .collect();
+        Self {
+            initial,
+            entries: record.entries,
+            turn_starts,
+        }
+    }
+
+    /// How many turns were recorded (including one cut short by game end).
+    #[must_use]
+    pub fn turn_count(&self) -> usize {
+        self.turn_starts.len()
+    }
+
+    /// State at the start of `turn` (0-based), or `None` past the end.
+    #[must_use]
+    pub fn snapshot_at_turn(&self, turn: usize) -> Option<GameSnapshot> {
+        self.turn_starts.get(turn).map(|&n| self.fold(n))
+    }
+
+    /// State after every message sent at or before `at`.
+    #[must_use]
+    pub fn snapshot_at(&self, at: DateTime<Utc>) -> GameSnapshot {
+        self.fold(self.entries.partition_point(|e| e.at <= at))
+    }
+
+    /// State after the whole record.
+    #[must_use]
+    pub fn final_snapshot(&self) -> GameSnapshot {
+        self.fold(self.entries.len())
+    }
+
+    /// The recorded entries, for stepping through messages one at a time.
+    #[must_use]
+    pub fn entries(&self) -> &[RecordEntry] {
+        &self.entries
+    }
+
+    fn fold(&self, count: usize) -> GameSnapshot {
+        let mut snapshot = self.initial.clone();
+        for entry in &self.entries[..count] {
+            apply(&mut snapshot, &entry.message);
+        }
+        snapshot
+    }
+}
+
+fn player_mut(snapshot: &mut GameSnapshot, id: i64) -> Option<&mut PlayerInfo> {
+    snapshot.players.iter_mut().find(|p| p.user_id == id)
+}
+
+/// Apply one message to `snapshot`. Messages for other games and ones that
+/// don't affect game state are ignored.
+#[allow(clippy::too_many_lines)]
+pub fn apply(snapshot: &mut GameSnapshot, message: &ServerMessage) {
+    match message {
+        ServerMessage::GameStarted {
+            game_id,
+            grid,
+            players,
+            current_turn,
+            round,
+            max_rounds,
+            custom,
+            time_remaining_ms,
+            ..
+        } if *game_id == snapshot.game_id => {
+            let mut ordered = players.clone();
+            ordered.sort_by_key(|p| p.turn_order);
+            snapshot.players = ordered
+                .into_iter()
+                .map(|p| PlayerInfo {
+                    user_id: p.user_id,
+                    username: p.username,
+                    avatar_url: p.avatar_url,
+                    score: p.score,
+                    gems: p.gems,
+                    team: p.team,
+                    is_connected: p.is_connected,
+                })
+                .collect();
+            snapshot.state = GameState::InProgress;
+            snapshot.grid.clone_from(grid);
+            snapshot.current_turn = *current_turn;
+            snapshot.round = *round;
+            snapshot.max_rounds = *max_rounds;
+            snapshot.custom.clone_from(custom);
+            snapshot.time_remaining_ms = *time_remaining_ms;
+        }
+        ServerMessage::GameSnapshot { game_id, game }
+        | ServerMessage::SpectatorJoined { game_id, game }
+            if *game_id == snapshot.game_id =>
+        {
+            *snapshot = GameSnapshot {
+                your_player: None,
+                ..game.clone()
+            };
+        }
+        ServerMessage::GameDelta { game_id, changes } if *game_id == snapshot.game_id => {
+            for change in changes {
+                snapshot.apply_change(change);
+            }
+        }
+        ServerMessage::WordScored {
+            player_id,
+            game_id,
+            word,
+            total_score,
+            total_gems,
+            new_grid,
+            ..
+        } if *game_id == snapshot.game_id => {
+            snapshot.grid.clone_from(new_grid);
+            snapshot.used_words.push(word.clone());
+            if let Some(p) = player_mut(snapshot, *player_id) {
+                p.score = *total_score;
+                p.gems = *total_gems;
+            }
+        }
+        ServerMessage::TurnChanged {
+            player_id,
+            game_id,
+            round,
+            ..
+        } if *game_id == snapshot.game_id => {
+            snapshot.current_turn = *player_id;
+            snapshot.round = *round;
+        }
+        ServerMessage::RoundChanged {
+            game_id,
+            round,
+            max_rounds,
+            new_grid,
+        } if *game_id == snapshot.game_id => {
+            snapshot.round = *round;
+            snapshot.max_rounds = *max_rounds;
+            if let Some(grid) = new_grid {
+                snapshot.grid.clone_from(grid);
+            }
+        }
+        ServerMessage::BoardShuffled {
+            player_id,
+            game_id,
+            new_grid,
+            total_gems,
+            ..
+        } if *game_id == snapshot.game_id => {
+            snapshot.grid.clone_from(new_grid);
+            if let Some(p) = player_mut(snapshot, *player_id) {
+                p.gems = *total_gems;
+            }
+        }
+        ServerMessage::TileSwapped {
+            player_id,
+            game_id,
+            row,
+            col,
+            new_letter,
+            total_gems,
+            ..
+        } if *game_id == snapshot.game_id => {
+            let pos = crate::protocol::Position {
+                row: *row,
+                col: *col,
+            };
+            if let Some(cell) = board::cell_mut(&mut snapshot.grid, pos) {
+                cell.letter = *new_letter;
+                cell.value = board::letter_value(*new_letter);
+            }
+            if let Some(p) = player_mut(snapshot, *player_id) {
+                p.gems = *total_gems;
+            }
+        }
+        ServerMessage::AdventureEvent {
+            game_id, new_grid, ..
+        } if *game_id == snapshot.game_id => {
+            snapshot.grid.clone_from(new_grid);
+        }
+        ServerMessage::TimerVoteUpdate { state, game_id } if *game_id == snapshot.game_id => {
+            snapshot.timer_expiration_time = match state {
+                TimerVoteState::TimerActive { expires_at, .. } => Some(*expires_at),
+                _ => None,
+            };
+            snapshot.timer_vote_state = state.clone();
+        }
+        ServerMessage::GameOver {
+            game_id,
+            final_scores,
+            ..
+        } if *game_id == snapshot.game_id => {
+            snapshot.state = GameState::Finished;
+            for score in final_scores {
+                if let Some(p) = player_mut(snapshot, score.user_id) {
+                    p.score = score.score;
+                }
+            }
+        }
+        ServerMessage::GameCancelled { game_id, .. } if *game_id == snapshot.game_id => {
+            snapshot.state = GameState::Cancelled;
+        }
+        ServerMessage::SpectatorAdded { spectator, game_id } if *game_id == snapshot.game_id => {
+            snapshot.apply_change(&GameChange::SpectatorJoined {
+                spectator: spectator.clone(),
+            });
+        }
+        ServerMessage::SpectatorRemoved {
+            spectator_id,
+            game_id,
+        } if *game_id == snapshot.game_id => {
+            snapshot.apply_change(&GameChange::SpectatorLeft {
+                spectator_id: *spectator_id,
+            });
+        }
+        ServerMessage::PlayerDisconnected {
+            game_id: Some(game_id),
+            player_id,
+            ..
+        } if *game_id == snapshot.game_id => {
+            if let Some(p) = player_mut(snapshot, *player_id) {
+                p.is_connected = false;
+            }
+        }
+        ServerMessage::PlayerReconnected { player_id } => {
+            if let Some(p) = player_mut(snapshot, *player_id) {
+                p.is_connected = true;
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::game::clock::{Clock, ManualClock};
+    use crate::game::engine::{AcceptAllWords, GameEngine};
+    use crate::game::record::{RecordHeader, RecordWriter};
+    use crate::protocol::{ClientMessage, GameConfig, Position};
+    use crate::testing::player_info;
+
+    fn json(snapshot: &GameSnapshot) -> serde_json::Value {
+        serde_json::to_value(snapshot).unwrap()
+    }
+
+    /// Play a short seeded game, recording it and the engine's own
+    /// snapshot at the start of every turn.
+    fn play() -> (GameRecord, Vec<GameSnapshot>) {
+        let players = vec![player_info(1, "ann"), player_info(2, "bob")];
+        let config = GameConfig {
+            num_rounds: Some(2),
+            ..GameConfig::default()
+        };
+        let clock = Arc::new(ManualClock::new(
+            DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        ));
+        let header = RecordHeader::new("g1", config.clone(), players.clone(), 9, clock.now());
+        let mut writer = RecordWriter::new(Vec::new(), &header, clock.clone()).unwrap();
+        let mut engine = GameEngine::new("g1", config, players, 9, Box::new(AcceptAllWords));
+
+        writer.record_outbound(&engine.start()).unwrap();
+        let mut expected = vec![engine.snapshot()];
+        while let Some(turn) = engine.current_player() {
+            clock.advance(chrono::Duration::seconds(10));
+            let positions: Vec<Position> = (0..3).map(|col| Position { row: 0, col }).collect();
+            let word = positions
+                .iter()
+                .map(|&p| board::cell(engine.grid(), p).unwrap().letter)
+                .collect();
+            let mut out = engine.handle(
+                turn,
+                &ClientMessage::SubmitWord {
+                    game_id: "g1".into(),
+                    word,
+                    positions,
+                },
+            );
+            if matches!(out[0].message, ServerMessage::Error { .. }) {
+                // Same letters as an earlier word; pass instead.
+                out = engine.handle(
+                    turn,
+                    &ClientMessage::PassTurn {
+                        game_id: "g1".into(),
+                    },
+                );
+            }
+            writer.record_outbound(&out).unwrap();
+            if engine.current_player().is_some() {
+                expected.push(engine.snapshot());
+            }
+        }
+        expected.push(engine.snapshot());
+
+        let bytes = writer.into_inner().unwrap();
+        (GameRecord::read_from(bytes.as_slice()).unwrap(), expected)
+    }
+
+    #[test]
+    fn rebuilds_every_turn() {
+        let (record, expected) = play();
+        let replayer = Replayer::new(record);
+        assert_eq!(replayer.turn_count(), expected.len() - 1);
+        for (turn, snapshot) in expected[..expected.len() - 1].iter().enumerate() {
+            assert_eq!(
+                json(&replayer.snapshot_at_turn(turn).unwrap()),
+                json(snapshot),
+                "turn {turn}"
+            );
+        }
+        assert!(replayer.snapshot_at_turn(expected.len()).is_none());
+        assert_eq!(
+            json(&replayer.final_snapshot()),
+            json(expected.last().unwrap())
+        );
+    }
+
+    #[test]
+    fn snapshot_at_time() {
+        let (record, expected) = play();
+        let start = record.header.recorded_at;
+        let replayer = Replayer::new(record);
+        assert_eq!(json(&replayer.snapshot_at(start)), json(&expected[0]));
+        let after_first = start + chrono::Duration::seconds(10);
+        assert_eq!(json(&replayer.snapshot_at(after_first)), json(&expected[1]));
+    }
+}
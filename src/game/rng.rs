@@ -0,0 +1,118 @@
+//! Seeded random number generation for game logic.
+//!
+//! Everything in [`crate::game`] that needs randomness takes a `&mut GameRng`
+//! instead of reaching for a global generator. Given the same seed, the same
+//! sequence of calls produces the same board, so server logic, sandbox
+//! previews and replays stay bit-for-bit identical.
+//!
+//! The generator is `SplitMix64`: tiny, fast, and — unlike an external crate's
+//! `StdRng` — its output is pinned by this file, so a dependency bump can
+//! never silently change a recorded game.
+
+/// Deterministic pseudo-random generator (`SplitMix64`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRng {
+    state: u64,
+}
+
+impl GameRng {
+    /// Create a generator from a seed. Equal seeds yield equal sequences.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Next raw 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "GameRng::below called with n = 0");
+        // Widening multiply keeps the result unbiased enough for game use
+        // without a rejection loop.
+        let n = n as u64;
+        let r = (u128::from(self.next_u64()) * u128::from(n)) >> 64;
+        usize::try_from(r).unwrap_or(0)
+    }
+
+    /// Uniform float in `[0.0, 1.0)`.
+    pub fn next_f64(&mut self) -> f64 {
+        // 53 random mantissa bits scaled by 2^-53.
+        #[allow(clippy::cast_precision_loss)]
+        let bits = (self.next_u64() >> 11) as f64;
+        bits / 9_007_199_254_740_992.0
+    }
+
+    /// Returns `true` with probability `p` (clamped to `0.0..=1.0`).
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p.clamp(0.0, 1.0)
+    }
+
+    /// Pick a uniformly random element, or `None` if the slice is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len())])
+        }
+    }
+
+    /// Fisher–Yates shuffle in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = GameRng::new(7);
+        let mut b = GameRng::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn sequence_is_pinned() {
+        // Replays depend on this exact output. If this fails, every
+        // recorded game is invalidated — do not "fix" the expectation.
+        let mut rng = GameRng::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut rng = GameRng::new(42);
+        for n in 1..50 {
+            assert!(rng.below(n) < n);
+        }
+    }
+
+    #[test]
+    fn shuffle_is_a_permutation() {
+        let mut rng = GameRng::new(3);
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,560 @@
+//! Timer vote state machine.
+//!
+//! Implements the rules documented on `ClientMessage::InitiateTimerVote` and
+//! `ClientMessage::VoteForTimer`, moving [`TimerVoteState`] through
+//!
+//! ```text
+//!            initiate               majority reached
+//!   Idle ───────────────▶ VoteInProgress ───────────────▶ TimerActive
+//!    ▲                         │ expires                      │ expires / turn ends
+//!    │                         ▼                              │
+//!    └─────────────────── Cooldown ◀──────────────────────────┘ (to Idle)
+//!          expires
+//! ```
+//!
+//! `Disabled` is entered whenever fewer than
+//! [`TimerVoteConfig::min_players`] players are in the game.
+//!
+//! Time comes from an injected [`Clock`]; call [`TimerVote::tick`] whenever
+//! [`TimerVote::next_deadline`] passes to emit expiry transitions.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+
+use super::clock::{Clock, Ticking};
+use crate::protocol::{ErrorCode, ServerMessage, TimerVoteState};
+
+/// Tunables for the timer vote.
+#[derive(Debug, Clone)]
+pub struct TimerVoteConfig {
+    /// Minimum players in the game for voting to be available.
+    pub min_players: usize,
+    /// How long a vote stays open.
+    pub vote_duration: Duration,
+    /// Length of the turn timer once a vote passes.
+    pub timer_seconds: u32,
+    /// How long after a failed vote before another can start.
+    pub cooldown: Duration,
+}
+
+impl Default for TimerVoteConfig {
+    fn default() -> Self {
+        Self {
+            min_players: 3,
+            vote_duration: Duration::seconds(20),
+            timer_seconds: 30,
+            cooldown: Duration::seconds(60),
+        }
+    }
+}
+
+/// Clock-driven timer vote for one game.
+pub struct TimerVote {
+    game_id: String,
+    clock: Arc<dyn Clock>,
+    config: TimerVoteConfig,
+    players: Vec<i64>,
+    current_turn: i64,
+    state: TimerVoteState,
+    /// Expiry messages produced by a call that then failed validation,
+    /// held for the next [`TimerVote::tick`].
+    pending: Vec<ServerMessage>,
+}
+
+impl std::fmt::Debug for TimerVote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TimerVote")
+            .field("game_id", &self.game_id)
+            .field("players", &self.players)
+            .field("current_turn", &self.current_turn)
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TimerVote {
+    /// Create the machine for a game. Starts `Idle`, or `Disabled` if there
+    /// are too few players.
+    pub fn new(
+        game_id: impl Into<String>,
+        players: Vec<i64>,
+        current_turn: i64,
+        clock: Arc<dyn Clock>,
+        config: TimerVoteConfig,
+    ) -> Self {
+        let mut vote = Self {
+            game_id: game_id.into(),
+            clock,
+            config,
+            players,
+            current_turn,
+            state: TimerVoteState::Idle,
+            pending: Vec::new(),
+        };
+        if !vote.enough_players() {
+            vote.state = TimerVoteState::Disabled;
+        }
+        vote
+    }
+
+    /// Current state (for `GameSnapshot.timer_vote_state`).
+    #[must_use]
+    pub fn state(&self) -> &TimerVoteState {
+        &self.state
+    }
+
+    /// When the running turn timer expires, if any (for
+    /// `GameSnapshot.timer_expiration_time`).
+    #[must_use]
+    pub fn timer_expiration_time(&self) -> Option<DateTime<Utc>> {
+        match self.state {
+            TimerVoteState::TimerActive { expires_at, .. } => Some(expires_at),
+            _ => None,
+        }
+    }
+
+    /// The next instant at which [`tick`](Self::tick) has something to emit.
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<DateTime<Utc>> {
+        if !self.pending.is_empty() {
+            return Some(self.clock.now());
+        }
+        match self.state {
+            TimerVoteState::VoteInProgress { expires_at, .. }
+            | TimerVoteState::TimerActive { expires_at, .. }
+            | TimerVoteState::Cooldown { expires_at } => Some(expires_at),
+            TimerVoteState::Idle | TimerVoteState::Disabled => None,
+        }
+    }
+
+    fn enough_players(&self) -> bool {
+        self.players.len() >= self.config.min_players
+    }
+
+    /// Strict majority of the players who aren't on turn. The initiator
+    /// counts towards it.
+    fn votes_needed(&self) -> u32 {
+        let eligible = self.players.len().saturating_sub(1);
+        u32::try_from(eligible / 2 + 1).unwrap_or(u32::MAX)
+    }
+
+    fn update(&self) -> ServerMessage {
+        ServerMessage::TimerVoteUpdate {
+            state: self.state.clone(),
+            game_id: self.game_id.clone(),
+        }
+    }
+
+    /// Handle `InitiateTimerVote` from `initiator`.
+    ///
+    /// Due expiries are applied first, so a cooldown that has just run out
+    /// doesn't reject the call.
+    ///
+    /// # Errors
+    ///
+    /// - `NotInGame` — initiator isn't a player
+    /// - `NotEnoughPlayers` — fewer than `min_players` in the game
+    /// - `IsYourTurn` — initiator is the player on turn
+    /// - `TimerVoteInProgress` — a vote or timer is already running
+    /// - `TimerVoteCooldown` — a failed vote is still cooling down
+    pub fn initiate(&mut self, initiator: i64) -> Result<Vec<ServerMessage>, ErrorCode> {
+        self.after_tick(|this| this.try_initiate(initiator))
+    }
+
+    fn try_initiate(&mut self, initiator: i64) -> Result<Vec<ServerMessage>, ErrorCode> {
+        if !self.players.contains(&initiator) {
+            return Err(ErrorCode::NotInGame);
+        }
+        if !self.enough_players() {
+            return Err(ErrorCode::NotEnoughPlayers);
+        }
+        if initiator == self.current_turn {
+            return Err(ErrorCode::IsYourTurn);
+        }
+        match self.state {
+            TimerVoteState::VoteInProgress { .. } | TimerVoteState::TimerActive { .. } => {
+                return Err(ErrorCode::TimerVoteInProgress);
+            }
+            TimerVoteState::Cooldown { .. } => return Err(ErrorCode::TimerVoteCooldown),
+            TimerVoteState::Idle | TimerVoteState::Disabled => {}
+        }
+
+        let now = self.clock.now();
+        self.state = TimerVoteState::VoteInProgress {
+            initiator_id: initiator,
+            voters: Vec::new(),
+            votes_needed: self.votes_needed(),
+            expires_at: now + self.config.vote_duration,
+        };
+        Ok(self.resolve_if_passed(now))
+    }
+
+    /// Handle `VoteForTimer` from `voter`.
+    ///
+    /// # Errors
+    ///
+    /// - `NotInGame` — voter isn't a player
+    /// - `NoTimerVote` — no vote is open
+    /// - `IsYourTurn` — voter is the player on turn
+    /// - `AlreadyVoted` — voter already voted or initiated the vote
+    pub fn vote(&mut self, voter: i64) -> Result<Vec<ServerMessage>, ErrorCode> {
+        self.after_tick(|this| this.try_vote(voter))
+    }
+
+    fn try_vote(&mut self, voter: i64) -> Result<Vec<ServerMessage>, ErrorCode> {
+        if !self.players.contains(&voter) {
+            return Err(ErrorCode::NotInGame);
+        }
+        let TimerVoteState::VoteInProgress {
+            initiator_id,
+            voters,
+            ..
+        } = &mut self.state
+        else {
+            return Err(ErrorCode::NoTimerVote);
+        };
+        if voter == self.current_turn {
+            return Err(ErrorCode::IsYourTurn);
+        }
+        if voter == *initiator_id || voters.contains(&voter) {
+            return Err(ErrorCode::AlreadyVoted);
+        }
+        voters.push(voter);
+
+        let now = self.clock.now();
+        Ok(self.resolve_if_passed(now))
+    }
+
+    /// Emit the vote update, plus the timer start if the vote just passed.
+    fn resolve_if_passed(&mut self, now: DateTime<Utc>) -> Vec<ServerMessage> {
+        let passed = matches!(
+            &self.state,
+            TimerVoteState::VoteInProgress { voters, votes_needed, .. }
+                if voters.len() + 1 >= *votes_needed as usize
+        );
+        if !passed {
+            return vec![self.update()];
+        }
+
+        let seconds = self.config.timer_seconds;
+        self.state = TimerVoteState::TimerActive {
+            expires_at: now + Duration::seconds(i64::from(seconds)),
+            target_player_id: self.current_turn,
+        };
+        vec![
+            self.update(),
+            ServerMessage::TurnTimerStarted {
+                target_player_id: self.current_turn,
+                game_id: self.game_id.clone(),
+                seconds,
+            },
+        ]
+    }
+
+    /// Apply any expiries that are due and return the resulting messages.
+    ///
+    /// - vote expired without a majority → `Cooldown`
+    /// - turn timer ran out → `TurnTimerExpired`, then `Idle` (the server
+    ///   auto-passes the target and calls [`on_turn_changed`](Self::on_turn_changed))
+    /// - cooldown over → `Idle`
+    pub fn tick(&mut self) -> Vec<ServerMessage> {
+        let mut out = std::mem::take(&mut self.pending);
+        let now = self.clock.now();
+        match self.state {
+            TimerVoteState::VoteInProgress { expires_at, .. } if now >= expires_at => {
+                self.state = TimerVoteState::Cooldown {
+                    expires_at: now + self.config.cooldown,
+                };
+                out.push(self.update());
+            }
+            TimerVoteState::TimerActive {
+                expires_at,
+                target_player_id,
+            } if now >= expires_at => {
+                self.state = TimerVoteState::Idle;
+                out.push(ServerMessage::TurnTimerExpired {
+                    player_id: target_player_id,
+                    game_id: self.game_id.clone(),
+                });
+                out.push(self.update());
+            }
+            TimerVoteState::Cooldown { expires_at } if now >= expires_at => {
+                self.state = TimerVoteState::Idle;
+                out.push(self.update());
+            }
+            _ => {}
+        }
+        out
+    }
+
+    /// The turn moved to `current_turn`. Any vote or timer aimed at the
+    /// previous player is cancelled; a cooldown keeps running.
+    pub fn on_turn_changed(&mut self, current_turn: i64) -> Vec<ServerMessage> {
+        let mut out = self.tick();
+        if current_turn == self.current_turn {
+            return out;
+        }
+        self.current_turn = current_turn;
+        if matches!(
+            self.state,
+            TimerVoteState::VoteInProgress { .. } | TimerVoteState::TimerActive { .. }
+        ) {
+            self.state = TimerVoteState::Idle;
+            out.push(self.update());
+        }
+        out
+    }
+
+    /// The set of players changed (join, leave). Enables or disables voting,
+    /// drops departed players' votes and recounts the majority, which may
+    /// pass an open vote.
+    pub fn set_players(&mut self, players: Vec<i64>) -> Vec<ServerMessage> {
+        let mut out = self.tick();
+        self.players = players;
+        let needed = self.votes_needed();
+
+        if !self.enough_players() {
+            if !matches!(self.state, TimerVoteState::Disabled) {
+                self.state = TimerVoteState::Disabled;
+                out.push(self.update());
+            }
+            return out;
+        }
+
+        match &mut self.state {
+            TimerVoteState::Disabled => {
+                self.state = TimerVoteState::Idle;
+                out.push(self.update());
+            }
+            TimerVoteState::VoteInProgress { initiator_id, .. }
+                if !self.players.contains(initiator_id) =>
+            {
+                self.state = TimerVoteState::Idle;
+                out.push(self.update());
+            }
+            TimerVoteState::VoteInProgress {
+                voters,
+                votes_needed,
+                ..
+            } => {
+                voters.retain(|v| self.players.contains(v));
+                *votes_needed = needed;
+                let now = self.clock.now();
+                out.extend(self.resolve_if_passed(now));
+            }
+            _ => {}
+        }
+        out
+    }
+}
+
+impl Ticking for TimerVote {
+    fn tick(&mut self) -> Vec<ServerMessage> {
+        TimerVote::tick(self)
+    }
+
+    fn pending(&mut self) -> &mut Vec<ServerMessage> {
+        &mut self.pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+
+    fn setup(players: Vec<i64>) -> (TimerVote, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock::new(
+            DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        ));
+        let vote = TimerVote::new("g1", players, 1, clock.clone(), TimerVoteConfig::default());
+        (vote, clock)
+    }
+
+    fn status(msg: &ServerMessage) -> &'static str {
+        match msg {
+            ServerMessage::TimerVoteUpdate { state, .. } => match state {
+                TimerVoteState::Idle => "idle",
+                TimerVoteState::VoteInProgress { .. } => "vote",
+                TimerVoteState::TimerActive { .. } => "active",
+                TimerVoteState::Cooldown { .. } => "cooldown",
+                TimerVoteState::Disabled => "disabled",
+            },
+            other => other.message_type(),
+        }
+    }
+
+    #[test]
+    fn two_players_is_disabled() {
+        let (mut vote, _) = setup(vec![1, 2]);
+        assert!(matches!(vote.state(), TimerVoteState::Disabled));
+        assert_eq!(vote.initiate(2).unwrap_err(), ErrorCode::NotEnoughPlayers);
+    }
+
+    #[test]
+    fn initiate_rules() {
+        let (mut vote, _) = setup(vec![1, 2, 3]);
+        assert_eq!(vote.initiate(9).unwrap_err(), ErrorCode::NotInGame);
+        assert_eq!(vote.initiate(1).unwrap_err(), ErrorCode::IsYourTurn);
+        let out = vote.initiate(2).unwrap();
+        assert_eq!(out.iter().map(status).collect::<Vec<_>>(), ["vote"]);
+        assert_eq!(
+            vote.initiate(3).unwrap_err(),
+            ErrorCode::TimerVoteInProgress
+        );
+    }
+
+    #[test]
+    fn vote_rules_and_pass() {
+        let (mut vote, clock) = setup(vec![1, 2, 3, 4]);
+        assert_eq!(vote.vote(3).unwrap_err(), ErrorCode::NoTimerVote);
+        vote.initiate(2).unwrap();
+        assert_eq!(vote.vote(2).unwrap_err(), ErrorCode::AlreadyVoted);
+        assert_eq!(vote.vote(1).unwrap_err(), ErrorCode::IsYourTurn);
+
+        // 3 eligible voters → 2 needed; initiator + one more passes.
+        let out = vote.vote(3).unwrap();
+        assert_eq!(
+            out.iter().map(status).collect::<Vec<_>>(),
+            ["active", "turn_timer_started"]
+        );
+        assert!(matches!(
+            out[1],
+            ServerMessage::TurnTimerStarted {
+                target_player_id: 1,
+                seconds: 30,
+                ..
+            }
+        ));
+        assert_eq!(
+            vote.timer_expiration_time(),
+            Some(clock.now() + Duration::seconds(30))
+        );
+        assert_eq!(vote.vote(4).unwrap_err(), ErrorCode::NoTimerVote);
+    }
+
+    #[test]
+    fn timer_expiry_emits_turn_timer_expired() {
+        let (mut vote, clock) = setup(vec![1, 2, 3]);
+        vote.initiate(2).unwrap();
+        vote.vote(3).unwrap();
+        clock.advance(Duration::seconds(29));
+        assert!(vote.tick().is_empty());
+        clock.advance(Duration::seconds(1));
+        let out = vote.tick();
+        assert_eq!(
+            out.iter().map(status).collect::<Vec<_>>(),
+            ["turn_timer_expired", "idle"]
+        );
+        assert!(matches!(
+            out[0],
+            ServerMessage::TurnTimerExpired { player_id: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn failed_vote_cools_down() {
+        let (mut vote, clock) = setup(vec![1, 2, 3, 4, 5]);
+        vote.initiate(2).unwrap();
+        clock.advance(Duration::seconds(20));
+        assert_eq!(
+            vote.tick().iter().map(status).collect::<Vec<_>>(),
+            ["cooldown"]
+        );
+        assert_eq!(vote.initiate(3).unwrap_err(), ErrorCode::TimerVoteCooldown);
+
+        clock.advance(Duration::seconds(60));
+        // initiate() ticks first, so an elapsed cooldown doesn't block.
+        let out = vote.initiate(3).unwrap();
+        assert_eq!(out.iter().map(status).collect::<Vec<_>>(), ["idle", "vote"]);
+    }
+
+    #[test]
+    fn expiry_survives_a_rejected_call() {
+        let (mut vote, clock) = setup(vec![1, 2, 3]);
+        vote.initiate(2).unwrap();
+        clock.advance(Duration::seconds(20));
+        // The vote lapses inside vote(), which then has nothing to vote on.
+        assert_eq!(vote.vote(3).unwrap_err(), ErrorCode::NoTimerVote);
+        assert_eq!(vote.next_deadline(), Some(clock.now()));
+        assert_eq!(
+            vote.tick().iter().map(status).collect::<Vec<_>>(),
+            ["cooldown"]
+        );
+    }
+
+    #[test]
+    fn turn_change_cancels_vote() {
+        let (mut vote, _) = setup(vec![1, 2, 3]);
+        vote.initiate(2).unwrap();
+        let out = vote.on_turn_changed(2);
+        assert_eq!(out.iter().map(status).collect::<Vec<_>>(), ["idle"]);
+        assert_eq!(vote.initiate(2).unwrap_err(), ErrorCode::IsYourTurn);
+    }
+
+    #[test]
+    fn roster_changes_toggle_disabled() {
+        let (mut vote, _) = setup(vec![1, 2, 3]);
+        vote.initiate(2).unwrap();
+        assert_eq!(
+            vote.set_players(vec![1, 2])
+                .iter()
+                .map(status)
+                .collect::<Vec<_>>(),
+            ["disabled"]
+        );
+        assert_eq!(
+            vote.set_players(vec![1, 2, 4])
+                .iter()
+                .map(status)
+                .collect::<Vec<_>>(),
+            ["idle"]
+        );
+    }
+
+    fn votes_needed(vote: &TimerVote) -> u32 {
+        match vote.state() {
+            TimerVoteState::VoteInProgress { votes_needed, .. } => *votes_needed,
+            other => panic!("expected a vote, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn roster_changes_recount_the_majority() {
+        // Leaving lowers the bar and can pass the vote outright.
+        let (mut vote, _) = setup(vec![1, 2, 3, 4, 5]);
+        vote.initiate(2).unwrap();
+        vote.vote(3).unwrap();
+        assert_eq!(votes_needed(&vote), 3);
+        assert_eq!(
+            vote.set_players(vec![1, 2, 3])
+                .iter()
+                .map(status)
+                .collect::<Vec<_>>(),
+            ["active", "turn_timer_started"]
+        );
+
+        // Otherwise it stays open, winnable by those who remain.
+        let (mut vote, _) = setup(vec![1, 2, 3, 4, 5]);
+        vote.initiate(2).unwrap();
+        assert_eq!(
+            vote.set_players(vec![1, 2, 4])
+                .iter()
+                .map(status)
+                .collect::<Vec<_>>(),
+            ["vote"]
+        );
+        assert_eq!(votes_needed(&vote), 2);
+        assert_eq!(
+            vote.vote(4).unwrap().iter().map(status).collect::<Vec<_>>(),
+            ["active", "turn_timer_started"]
+        );
+
+        // Joining raises it.
+        let (mut vote, _) = setup(vec![1, 2, 3]);
+        vote.initiate(2).unwrap();
+        vote.set_players(vec![1, 2, 3, 4, 5]);
+        assert_eq!(votes_needed(&vote), 3);
+    }
+}
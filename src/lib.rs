@@ -7,7 +7,15 @@
 //! - Server-to-client message types
 //! - Shared data types (`Grid`, `Position`, `PlayerInfo`, etc.)
 //! - Player identity and context types
+//! - Rooms that broadcast to players and spectators with per-recipient messages
+//! - Spectator visibility rules (redaction and stream delay)
+//! - Deterministic game logic (adventure events, headless game engine)
 //! - Compatibility layer for gradual migration
+//! - Async socket transports (features `transport`, `ws`)
+//! - An async Rust client (feature `client`)
+//! - Test doubles such as `MockPlayerContext` (feature `testing`)
+//! - Message counts, sizes and ack lag through a pluggable recorder (feature `metrics`)
+//! - Spans and events with redacted payloads (feature `tracing`)
 //!
 //! # Usage
 //!
@@ -27,10 +35,24 @@
 //! let envelope = Envelope::new(42, response);
 //! ```
 
+#[cfg(feature = "client")]
+pub mod client;
+pub mod game;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod player;
 pub mod protocol;
+pub mod room;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "tracing")]
+pub mod trace;
+#[cfg(feature = "transport")]
+pub mod transport;
+pub mod visibility;
 
 // Re-export commonly used items at crate root for convenience
+pub use game::{apply_event, AdventureEventOutcome, EffectTracker, GameEngine, GameRng};
 pub use player::{PlayerContext, PlayerIdentity};
 pub use protocol::compat::{
     legacy_game_state_to_snapshot, parse_client_message, serialize_server_message,
@@ -39,7 +61,9 @@ pub use protocol::compat::{
 pub use protocol::{
     client_messages::ClientMessage,
     envelope::{Envelope, MaybeEnveloped},
+    error::ProtocolError,
     server_messages::ServerMessage,
+    system::{HandlerInput, SystemMessage},
     types::{
         AdminGameInfo, AdventureEventKind, BotDifficulty, BotSpec, BuildInfo, CustomMeta,
         DebugBackendGameState, DebugHandlerGameState, DebugLobbyState, DebugPlayerInfo,
@@ -0,0 +1,226 @@
+//! Message metrics (feature `metrics`).
+//!
+//! A [`MetricsRecorder`] is told about every message that crosses a
+//! connection: its `message_type()` and serialized size, client frames that
+//! fail to parse (by [`ProtocolError::kind`]), and how far behind the
+//! client's envelope acks run. `ServerConnection::with_metrics` (with the
+//! `transport` feature) wires one in; export to Prometheus, `StatsD` or logs
+//! by implementing the trait.
+//!
+//! [`InMemoryRecorder`] keeps running totals that tests and debug endpoints
+//! can read:
+//!
+//! ```
+//! use runecast_protocol::metrics::{Direction, InMemoryRecorder, MetricsRecorder};
+//!
+//! let recorder = InMemoryRecorder::new();
+//! recorder.message(Direction::Outbound, "word_scored", 2_400);
+//! recorder.message(Direction::Outbound, "selection_update", 90);
+//! recorder.message(Direction::Outbound, "selection_update", 110);
+//!
+//! let snapshot = recorder.snapshot();
+//! assert_eq!(snapshot.sent["selection_update"].count, 2);
+//! assert_eq!(snapshot.sent["word_scored"].bytes, 2_400);
+//! ```
+//!
+//! [`ProtocolError::kind`]: crate::protocol::ProtocolError::kind
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+/// Which way a message travelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Client to server.
+    Inbound,
+    /// Server to client.
+    Outbound,
+}
+
+/// The state of a client's envelope acks when a new one arrives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AckLag {
+    /// Enveloped messages sent but not yet acknowledged.
+    pub unacked: u64,
+    /// Time since the newly acknowledged message was sent, when known.
+    pub latency: Option<Duration>,
+}
+
+/// Receives metrics as they happen. Implementations must be cheap; they
+/// run on every message.
+pub trait MetricsRecorder: Send + Sync {
+    /// A message of `message_type` crossed the wire as `bytes` of JSON.
+    fn message(&self, direction: Direction, message_type: &'static str, bytes: usize);
+
+    /// A client frame failed to parse; `kind` is a
+    /// [`ProtocolError::kind`](crate::protocol::ProtocolError::kind).
+    fn parse_failure(&self, kind: &'static str);
+
+    /// A client acknowledged an enveloped message.
+    fn ack_lag(&self, lag: AckLag);
+}
+
+/// Totals for one message type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageStats {
+    pub count: u64,
+    /// Serialized bytes across all messages.
+    pub bytes: u64,
+    /// The largest single message, in bytes.
+    pub max_bytes: u64,
+}
+
+impl MessageStats {
+    /// Mean serialized size, or 0 if none were recorded.
+    #[must_use]
+    pub fn mean_bytes(&self) -> u64 {
+        self.bytes.checked_div(self.count).unwrap_or(0)
+    }
+}
+
+/// Running totals of [`AckLag`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AckLagStats {
+    pub samples: u64,
+    pub max_unacked: u64,
+    pub total_unacked: u64,
+    /// Samples that carried a latency.
+    pub timed_samples: u64,
+    pub max_latency: Duration,
+    pub total_latency: Duration,
+}
+
+impl AckLagStats {
+    /// Mean latency over the timed samples.
+    #[must_use]
+    pub fn mean_latency(&self) -> Option<Duration> {
+        let samples = u32::try_from(self.timed_samples).ok()?;
+        self.total_latency.checked_div(samples)
+    }
+}
+
+/// Everything an [`InMemoryRecorder`] has seen.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    /// Server-to-client messages, by `message_type()`.
+    pub sent: BTreeMap<&'static str, MessageStats>,
+    /// Client-to-server messages, by `message_type()`.
+    pub received: BTreeMap<&'static str, MessageStats>,
+    /// Parse failures, by error kind.
+    pub parse_failures: BTreeMap<&'static str, u64>,
+    pub ack_lag: AckLagStats,
+}
+
+impl MetricsSnapshot {
+    /// Outbound message types by total bytes, largest first.
+    #[must_use]
+    pub fn top_senders(&self) -> Vec<(&'static str, MessageStats)> {
+        let mut types: Vec<_> = self.sent.iter().map(|(t, s)| (*t, *s)).collect();
+        types.sort_by(|a, b| b.1.bytes.cmp(&a.1.bytes).then(a.0.cmp(b.0)));
+        types
+    }
+}
+
+/// A [`MetricsRecorder`] that keeps totals in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryRecorder {
+    totals: Mutex<MetricsSnapshot>,
+}
+
+impl InMemoryRecorder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn totals(&self) -> MutexGuard<'_, MetricsSnapshot> {
+        self.totals.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A copy of the totals so far.
+    #[must_use]
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.totals().clone()
+    }
+
+    /// Zero all totals.
+    pub fn reset(&self) {
+        *self.totals() = MetricsSnapshot::default();
+    }
+}
+
+impl MetricsRecorder for InMemoryRecorder {
+    fn message(&self, direction: Direction, message_type: &'static str, bytes: usize) {
+        let bytes = u64::try_from(bytes).unwrap_or(u64::MAX);
+        let mut totals = self.totals();
+        let by_type = match direction {
+            Direction::Inbound => &mut totals.received,
+            Direction::Outbound => &mut totals.sent,
+        };
+        let stats = by_type.entry(message_type).or_default();
+        stats.count += 1;
+        stats.bytes = stats.bytes.saturating_add(bytes);
+        stats.max_bytes = stats.max_bytes.max(bytes);
+    }
+
+    fn parse_failure(&self, kind: &'static str) {
+        *self.totals().parse_failures.entry(kind).or_default() += 1;
+    }
+
+    fn ack_lag(&self, lag: AckLag) {
+        let mut totals = self.totals();
+        let stats = &mut totals.ack_lag;
+        stats.samples += 1;
+        stats.max_unacked = stats.max_unacked.max(lag.unacked);
+        stats.total_unacked = stats.total_unacked.saturating_add(lag.unacked);
+        if let Some(latency) = lag.latency {
+            stats.timed_samples += 1;
+            stats.max_latency = stats.max_latency.max(latency);
+            stats.total_latency = stats.total_latency.saturating_add(latency);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_by_type_failure_kind_and_ack_lag() {
+        let recorder = InMemoryRecorder::new();
+        recorder.message(Direction::Outbound, "word_scored", 2_000);
+        recorder.message(Direction::Outbound, "selection_update", 100);
+        recorder.message(Direction::Outbound, "selection_update", 300);
+        recorder.message(Direction::Inbound, "submit_word", 80);
+        recorder.parse_failure("malformed_json");
+        recorder.parse_failure("malformed_json");
+        recorder.parse_failure("server_only");
+        recorder.ack_lag(AckLag {
+            unacked: 4,
+            latency: Some(Duration::from_millis(30)),
+        });
+        recorder.ack_lag(AckLag {
+            unacked: 0,
+            latency: None,
+        });
+
+        let snapshot = recorder.snapshot();
+        let selection = snapshot.sent["selection_update"];
+        assert_eq!((selection.count, selection.bytes), (2, 400));
+        assert_eq!((selection.max_bytes, selection.mean_bytes()), (300, 200));
+        assert_eq!(snapshot.received["submit_word"].count, 1);
+        assert_eq!(snapshot.top_senders()[0].0, "word_scored");
+        assert_eq!(snapshot.parse_failures["malformed_json"], 2);
+        assert_eq!(snapshot.parse_failures["server_only"], 1);
+        assert_eq!(snapshot.ack_lag.samples, 2);
+        assert_eq!(snapshot.ack_lag.max_unacked, 4);
+        assert_eq!(
+            snapshot.ack_lag.mean_latency(),
+            Some(Duration::from_millis(30))
+        );
+
+        recorder.reset();
+        assert_eq!(recorder.snapshot(), MetricsSnapshot::default());
+    }
+}
@@ -3,6 +3,9 @@
 //! These types are the single source of truth for player identity information,
 //! shared across the handlers crate and backend.
 
+use std::sync::Arc;
+
+use crate::room::Role;
 use crate::ServerMessage;
 
 /// Core player identity - the immutable parts that identify a player.
@@ -24,6 +27,36 @@ pub trait PlayerIdentity {
 pub trait PlayerContext: Send + Sync {
     async fn send_message(&self, msg: ServerMessage);
 
+    /// Send `msg`, which has already been serialized as `json`.
+    ///
+    /// [`Room`](crate::room::Room) broadcasts serialize each distinct
+    /// message once and deliver it through this method. Contexts that write
+    /// JSON to a socket can override it to reuse `json`; the default sends
+    /// `msg` as usual.
+    async fn send_serialized(&self, msg: &ServerMessage, json: &Arc<str>) {
+        let _ = json;
+        self.send_message(msg.clone()).await;
+    }
+
+    /// Send `msg` to a recipient the caller knows to be in `role`.
+    ///
+    /// [`Room`](crate::room::Room) broadcasts pass each member's role here,
+    /// so contexts that filter by role, like
+    /// [`VisibleContext`](crate::visibility::VisibleContext), follow the
+    /// room rather than [`is_spectating`](Self::is_spectating). The default
+    /// ignores `role`.
+    async fn send_message_as(&self, msg: ServerMessage, role: Role) {
+        let _ = role;
+        self.send_message(msg).await;
+    }
+
+    /// [`send_serialized`](Self::send_serialized) for a recipient in
+    /// `role`; see [`send_message_as`](Self::send_message_as).
+    async fn send_serialized_as(&self, msg: &ServerMessage, json: &Arc<str>, role: Role) {
+        let _ = role;
+        self.send_serialized(msg, json).await;
+    }
+
     /// Get the player's identity.
     fn identity(&self) -> &dyn PlayerIdentity;
 
@@ -0,0 +1,444 @@
+//! Per-connection rewriting of outgoing messages.
+//!
+//! Handlers build the current message forms (`GameSnapshot`, `GameDelta`,
+//! full grids) and hand them to the connection's [`OutputAdapter`], which
+//! rewrites each one into the shape that client negotiated:
+//!
+//! ```text
+//! ServerMessage ──▶ downgraders ──▶ track state ──▶ expand deltas ──▶ legacy state ──▶ strip grid flags ──▶ envelope?
+//! ```
+//!
+//! The adapter keeps the last game and lobby snapshot it forwarded so it can
+//! turn deltas into snapshots for clients that cannot apply them.
+
+use std::collections::HashMap;
+
+use super::compat;
+use super::server_messages::LobbySnapshot;
+use super::types::{GameSnapshot, Grid};
+use super::version::Downgrader;
+use super::{ServerMessage, CURRENT_WIRE_VERSION};
+
+/// What a client understands, decided once per connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Capabilities {
+    /// Accepts `Envelope`-wrapped messages with `seq` / `ack`.
+    pub envelope: bool,
+    /// Understands `game_snapshot` / `lobby_snapshot`; otherwise receives
+    /// the legacy `game_state` / `lobby_state`.
+    pub snapshots: bool,
+    /// Can apply `game_delta` / `lobby_delta`; otherwise receives the
+    /// resulting snapshot.
+    pub deltas: bool,
+    /// Knows the `is_hole` / `is_poisoned` / `is_abducted` cell flags.
+    pub grid_flags: bool,
+}
+
+impl Capabilities {
+    /// Everything the current protocol offers.
+    pub const CURRENT: Self = Self {
+        envelope: true,
+        snapshots: true,
+        deltas: true,
+        grid_flags: true,
+    };
+
+    /// Capabilities implied by a wire version (see
+    /// [`version`](super::version)). Version 1 clients get none of them.
+    #[must_use]
+    pub fn for_version(version: u32) -> Self {
+        if version >= 2 {
+            Self::CURRENT
+        } else {
+            Self {
+                envelope: false,
+                snapshots: false,
+                deltas: false,
+                grid_flags: false,
+            }
+        }
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::for_version(CURRENT_WIRE_VERSION)
+    }
+}
+
+/// Rewrites outgoing messages for one connection.
+#[derive(Debug, Clone)]
+pub struct OutputAdapter {
+    capabilities: Capabilities,
+    /// Registered version steps, newest first; see
+    /// [`VersionPipeline::output_adapter`](super::VersionPipeline::output_adapter).
+    downgraders: Vec<Downgrader>,
+    /// Last snapshot forwarded per game, kept only when deltas are expanded.
+    games: HashMap<String, GameSnapshot>,
+    lobby: Option<LobbySnapshot>,
+}
+
+impl OutputAdapter {
+    /// Create an adapter for a connection with `capabilities`.
+    #[must_use]
+    pub fn new(capabilities: Capabilities) -> Self {
+        Self {
+            capabilities,
+            downgraders: Vec::new(),
+            games: HashMap::new(),
+            lobby: None,
+        }
+    }
+
+    /// Run `downgraders`, newest step first, before the capability
+    /// rewrites.
+    pub(crate) fn with_downgraders(mut self, downgraders: Vec<Downgrader>) -> Self {
+        self.downgraders = downgraders;
+        self
+    }
+
+    /// The capabilities this adapter was built with.
+    #[must_use]
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Rewrite `msg` for this connection.
+    ///
+    /// Returns `None` when nothing should be sent: a message a downgrader
+    /// dropped, or a delta for a game or lobby this connection has no
+    /// snapshot of yet. Send a snapshot first.
+    pub fn adapt(&mut self, msg: ServerMessage) -> Option<ServerMessage> {
+        let msg = self.downgrade(msg)?;
+        let msg = if self.capabilities.deltas {
+            msg
+        } else {
+            self.expand_deltas(msg)?
+        };
+        let mut msg = if self.capabilities.snapshots {
+            msg
+        } else {
+            Self::to_legacy_state(msg)
+        };
+        if !self.capabilities.grid_flags {
+            for_each_grid(&mut msg, strip_grid_flags);
+        }
+        Some(msg)
+    }
+
+    /// [`adapt`](Self::adapt) and serialize, enveloping with `seq` / `ack`
+    /// only if the client accepts envelopes.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `serde_json::Error` if the message cannot be serialized.
+    pub fn encode(
+        &mut self,
+        msg: ServerMessage,
+        seq: Option<u64>,
+        ack: Option<u64>,
+    ) -> Result<Option<String>, serde_json::Error> {
+        let Some(msg) = self.adapt(msg) else {
+            return Ok(None);
+        };
+        let seq = seq.filter(|_| self.capabilities.envelope);
+        compat::serialize_server_message(&msg, seq, ack).map(Some)
+    }
+
+    /// Run the downgraders on `msg` and on any missed events it carries.
+    fn downgrade(&self, msg: ServerMessage) -> Option<ServerMessage> {
+        let msg = self
+            .downgraders
+            .iter()
+            .try_fold(msg, |msg, downgrader| downgrader(msg))?;
+        Some(match msg {
+            ServerMessage::Resumed { missed_events } => ServerMessage::Resumed {
+                missed_events: missed_events
+                    .into_iter()
+                    .filter_map(|m| self.downgrade(m))
+                    .collect(),
+            },
+            other => other,
+        })
+    }
+
+    /// Record snapshots and replace deltas with the snapshot they produce.
+    fn expand_deltas(&mut self, msg: ServerMessage) -> Option<ServerMessage> {
+        match msg {
+            ServerMessage::GameDelta { game_id, changes } => {
+                let game = self.games.get_mut(&game_id)?;
+                for change in &changes {
+                    game.apply_change(change);
+                }
+                Some(ServerMessage::GameSnapshot {
+                    game: game.clone(),
+                    game_id,
+                })
+            }
+            ServerMessage::LobbyDelta { changes } => {
+                let lobby = self.lobby.as_mut()?;
+                for change in &changes {
+                    lobby.apply_change(change);
+                }
+                Some(ServerMessage::LobbySnapshot {
+                    lobby: lobby.clone(),
+                })
+            }
+            ServerMessage::Resumed { missed_events } => Some(ServerMessage::Resumed {
+                missed_events: missed_events
+                    .into_iter()
+                    .filter_map(|m| self.expand_deltas(m))
+                    .collect(),
+            }),
+            other => {
+                self.track(&other);
+                Some(other)
+            }
+        }
+    }
+
+    fn track(&mut self, msg: &ServerMessage) {
+        match msg {
+            ServerMessage::Ready { lobby, game, .. } => {
+                if let Some(lobby) = lobby {
+                    self.lobby = Some(lobby.clone());
+                }
+                if let Some(game) = game {
+                    self.games.insert(game.game_id.clone(), game.clone());
+                }
+            }
+            ServerMessage::GameSnapshot { game, .. }
+            | ServerMessage::SpectatorJoined { game, .. } => {
+                self.games.insert(game.game_id.clone(), game.clone());
+            }
+            ServerMessage::LobbyJoined { lobby, .. } | ServerMessage::LobbySnapshot { lobby } => {
+                self.lobby = Some(lobby.clone());
+            }
+            ServerMessage::GameOver { game_id, .. }
+            | ServerMessage::GameCancelled { game_id, .. } => {
+                self.games.remove(game_id);
+            }
+            ServerMessage::LobbyLeft => self.lobby = None,
+            _ => {}
+        }
+    }
+
+    fn to_legacy_state(msg: ServerMessage) -> ServerMessage {
+        match msg {
+            ServerMessage::GameSnapshot { game, .. }
+            | ServerMessage::SpectatorJoined { game, .. } => {
+                compat::snapshot_to_legacy_game_state(&game)
+            }
+            ServerMessage::LobbySnapshot { lobby } => {
+                compat::snapshot_to_legacy_lobby_state(&lobby)
+            }
+            ServerMessage::Resumed { missed_events } => ServerMessage::Resumed {
+                missed_events: missed_events
+                    .into_iter()
+                    .map(Self::to_legacy_state)
+                    .collect(),
+            },
+            other => other,
+        }
+    }
+}
+
+/// Clear the adventure cell flags; such cells render as ordinary tiles.
+fn strip_grid_flags(grid: &mut Grid) {
+    for cell in grid.iter_mut().flatten() {
+        cell.is_hole = false;
+        cell.is_poisoned = false;
+        cell.is_abducted = false;
+    }
+}
+
+/// Call `f` on every grid carried by `msg`, including nested snapshots and
+/// missed events.
+fn for_each_grid(msg: &mut ServerMessage, f: fn(&mut Grid)) {
+    match msg {
+        ServerMessage::Resumed { missed_events } => {
+            for event in missed_events {
+                for_each_grid(event, f);
+            }
+        }
+        ServerMessage::Ready {
+            game: Some(game), ..
+        }
+        | ServerMessage::GameSnapshot { game, .. }
+        | ServerMessage::SpectatorJoined { game, .. } => f(&mut game.grid),
+        ServerMessage::GameDelta { changes, .. } => {
+            for change in changes {
+                if let super::GameChange::GridUpdated { grid, .. } = change {
+                    f(grid);
+                }
+            }
+        }
+        ServerMessage::GameStarted { grid, .. } | ServerMessage::GameStateUpdate { grid, .. } => {
+            f(grid);
+        }
+        ServerMessage::AdventureEvent { new_grid, .. }
+        | ServerMessage::WordScored { new_grid, .. }
+        | ServerMessage::BoardShuffled { new_grid, .. }
+        | ServerMessage::RoundChanged {
+            new_grid: Some(new_grid),
+            ..
+        } => f(new_grid),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{
+        GameChange, GameState, GridCell, LobbyChange, LobbyPlayerInfo, TimerVoteState,
+    };
+    use crate::testing::{lobby_snapshot, player_info};
+
+    fn cell(is_hole: bool) -> GridCell {
+        GridCell {
+            letter: 'A',
+            value: 1,
+            multiplier: None,
+            has_gem: false,
+            is_hole,
+            is_poisoned: false,
+            is_abducted: false,
+        }
+    }
+
+    fn game() -> GameSnapshot {
+        GameSnapshot {
+            game_id: "g1".into(),
+            state: GameState::InProgress,
+            grid: vec![vec![cell(false), cell(true)]],
+            players: vec![player_info(1, "ada")],
+            spectators: Vec::new(),
+            current_turn: 1,
+            round: 1,
+            max_rounds: 5,
+            used_words: Vec::new(),
+            timer_vote_state: TimerVoteState::Idle,
+            your_player: None,
+            timer_expiration_time: None,
+            custom: None,
+            time_remaining_ms: None,
+        }
+    }
+
+    fn snapshot_msg() -> ServerMessage {
+        ServerMessage::GameSnapshot {
+            game_id: "g1".into(),
+            game: game(),
+        }
+    }
+
+    #[test]
+    fn current_clients_get_messages_unchanged() {
+        let mut adapter = OutputAdapter::new(Capabilities::default());
+        let delta = ServerMessage::GameDelta {
+            game_id: "g1".into(),
+            changes: vec![GameChange::RoundChanged { round: 2 }],
+        };
+        assert!(matches!(
+            adapter.adapt(delta),
+            Some(ServerMessage::GameDelta { .. })
+        ));
+        let json = adapter
+            .encode(snapshot_msg(), Some(3), Some(2))
+            .unwrap()
+            .unwrap();
+        assert!(json.starts_with(r#"{"seq":3"#), "{json}");
+        assert!(json.contains(r#""is_hole":true"#));
+    }
+
+    #[test]
+    fn deltas_expand_into_snapshots() {
+        let caps = Capabilities {
+            deltas: false,
+            ..Capabilities::CURRENT
+        };
+        let mut adapter = OutputAdapter::new(caps);
+        let delta = ServerMessage::GameDelta {
+            game_id: "g1".into(),
+            changes: vec![
+                GameChange::RoundChanged { round: 2 },
+                GameChange::ScoreUpdated {
+                    player_id: 1,
+                    score: 12,
+                    gems: 1,
+                },
+            ],
+        };
+        // No base snapshot yet: nothing to expand into.
+        assert!(adapter.adapt(delta.clone()).is_none());
+
+        adapter.adapt(snapshot_msg()).unwrap();
+        let Some(ServerMessage::GameSnapshot { game, .. }) = adapter.adapt(delta) else {
+            panic!("expected a snapshot");
+        };
+        assert_eq!(game.round, 2);
+        assert_eq!(game.players[0].score, 12);
+
+        adapter.adapt(ServerMessage::LobbySnapshot {
+            lobby: lobby_snapshot("l1"),
+        });
+        let joined = ServerMessage::LobbyDelta {
+            changes: vec![LobbyChange::PlayerJoined {
+                player: LobbyPlayerInfo {
+                    user_id: 7,
+                    username: "bo".into(),
+                    avatar_url: None,
+                    banner_url: None,
+                    accent_color: None,
+                    current_game_pool: None,
+                    active_game_id: None,
+                    spectate_game_id: None,
+                },
+            }],
+        };
+        let Some(ServerMessage::LobbySnapshot { lobby }) = adapter.adapt(joined) else {
+            panic!("expected a lobby snapshot");
+        };
+        assert_eq!(lobby.players.len(), 1);
+    }
+
+    #[test]
+    fn version_1_clients_get_legacy_raw_messages() {
+        let mut adapter = OutputAdapter::new(Capabilities::for_version(1));
+        let json = adapter
+            .encode(snapshot_msg(), Some(3), None)
+            .unwrap()
+            .unwrap();
+        assert!(json.starts_with(r#"{"type":"game_state""#), "{json}");
+        assert!(!json.contains("is_hole"));
+
+        let delta = ServerMessage::LobbyDelta {
+            changes: vec![LobbyChange::HostChanged {
+                new_host_id: "9".into(),
+            }],
+        };
+        assert!(adapter.adapt(delta.clone()).is_none());
+        adapter.adapt(ServerMessage::LobbyJoined {
+            lobby_id: "l1".into(),
+            lobby_code: None,
+            lobby: lobby_snapshot("l1"),
+        });
+        assert!(matches!(
+            adapter.adapt(delta),
+            Some(ServerMessage::LobbyStateUpdate { .. })
+        ));
+
+        let resumed = ServerMessage::Resumed {
+            missed_events: vec![snapshot_msg()],
+        };
+        let Some(ServerMessage::Resumed { missed_events }) = adapter.adapt(resumed) else {
+            panic!("expected resumed");
+        };
+        let ServerMessage::GameStateUpdate { grid, .. } = &missed_events[0] else {
+            panic!("expected game_state");
+        };
+        assert!(!grid[0][1].is_hole);
+    }
+}
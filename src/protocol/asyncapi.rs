@@ -0,0 +1,421 @@
+//! AsyncAPI 3.0 description of the WebSocket protocol (feature `asyncapi`).
+//!
+//! The document is written from the server's point of view:
+//!
+//! - channel `client` carries every [`ClientMessage`]; each one gets a
+//!   `receive` operation whose `reply` lists the server messages from its
+//!   [`Exchange`](super::exchange::Exchange), plus `error` with the codes in
+//!   `x-error-codes`
+//! - channel `server` carries every [`ServerMessage`]; the `push` operation
+//!   covers broadcasts that answer no particular request
+//! - payload schemas come from [`schema`](super::schema) and live under
+//!   `components/schemas`, together with `ClientEnvelope` / `ServerEnvelope`
+//!   for the optional [`Envelope`] wrapper
+//!
+//! Run `cargo run --features asyncapi --bin gen-asyncapi -- [out-file]`.
+
+use std::collections::BTreeMap;
+
+use schemars::generate::SchemaSettings;
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::exchange::{exchange_for, Exchange, EXCHANGES};
+use super::types::ErrorCode;
+use super::{ClientMessage, Envelope, MaybeEnveloped, ServerMessage, PROTOCOL_VERSION};
+
+const SCHEMAS: &str = "#/components/schemas";
+
+/// Root of an AsyncAPI 3.0 document (the subset this crate emits).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AsyncApi {
+    pub asyncapi: &'static str,
+    pub info: Info,
+    pub default_content_type: &'static str,
+    pub channels: BTreeMap<&'static str, Channel>,
+    pub operations: BTreeMap<String, Operation>,
+    pub components: Components,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Info {
+    pub title: &'static str,
+    pub version: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Channel {
+    pub address: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub messages: BTreeMap<String, Reference>,
+    /// Schema of the optional envelope wrapping each message.
+    #[serde(rename = "x-envelope")]
+    pub envelope: Reference,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Operation {
+    pub action: Action,
+    pub channel: Reference,
+    pub summary: String,
+    pub messages: Vec<Reference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<Reply>,
+    /// Codes the `error` reply can carry for this request.
+    #[serde(rename = "x-error-codes", skip_serializing_if = "Vec::is_empty")]
+    pub error_codes: Vec<ErrorCode>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Send,
+    Receive,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Reply {
+    pub channel: Reference,
+    pub messages: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Components {
+    pub schemas: Map<String, Value>,
+    pub messages: BTreeMap<String, Message>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub name: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub payload: Schema,
+    /// Client requests this server message answers.
+    #[serde(rename = "x-replies-to", skip_serializing_if = "Vec::is_empty")]
+    pub replies_to: Vec<&'static str>,
+}
+
+/// A `{"$ref": "#/..."}` pointer into the document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    #[serde(rename = "$ref")]
+    pub target: String,
+}
+
+impl Reference {
+    fn channel(channel: &str) -> Self {
+        Self {
+            target: format!("#/channels/{channel}"),
+        }
+    }
+
+    fn channel_message(channel: &str, tag: &str) -> Self {
+        Self {
+            target: format!("#/channels/{channel}/messages/{tag}"),
+        }
+    }
+
+    fn component_message(key: &str) -> Self {
+        Self {
+            target: format!("#/components/messages/{key}"),
+        }
+    }
+
+    fn schema(name: &str) -> Self {
+        Self {
+            target: format!("{SCHEMAS}/{name}"),
+        }
+    }
+}
+
+/// Build the AsyncAPI document.
+#[must_use]
+pub fn generate() -> AsyncApi {
+    let mut settings = SchemaSettings::draft07().for_serialize();
+    settings.definitions_path = "/components/schemas".into();
+    settings.meta_schema = None;
+    let mut generator = settings.into_generator();
+
+    let client = variants::<ClientMessage>(&mut generator);
+    let server = variants::<ServerMessage>(&mut generator);
+    let client_envelope = generator.subschema_for::<MaybeEnveloped<ClientMessage>>();
+    let server_envelope = generator.subschema_for::<Envelope<ServerMessage>>();
+
+    let mut schemas = generator.take_definitions(true);
+    rename_def(&mut schemas, &client_envelope, "ClientEnvelope");
+    rename_def(&mut schemas, &server_envelope, "ServerEnvelope");
+
+    let mut messages = BTreeMap::new();
+    let mut client_channel = BTreeMap::new();
+    for (tag, payload) in &client {
+        let key = format!("client.{tag}");
+        client_channel.insert(tag.clone(), Reference::component_message(&key));
+        messages.insert(key, message(tag, payload, Vec::new()));
+    }
+    let mut server_channel = BTreeMap::new();
+    for (tag, payload) in &server {
+        let replies_to = EXCHANGES
+            .iter()
+            .filter(|e| e.replies.contains(&tag.as_str()))
+            .map(|e| e.request)
+            .collect();
+        let key = format!("server.{tag}");
+        server_channel.insert(tag.clone(), Reference::component_message(&key));
+        messages.insert(key, message(tag, payload, replies_to));
+    }
+
+    let mut operations: BTreeMap<String, Operation> = client
+        .iter()
+        .map(|(tag, _)| (tag.clone(), operation(tag, exchange_for(tag))))
+        .collect();
+    operations.insert(
+        "push".into(),
+        Operation {
+            action: Action::Send,
+            channel: Reference::channel("server"),
+            summary: "Server-initiated messages (broadcasts, timers, state sync).".into(),
+            messages: server
+                .iter()
+                .map(|(tag, _)| Reference::channel_message("server", tag))
+                .collect(),
+            reply: None,
+            error_codes: Vec::new(),
+        },
+    );
+
+    AsyncApi {
+        asyncapi: "3.0.0",
+        info: Info {
+            title: "RuneCast WebSocket protocol",
+            version: PROTOCOL_VERSION,
+            description: "JSON text frames tagged by `type`. Every message may be sent \
+                raw or wrapped in an envelope carrying `seq` / `ack` / `ts`.",
+        },
+        default_content_type: "application/json",
+        channels: BTreeMap::from([
+            (
+                "client",
+                Channel {
+                    address: "/",
+                    title: "Client → server",
+                    description: "Raw `ClientMessage` or `ClientEnvelope` with the \
+                        message as `payload`.",
+                    messages: client_channel,
+                    envelope: Reference::schema("ClientEnvelope"),
+                },
+            ),
+            (
+                "server",
+                Channel {
+                    address: "/",
+                    title: "Server → client",
+                    description: "`ServerEnvelope` with the message as `payload`.",
+                    messages: server_channel,
+                    envelope: Reference::schema("ServerEnvelope"),
+                },
+            ),
+        ]),
+        operations,
+        components: Components { schemas, messages },
+    }
+}
+
+/// `(tag, branch schema)` for each variant of an internally tagged enum.
+fn variants<T: JsonSchema>(generator: &mut SchemaGenerator) -> Vec<(String, Schema)> {
+    generator.subschema_for::<T>();
+    let Some(Value::Array(branches)) = generator
+        .definitions()
+        .get(T::schema_name().as_ref())
+        .and_then(|schema| schema.get("oneOf"))
+    else {
+        return Vec::new();
+    };
+    branches
+        .iter()
+        .filter_map(|branch| {
+            let tag = branch.pointer("/properties/type/const")?.as_str()?;
+            let schema = Schema::try_from(branch.clone()).ok()?;
+            Some((tag.to_string(), schema))
+        })
+        .collect()
+}
+
+/// Move the definition a `$ref` points at to a stable, readable name.
+fn rename_def(defs: &mut Map<String, Value>, reference: &Schema, to: &str) {
+    let Some(from) = reference
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|r| r.strip_prefix(SCHEMAS))
+        .map(|r| r.trim_start_matches('/'))
+    else {
+        return;
+    };
+    if let Some(def) = defs.remove(from) {
+        defs.insert(to.into(), def);
+    }
+}
+
+fn message(tag: &str, payload: &Schema, replies_to: Vec<&'static str>) -> Message {
+    let description = payload
+        .get("description")
+        .and_then(Value::as_str)
+        .filter(|d| !d.is_empty());
+    Message {
+        name: tag.into(),
+        title: title(tag),
+        summary: description.and_then(|d| d.lines().next()).map(Into::into),
+        description: description.map(Into::into),
+        payload: payload.clone(),
+        replies_to,
+    }
+}
+
+fn operation(tag: &str, exchange: Option<&Exchange>) -> Operation {
+    let mut op = Operation {
+        action: Action::Receive,
+        channel: Reference::channel("client"),
+        summary: format!("{tag} (no direct reply)"),
+        messages: vec![Reference::channel_message("client", tag)],
+        reply: None,
+        error_codes: Vec::new(),
+    };
+    let Some(exchange) = exchange.filter(|e| e.expects_reply()) else {
+        return op;
+    };
+
+    let mut outcomes: Vec<String> = exchange.replies.iter().map(ToString::to_string).collect();
+    let mut replies: Vec<Reference> = exchange
+        .replies
+        .iter()
+        .map(|reply| Reference::channel_message("server", reply))
+        .collect();
+    if !exchange.errors.is_empty() {
+        replies.push(Reference::channel_message("server", "error"));
+        let codes: Vec<String> = exchange
+            .errors
+            .iter()
+            .copied()
+            .map(error_code_name)
+            .collect();
+        outcomes.push(format!("error({})", codes.join(" | ")));
+    }
+    op.summary = format!("{tag} → {}", outcomes.join(" | "));
+    op.error_codes = exchange.errors.to_vec();
+    op.reply = Some(Reply {
+        channel: Reference::channel("server"),
+        messages: replies,
+    });
+    op
+}
+
+/// The wire name of an error code, e.g. `lobby_not_found`.
+fn error_code_name(code: ErrorCode) -> String {
+    serde_json::to_value(code)
+        .ok()
+        .and_then(|v| v.as_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
+/// `join_custom_lobby` → `JoinCustomLobby`.
+fn title(tag: &str) -> String {
+    tag.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Value {
+        serde_json::to_value(generate()).unwrap()
+    }
+
+    fn refs<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    out.push(r);
+                }
+                map.values().for_each(|v| refs(v, out));
+            }
+            Value::Array(items) => items.iter().for_each(|v| refs(v, out)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn every_ref_resolves() {
+        let doc = document();
+        let mut all = Vec::new();
+        refs(&doc, &mut all);
+        assert!(all.len() > 100);
+        for r in all {
+            let target = r.strip_prefix('#').and_then(|p| doc.pointer(p));
+            assert!(target.is_some(), "dangling $ref {r}");
+        }
+    }
+
+    #[test]
+    fn every_client_message_has_an_exchange() {
+        let mut generator = SchemaGenerator::default();
+        for (tag, _) in variants::<ClientMessage>(&mut generator) {
+            assert!(exchange_for(&tag).is_some(), "no exchange for {tag}");
+        }
+        let server: Vec<String> = variants::<ServerMessage>(&mut generator)
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .collect();
+        for exchange in EXCHANGES {
+            for reply in exchange.replies {
+                assert!(server.iter().any(|t| t == reply), "unknown reply {reply}");
+            }
+        }
+    }
+
+    #[test]
+    fn join_custom_lobby_replies() {
+        let doc = generate();
+        let op = &doc.operations["join_custom_lobby"];
+        assert_eq!(op.action, Action::Receive);
+        assert_eq!(
+            op.reply.as_ref().unwrap().messages,
+            [
+                Reference::channel_message("server", "lobby_joined"),
+                Reference::channel_message("server", "error"),
+            ]
+        );
+        assert!(op.error_codes.contains(&ErrorCode::LobbyNotFound));
+        assert!(op
+            .summary
+            .starts_with("join_custom_lobby → lobby_joined | error(lobby_not_found"));
+    }
+
+    #[test]
+    fn envelopes_and_server_only_messages() {
+        let doc = document();
+        let schemas = &doc["components"]["schemas"];
+        assert!(schemas["ServerEnvelope"]["properties"]["seq"].is_object());
+        assert!(schemas.get("ClientEnvelope").is_some());
+        assert!(doc["channels"]["client"]["messages"]
+            .get("player_disconnected")
+            .is_none());
+        assert!(doc["channels"]["server"]["messages"]
+            .get("player_disconnected")
+            .is_some());
+        assert_eq!(doc["operations"]["heartbeat"]["x-error-codes"], Value::Null);
+    }
+}
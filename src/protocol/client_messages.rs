@@ -15,10 +15,15 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::types::{AdventureEventKind, GameConfig, GameMode, GameType, Position};
+use super::types::{
+    AdminLobbyFilter, AdventureEventKind, GameConfig, GameMode, GameType, NewsItemPayload,
+    PageRequest, Position, WinnerPolicy,
+};
 
 /// Messages sent from client to server.
+#[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientMessage {
     // ========================================================================
@@ -265,26 +270,56 @@ pub enum ClientMessage {
     AdminGetGames,
 
     /// Delete a specific game (admin only).
-    AdminDeleteGame { game_id: String },
+    AdminDeleteGame {
+        game_id: String,
+        /// Why, for the audit trail
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
 
-    // ========================================================================
-    // System Messages (server-generated, not sent by clients)
-    // ========================================================================
-    /// Player disconnected from WebSocket.
-    ///
-    /// This message is generated by the backend when a WebSocket connection
-    /// closes unexpectedly. It is dispatched to handlers to trigger grace
-    /// period logic and schedule cleanup timers.
-    ///
-    /// **Not sent by clients** - synthesized by the server.
-    PlayerDisconnected {
-        /// The lobby the player was in (if any)
-        #[serde(skip_serializing_if = "Option::is_none")]
-        lobby_id: Option<String>,
-        /// The game the player was in (if any)
-        #[serde(skip_serializing_if = "Option::is_none")]
-        game_id: Option<String>,
+    /// List lobbies matching `filter`, one page at a time (admin only).
+    AdminListLobbies {
+        #[serde(default)]
+        filter: AdminLobbyFilter,
+        #[serde(default)]
+        page: PageRequest,
+    },
+
+    /// Fetch any lobby's full snapshot (admin only).
+    AdminInspectLobby { lobby_id: String },
+
+    /// Fetch any game's full snapshot (admin only).
+    AdminInspectGame { game_id: String },
+
+    /// Remove a player from their lobby and game and close their
+    /// connection (admin only). They may reconnect.
+    AdminKickPlayer {
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        player_id: i64,
+        reason: String,
+    },
+
+    /// Kick a player and refuse their connections until `expires_at`
+    /// (admin only).
+    AdminBanPlayer {
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        player_id: i64,
+        reason: String,
+        /// When the ban lifts; `None` bans permanently
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
     },
+
+    /// End a game now, awarding the win per `winner` (admin only).
+    AdminForceEndGame {
+        game_id: String,
+        winner: WinnerPolicy,
+        reason: String,
+    },
+
+    /// Announce a news item to every connected client as `NewsAnnounced`
+    /// (admin only).
+    AdminPushNews { item: NewsItemPayload },
 }
 
 impl ClientMessage {
@@ -324,7 +359,13 @@ impl ClientMessage {
             Self::LeaveRematch { .. } => "leave_rematch",
             Self::AdminGetGames => "admin_get_games",
             Self::AdminDeleteGame { .. } => "admin_delete_game",
-            Self::PlayerDisconnected { .. } => "player_disconnected",
+            Self::AdminListLobbies { .. } => "admin_list_lobbies",
+            Self::AdminInspectLobby { .. } => "admin_inspect_lobby",
+            Self::AdminInspectGame { .. } => "admin_inspect_game",
+            Self::AdminKickPlayer { .. } => "admin_kick_player",
+            Self::AdminBanPlayer { .. } => "admin_ban_player",
+            Self::AdminForceEndGame { .. } => "admin_force_end_game",
+            Self::AdminPushNews { .. } => "admin_push_news",
         }
     }
 
@@ -353,6 +394,13 @@ impl ClientMessage {
                 | Self::LeaveRematch { .. }
                 | Self::AdminGetGames
                 | Self::AdminDeleteGame { .. }
+                | Self::AdminListLobbies { .. }
+                | Self::AdminInspectLobby { .. }
+                | Self::AdminInspectGame { .. }
+                | Self::AdminKickPlayer { .. }
+                | Self::AdminBanPlayer { .. }
+                | Self::AdminForceEndGame { .. }
+                | Self::AdminPushNews { .. }
         )
     }
 
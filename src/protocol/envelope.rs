@@ -32,6 +32,7 @@ use serde::{Deserialize, Serialize};
 /// - Acknowledgments for delivery confirmation
 /// - Timestamps for latency measurement and clock sync
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Envelope<T> {
     /// Monotonically increasing sequence number (per connection).
     /// Server and client maintain separate sequences.
@@ -104,6 +105,7 @@ where
 /// During the migration period, clients may send either format.
 /// The server should accept both and respond in the same format.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum MaybeEnveloped<T> {
     /// New format with envelope
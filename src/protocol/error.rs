@@ -0,0 +1,68 @@
+//! Errors from parsing client frames.
+
+use serde_json::error::Category;
+
+use super::version::VersionError;
+
+/// Why a client frame could not be turned into a [`ClientMessage`].
+///
+/// [`ClientMessage`]: super::ClientMessage
+#[derive(Debug)]
+pub enum ProtocolError {
+    /// The text is not JSON, or not a valid client message.
+    Json(serde_json::Error),
+    /// The message type is one only the server produces, such as
+    /// `player_disconnected`; see [`SystemMessage`](super::SystemMessage).
+    ServerOnly(String),
+    /// The frame's wire version cannot be handled.
+    Version(VersionError),
+}
+
+impl ProtocolError {
+    /// A short, stable label for metrics and logs: `malformed_json`,
+    /// `invalid_message`, `server_only` or `version`.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Json(e) if e.classify() == Category::Data => "invalid_message",
+            Self::Json(_) => "malformed_json",
+            Self::ServerOnly(_) => "server_only",
+            Self::Version(_) => "version",
+        }
+    }
+}
+
+impl std::fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid message: {e}"),
+            Self::ServerOnly(tag) => write!(f, "`{tag}` cannot be sent by clients"),
+            Self::Version(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+            Self::ServerOnly(_) => None,
+            Self::Version(e) => Some(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ProtocolError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<VersionError> for ProtocolError {
+    fn from(e: VersionError) -> Self {
+        match e {
+            VersionError::Json(e) => Self::Json(e),
+            e => Self::Version(e),
+        }
+    }
+}
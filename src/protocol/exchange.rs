@@ -0,0 +1,303 @@
+//! Request/response pairings for client messages.
+//!
+//! Each [`Exchange`] records which server messages answer a client message
+//! and which [`ErrorCode`]s it can be rejected with. Replies are addressed
+//! to the sender; broadcasts the request also triggers (e.g. `TurnChanged`
+//! after `SubmitWord`) are not listed.
+//!
+//! ```text
+//! JoinCustomLobby ──▶ LobbyJoined
+//!                 └─▶ Error(LobbyNotFound | LobbyFull | AlreadyInLobby)
+//! ```
+//!
+//! Names are the wire tags returned by `message_type()`.
+
+use super::types::ErrorCode;
+use super::ClientMessage;
+
+/// What the server sends back for one client message type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exchange {
+    /// The client message tag, e.g. `"join_custom_lobby"`.
+    pub request: &'static str,
+    /// Server message tags that acknowledge success. Empty for
+    /// fire-and-forget messages.
+    pub replies: &'static [&'static str],
+    /// Error codes the request can be rejected with (sent as `Error`).
+    pub errors: &'static [ErrorCode],
+}
+
+impl Exchange {
+    const fn new(
+        request: &'static str,
+        replies: &'static [&'static str],
+        errors: &'static [ErrorCode],
+    ) -> Self {
+        Self {
+            request,
+            replies,
+            errors,
+        }
+    }
+
+    /// Whether the server ever answers this request directly.
+    #[must_use]
+    pub fn expects_reply(&self) -> bool {
+        !self.replies.is_empty() || !self.errors.is_empty()
+    }
+}
+
+use ErrorCode::{
+    AlreadyInGame, AlreadyInLobby, AlreadyVoted, GameInProgress, GameNotActive, GameNotFound,
+    InsufficientGems, InvalidAction, InvalidPath, InvalidRequest, InvalidSession, IsYourTurn,
+    LobbyFull, LobbyNotFound, NoTimerVote, NotAuthenticated, NotAuthorized, NotEnoughPlayers,
+    NotHost, NotInGame, NotInLobby, NotYourTurn, PathTooShort, PlayerNotFound, SessionExpired,
+    TimerVoteCooldown, TimerVoteInProgress, TooManyPlayers, WordAlreadyUsed, WordNotInDictionary,
+};
+
+/// Errors shared by every in-game action routed through the engine.
+macro_rules! game_errors {
+    ($($extra:expr),* $(,)?) => {
+        &[GameNotFound, GameNotActive, NotInGame, InvalidRequest $(, $extra)*]
+    };
+}
+
+/// Declares [`EXCHANGES`] and [`ClientMessage::exchange`] from one list, so
+/// a variant without an entry fails to compile.
+macro_rules! exchanges {
+    ($($variant:ident => ($request:literal, $replies:expr, $errors:expr $(,)?)),* $(,)?) => {
+        /// One entry per [`ClientMessage`] variant, in declaration order.
+        pub const EXCHANGES: &[Exchange] = &[$(Exchange::new($request, $replies, $errors)),*];
+
+        impl ClientMessage {
+            /// The replies and errors this message can produce; see [`EXCHANGES`].
+            #[must_use]
+            pub fn exchange(&self) -> &'static Exchange {
+                let exchange = match self {
+                    $(Self::$variant { .. } => const { &Exchange::new($request, $replies, $errors) },)*
+                };
+                debug_assert_eq!(exchange.request, self.message_type());
+                exchange
+            }
+        }
+    };
+}
+
+exchanges! {
+    // Connection
+    Identify => (
+        "identify",
+        &["ready", "resumed", "invalid_session"],
+        &[NotAuthenticated, SessionExpired, InvalidSession],
+    ),
+    Heartbeat => ("heartbeat", &["heartbeat_ack"], &[]),
+    Ack => ("ack", &[], &[]),
+    RequestSync => ("request_sync", &["lobby_snapshot", "game_snapshot"], &[]),
+    DebugState => ("debug_state", &["debug_state_response"], &[]),
+    // Lobby
+    JoinChannelLobby => (
+        "join_channel_lobby",
+        &["lobby_joined"],
+        &[LobbyFull, AlreadyInLobby, InvalidRequest],
+    ),
+    CreateCustomLobby => (
+        "create_custom_lobby",
+        &["custom_lobby_created", "lobby_joined"],
+        &[AlreadyInLobby],
+    ),
+    JoinCustomLobby => (
+        "join_custom_lobby",
+        &["lobby_joined"],
+        &[LobbyNotFound, LobbyFull, AlreadyInLobby],
+    ),
+    LeaveLobby => ("leave_lobby", &["lobby_left"], &[NotInLobby]),
+    // Game pools
+    JoinGamePool => (
+        "join_game_pool",
+        &["pool_joined"],
+        &[NotInLobby, AlreadyInGame],
+    ),
+    LeaveGamePool => ("leave_game_pool", &["pool_left"], &[NotInLobby]),
+    // Game lifecycle
+    CreateGame => (
+        "create_game",
+        &["game_started"],
+        &[NotInLobby, GameInProgress, NotEnoughPlayers],
+    ),
+    SetSandboxConfig => (
+        "set_sandbox_config",
+        &["lobby_snapshot"],
+        &[NotInLobby, NotHost, NotAuthorized, InvalidRequest],
+    ),
+    TriggerAdventureEvent => (
+        "trigger_adventure_event",
+        &["adventure_event"],
+        game_errors![NotHost, InvalidAction],
+    ),
+    StartGame => (
+        "start_game",
+        &["game_started"],
+        &[
+            NotInLobby,
+            NotHost,
+            NotAuthorized,
+            GameInProgress,
+            NotEnoughPlayers,
+            TooManyPlayers,
+        ],
+    ),
+    // Game actions
+    SubmitWord => (
+        "submit_word",
+        &["word_scored"],
+        game_errors![
+            NotYourTurn,
+            InvalidPath,
+            PathTooShort,
+            WordNotInDictionary,
+            WordAlreadyUsed,
+        ],
+    ),
+    PassTurn => ("pass_turn", &["turn_passed"], game_errors![NotYourTurn]),
+    ShuffleBoard => (
+        "shuffle_board",
+        &["board_shuffled"],
+        game_errors![NotYourTurn, InsufficientGems],
+    ),
+    EnterSwapMode => ("enter_swap_mode", &["swap_mode_entered"], game_errors![]),
+    ExitSwapMode => ("exit_swap_mode", &["swap_mode_exited"], game_errors![]),
+    SwapTile => (
+        "swap_tile",
+        &["tile_swapped"],
+        game_errors![NotYourTurn, InsufficientGems],
+    ),
+    // Spectators
+    SpectateGame => (
+        "spectate_game",
+        &["spectator_joined"],
+        &[NotInLobby, GameNotFound, AlreadyInGame],
+    ),
+    JoinGame => (
+        "join_game",
+        &["spectator_became_player"],
+        &[
+            NotInLobby,
+            GameNotFound,
+            GameNotActive,
+            AlreadyInGame,
+            TooManyPlayers,
+        ],
+    ),
+    LeaveSpectator => ("leave_spectator", &["spectator_left"], &[GameNotFound]),
+    LeaveGame => ("leave_game", &["player_left"], &[GameNotFound, NotInGame]),
+    // Live updates are relayed to the other players only.
+    SelectionUpdate => ("selection_update", &[], &[]),
+    // Timer vote
+    InitiateTimerVote => (
+        "initiate_timer_vote",
+        &["timer_vote_update"],
+        game_errors![
+            NotEnoughPlayers,
+            IsYourTurn,
+            TimerVoteInProgress,
+            TimerVoteCooldown,
+        ],
+    ),
+    VoteForTimer => (
+        "vote_for_timer",
+        &["timer_vote_update"],
+        game_errors![NoTimerVote, AlreadyVoted, IsYourTurn],
+    ),
+    // Rematch
+    TriggerRematch => (
+        "trigger_rematch",
+        &["rematch_starting"],
+        &[GameNotFound, NotInGame, InvalidAction],
+    ),
+    LeaveRematch => (
+        "leave_rematch",
+        &["player_left_rematch"],
+        &[GameNotFound, NotInGame, InvalidAction],
+    ),
+    // Admin
+    AdminGetGames => ("admin_get_games", &["admin_games_list"], &[NotAuthorized]),
+    AdminDeleteGame => (
+        "admin_delete_game",
+        &["admin_game_deleted"],
+        &[NotAuthorized, GameNotFound],
+    ),
+    AdminListLobbies => (
+        "admin_list_lobbies",
+        &["admin_lobbies_list"],
+        &[NotAuthorized],
+    ),
+    AdminInspectLobby => (
+        "admin_inspect_lobby",
+        &["admin_lobby_inspected"],
+        &[NotAuthorized, LobbyNotFound],
+    ),
+    AdminInspectGame => (
+        "admin_inspect_game",
+        &["admin_game_inspected"],
+        &[NotAuthorized, GameNotFound],
+    ),
+    AdminKickPlayer => (
+        "admin_kick_player",
+        &["admin_player_kicked"],
+        &[NotAuthorized, PlayerNotFound],
+    ),
+    AdminBanPlayer => (
+        "admin_ban_player",
+        &["admin_player_banned"],
+        &[NotAuthorized, PlayerNotFound, InvalidRequest],
+    ),
+    // `InvalidRequest` when a `player` winner is not in the game.
+    AdminForceEndGame => (
+        "admin_force_end_game",
+        &["admin_game_force_ended"],
+        &[NotAuthorized, GameNotFound, GameNotActive, InvalidRequest],
+    ),
+    AdminPushNews => (
+        "admin_push_news",
+        &["admin_news_pushed"],
+        &[NotAuthorized, InvalidRequest],
+    ),
+}
+
+/// Look up the exchange for a client message tag.
+#[must_use]
+pub fn exchange_for(message_type: &str) -> Option<&'static Exchange> {
+    EXCHANGES.iter().find(|e| e.request == message_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_unique() {
+        for (i, e) in EXCHANGES.iter().enumerate() {
+            assert!(
+                EXCHANGES[..i].iter().all(|prev| prev.request != e.request),
+                "duplicate exchange for {}",
+                e.request
+            );
+        }
+    }
+
+    #[test]
+    fn join_custom_lobby_pairing() {
+        let msg = ClientMessage::JoinCustomLobby {
+            lobby_code: "ABC123".into(),
+        };
+        let exchange = msg.exchange();
+        assert_eq!(exchange.replies, ["lobby_joined"]);
+        assert!(exchange.errors.contains(&LobbyNotFound));
+        assert!(!ClientMessage::SelectionUpdate {
+            game_id: "g".into(),
+            positions: vec![],
+        }
+        .exchange()
+        .expects_reply());
+    }
+}
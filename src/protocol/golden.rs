@@ -0,0 +1,1403 @@
+//! Golden wire-format corpus.
+//!
+//! `fixtures/wire/` holds the canonical JSON for every message variant and
+//! nested type, written from the samples in this module:
+//!
+//! ```text
+//! fixtures/wire/
+//! ├── client/<type>.json          one per ClientMessage variant
+//! ├── server/<type>.json          one per ServerMessage variant
+//! ├── types/<Type>[.<variant>].json
+//! │                               nested types; tagged enums get one file per
+//! │                               variant, unit enums list every value
+//! ├── envelope/*.json             the optional seq/ack/ts wrapper
+//! └── legacy/*.json               older shapes: `input` must still decode and
+//!                                 re-encode as `canonical`
+//! ```
+//!
+//! The test fails when a sample serializes differently from its file, when a
+//! file does not survive decode → encode byte for byte, or when a file has
+//! no sample. After an intentional wire change, rewrite the corpus with
+//!
+//! ```text
+//! RUNECAST_BLESS=1 cargo test golden
+//! ```
+//!
+//! and review the fixture diff like any other code change. Bless never
+//! touches a legacy `input`; those record what older peers actually send.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, TimeZone, Utc};
+use pretty_assertions::StrComparison;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::types::{
+    AdminAudit, AdminGameInfo, AdminLobbyFilter, AdminLobbyInfo, AdventureEventKind, BotDifficulty,
+    BotSpec, BuildInfo, CustomMeta, DebugBackendGameState, DebugHandlerGameState, DebugLobbyState,
+    DebugPlayerInfo, DebugWebsocketContext, ErrorCode, EventConfig, GameChange, GameConfig,
+    GameMode, GamePlayerInfo, GameSnapshot, GameState, GameSummary, GameType, Grid, GridCell,
+    LevelTargets, LobbyChange, LobbyGameInfo, LobbyGamePlayerInfo, LobbyPlayerInfo, LobbyType,
+    Multiplier, NewsItemPayload, NewsNotificationType, PageInfo, PageRequest, PlayerInfo, Position,
+    RematchCountdownState, ScoreInfo, SpectatorInfo, TimerVoteState, WinnerPolicy,
+};
+use super::{
+    permission, ClientMessage, Envelope, LobbySnapshot, MaybeEnveloped, ServerMessage,
+    VersionPipeline, EXCHANGES, PERMISSIONS,
+};
+
+/// Corpus location, relative to the crate root.
+const ROOT: &str = "fixtures/wire";
+
+/// Set to `1` to rewrite fixtures from the samples instead of comparing.
+const BLESS_VAR: &str = "RUNECAST_BLESS";
+
+/// A payload in an older wire shape and what it decodes to today.
+#[derive(Debug, Serialize, Deserialize)]
+struct LegacyFixture {
+    /// Rust type to decode `input` as (see [`reencode_as`]).
+    #[serde(rename = "type")]
+    type_name: String,
+    /// Which older shape this is and why it must keep decoding.
+    note: String,
+    input: Value,
+    canonical: Value,
+}
+
+struct Corpus {
+    root: PathBuf,
+    bless: bool,
+    seen: BTreeSet<PathBuf>,
+    failures: Vec<String>,
+}
+
+impl Corpus {
+    fn new() -> Self {
+        Self {
+            root: Path::new(env!("CARGO_MANIFEST_DIR")).join(ROOT),
+            bless: std::env::var(BLESS_VAR).is_ok_and(|v| v == "1"),
+            seen: BTreeSet::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    fn check<T: Serialize + DeserializeOwned>(&mut self, name: &str, sample: &T) {
+        let path = self.root.join(format!("{name}.json"));
+        self.seen.insert(path.clone());
+        let expected = pretty(sample);
+        if self.bless {
+            write_if_changed(&path, &expected);
+            return;
+        }
+
+        let Ok(on_disk) = fs::read_to_string(&path) else {
+            self.failures.push(format!("{name}: missing fixture"));
+            return;
+        };
+        if on_disk != expected {
+            self.failures.push(format!(
+                "{name}: sample no longer serializes as the fixture\n{}",
+                StrComparison::new(&on_disk, &expected)
+            ));
+        }
+        match serde_json::from_str::<T>(&on_disk) {
+            Ok(decoded) => {
+                let again = pretty(&decoded);
+                if again != on_disk {
+                    self.failures.push(format!(
+                        "{name}: fixture does not round-trip\n{}",
+                        StrComparison::new(&on_disk, &again)
+                    ));
+                }
+            }
+            Err(e) => self
+                .failures
+                .push(format!("{name}: fixture no longer decodes: {e}")),
+        }
+    }
+
+    fn check_legacy(&mut self) {
+        let dir = self.root.join("legacy");
+        for path in json_files(&dir) {
+            self.seen.insert(path.clone());
+            let name = path.strip_prefix(&self.root).unwrap_or(&path).display();
+            let mut fixture: LegacyFixture = match fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+            {
+                Ok(fixture) => fixture,
+                Err(e) => {
+                    self.failures.push(format!("{name}: unreadable: {e}"));
+                    continue;
+                }
+            };
+            let decoded = match reencode_as(&fixture.type_name, fixture.input.clone()) {
+                Ok(decoded) => decoded,
+                Err(e) => {
+                    self.failures
+                        .push(format!("{name}: input no longer decodes: {e}"));
+                    continue;
+                }
+            };
+            if decoded == fixture.canonical {
+                continue;
+            }
+            if self.bless {
+                fixture.canonical = decoded;
+                write_if_changed(&path, &pretty(&fixture));
+            } else {
+                self.failures.push(format!(
+                    "{name}: input decodes differently\n{}",
+                    StrComparison::new(&pretty(&fixture.canonical), &pretty(&decoded))
+                ));
+            }
+        }
+    }
+
+    /// Flag (or, when blessing, delete) fixtures with no sample, then fail
+    /// with every collected difference.
+    fn finish(mut self) {
+        for dir in ["client", "server", "types", "envelope"] {
+            for path in json_files(&self.root.join(dir)) {
+                if self.seen.contains(&path) {
+                    continue;
+                }
+                if self.bless {
+                    fs::remove_file(&path).expect("remove stale fixture");
+                } else {
+                    self.failures
+                        .push(format!("{}: fixture has no sample", path.display()));
+                }
+            }
+        }
+        assert!(
+            self.failures.is_empty(),
+            "{} wire fixture(s) differ; if intended, rerun with {BLESS_VAR}=1\n\n{}",
+            self.failures.len(),
+            self.failures.join("\n\n")
+        );
+    }
+}
+
+fn pretty<T: Serialize>(value: &T) -> String {
+    let mut text = serde_json::to_string_pretty(value).expect("sample serializes");
+    text.push('\n');
+    text
+}
+
+fn write_if_changed(path: &Path, text: &str) {
+    if fs::read_to_string(path).is_ok_and(|old| old == text) {
+        return;
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).expect("create fixture dir");
+    }
+    fs::write(path, text).expect("write fixture");
+}
+
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn reencode<T: Serialize + DeserializeOwned>(input: Value) -> Result<Value, String> {
+    let decoded: T = serde_json::from_value(input).map_err(|e| e.to_string())?;
+    serde_json::to_value(decoded).map_err(|e| e.to_string())
+}
+
+/// Decode `input` as the named type and encode it again.
+/// `ClientMessage@v<n>` decodes through the [`VersionPipeline`] from wire
+/// version `n`.
+fn reencode_as(type_name: &str, input: Value) -> Result<Value, String> {
+    if let Some(version) = type_name.strip_prefix("ClientMessage@v") {
+        let version = version
+            .parse()
+            .map_err(|_| format!("bad version in {type_name}"))?;
+        let message = VersionPipeline::default()
+            .upgrade(input, version)
+            .map_err(|e| e.to_string())?;
+        return serde_json::to_value(message).map_err(|e| e.to_string());
+    }
+    macro_rules! dispatch {
+        ($($name:literal => $ty:ty),* $(,)?) => {
+            match type_name {
+                $($name => reencode::<$ty>(input),)*
+                other => Err(format!("unknown fixture type {other}")),
+            }
+        };
+    }
+    dispatch! {
+        "ClientMessage" => ClientMessage,
+        "ServerMessage" => ServerMessage,
+        "ClientEnvelope" => MaybeEnveloped<ClientMessage>,
+        "LobbySnapshot" => LobbySnapshot,
+        "GameSnapshot" => GameSnapshot,
+        "GridCell" => GridCell,
+        "PlayerInfo" => PlayerInfo,
+        "GameConfig" => GameConfig,
+        "EventConfig" => EventConfig,
+        "GameType" => GameType,
+        "AdventureEventKind" => AdventureEventKind,
+        "NewsItemPayload" => NewsItemPayload,
+    }
+}
+
+// ============================================================================
+// Samples
+// ============================================================================
+
+const GAME: &str = "game_7";
+/// Snowflake-sized so a numeric encoding would lose precision in JS.
+const ALICE: i64 = 1_234_567_890_123_456_789;
+const BOB: i64 = 42;
+
+fn at(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 14, hour, 15, 9).unwrap()
+}
+
+fn pos(row: usize, col: usize) -> Position {
+    Position { row, col }
+}
+
+fn cell(letter: char, value: u8) -> GridCell {
+    GridCell {
+        letter,
+        value,
+        multiplier: None,
+        has_gem: false,
+        is_hole: false,
+        is_poisoned: false,
+        is_abducted: false,
+    }
+}
+
+/// A 2×3 grid exercising every cell flag.
+fn grid() -> Grid {
+    vec![
+        vec![
+            GridCell {
+                multiplier: Some(Multiplier::DoubleWord),
+                has_gem: true,
+                ..cell('R', 1)
+            },
+            GridCell {
+                multiplier: Some(Multiplier::TripleLetter),
+                ..cell('U', 1)
+            },
+            GridCell {
+                is_hole: true,
+                ..cell('N', 1)
+            },
+        ],
+        vec![
+            GridCell {
+                is_poisoned: true,
+                ..cell('E', 1)
+            },
+            GridCell {
+                is_abducted: true,
+                ..cell('Q', 10)
+            },
+            cell('S', 1),
+        ],
+    ]
+}
+
+fn lobby_player() -> LobbyPlayerInfo {
+    LobbyPlayerInfo {
+        user_id: ALICE,
+        username: "alice".into(),
+        avatar_url: Some("https://cdn.example/avatars/alice.png".into()),
+        banner_url: Some("https://cdn.example/banners/alice.png".into()),
+        accent_color: Some(0x00FF_8800),
+        current_game_pool: Some(GameType::Open),
+        active_game_id: Some(GAME.into()),
+        spectate_game_id: None,
+    }
+}
+
+fn player_info() -> PlayerInfo {
+    PlayerInfo {
+        user_id: ALICE,
+        username: "alice".into(),
+        avatar_url: Some("https://cdn.example/avatars/alice.png".into()),
+        score: 37,
+        gems: 4,
+        team: Some(1),
+        is_connected: true,
+    }
+}
+
+fn game_player() -> GamePlayerInfo {
+    GamePlayerInfo {
+        user_id: BOB,
+        username: "bob".into(),
+        avatar_url: None,
+        turn_order: 1,
+        score: 12,
+        gems: 2,
+        is_connected: false,
+        team: Some(2),
+    }
+}
+
+fn spectator() -> SpectatorInfo {
+    SpectatorInfo {
+        user_id: BOB,
+        username: "bob".into(),
+        avatar_url: None,
+    }
+}
+
+fn score() -> ScoreInfo {
+    ScoreInfo {
+        user_id: ALICE,
+        username: "alice".into(),
+        score: 88,
+    }
+}
+
+fn lobby_game() -> LobbyGameInfo {
+    LobbyGameInfo {
+        game_id: GAME.into(),
+        game_type: GameType::TwoVTwo,
+        current_round: 2,
+        max_rounds: 5,
+        players: vec![LobbyGamePlayerInfo {
+            user_id: BOB,
+            username: "bob".into(),
+            score: 12,
+        }],
+    }
+}
+
+fn game_config() -> GameConfig {
+    GameConfig {
+        regenerate_board_each_round: true,
+        grid_size: 6,
+        adventure_level: Some(3),
+        level_targets: Some(LevelTargets {
+            one_star: 50,
+            two_star: 100,
+            three_star: 150,
+        }),
+        num_rounds: Some(4),
+        bots: vec![BotSpec {
+            difficulty: BotDifficulty::Hard,
+        }],
+        custom: Some(CustomMeta {}),
+        events: Some(EventConfig {
+            enabled_kinds: vec![AdventureEventKind::Bomb, AdventureEventKind::Ufo],
+            frequency: 0.25,
+        }),
+    }
+}
+
+fn lobby_snapshot() -> LobbySnapshot {
+    LobbySnapshot {
+        lobby_id: "lobby_1".into(),
+        lobby_type: LobbyType::Custom,
+        lobby_code: Some("ABC123".into()),
+        players: vec![lobby_player()],
+        games: vec![lobby_game()],
+        max_players: 6,
+        sandbox_config: Some(GameConfig::default()),
+        host_id: Some(ALICE),
+    }
+}
+
+fn vote_in_progress() -> TimerVoteState {
+    TimerVoteState::VoteInProgress {
+        initiator_id: BOB,
+        voters: vec![BOB, ALICE],
+        votes_needed: 3,
+        expires_at: at(12),
+    }
+}
+
+fn game_snapshot() -> GameSnapshot {
+    GameSnapshot {
+        game_id: GAME.into(),
+        state: GameState::InProgress,
+        grid: grid(),
+        players: vec![player_info()],
+        spectators: vec![spectator()],
+        current_turn: ALICE,
+        round: 2,
+        max_rounds: 5,
+        used_words: vec!["RUNE".into(), "SUN".into()],
+        timer_vote_state: vote_in_progress(),
+        your_player: Some(player_info()),
+        timer_expiration_time: Some(at(13)),
+        custom: Some(CustomMeta {}),
+        time_remaining_ms: Some(45_000),
+    }
+}
+
+fn news_item() -> NewsItemPayload {
+    NewsItemPayload {
+        id: "news_1".into(),
+        title: "Maintenance tonight".into(),
+        message: "Servers restart at 02:00 UTC.".into(),
+        notification_type: NewsNotificationType::Maintenance,
+        created_at: at(9),
+        expires_at: Some(at(23)),
+        auto_hide_seconds: 30,
+        refresh_on_every_login: true,
+        priority: 2,
+    }
+}
+
+fn debug_lobby() -> DebugLobbyState {
+    DebugLobbyState::Found {
+        lobby_id: "lobby_1".into(),
+        player_in_lobby: true,
+        lobby_player_ids: vec![ALICE, BOB],
+        active_game_id: Some(GAME.into()),
+    }
+}
+
+fn debug_backend_game() -> DebugBackendGameState {
+    DebugBackendGameState::Found {
+        game_id: GAME.into(),
+        player_in_session_players: true,
+        spectator_in_session: false,
+        session_player_ids: vec![ALICE],
+        session_spectator_ids: vec![BOB],
+        lobby_id: "lobby_1".into(),
+    }
+}
+
+fn debug_handler_game() -> DebugHandlerGameState {
+    DebugHandlerGameState::Found {
+        game_id: GAME.into(),
+        player_in_handler_game: true,
+        handler_player_ids: vec![ALICE],
+        current_turn_index: 0,
+        round: 2,
+        state: "in_progress".into(),
+    }
+}
+
+fn game_id() -> String {
+    GAME.into()
+}
+
+fn admin_game() -> AdminGameInfo {
+    AdminGameInfo {
+        game_id: game_id(),
+        state: GameState::InProgress,
+        created_at: at(8),
+        players: vec![ALICE],
+        lobby_id: Some("lobby_1".into()),
+        game_type: Some(GameType::Open),
+        spectator_count: 1,
+    }
+}
+
+fn admin_lobby() -> AdminLobbyInfo {
+    AdminLobbyInfo {
+        lobby_id: "lobby_1".into(),
+        lobby_type: LobbyType::Custom,
+        lobby_code: Some("ABC123".into()),
+        host_id: Some(ALICE),
+        players: vec![ALICE, BOB],
+        games: vec![game_summary()],
+        created_at: at(7),
+    }
+}
+
+fn game_summary() -> GameSummary {
+    GameSummary {
+        game_id: game_id(),
+        state: GameState::Starting,
+        current_round: 1,
+        max_rounds: 5,
+        player_count: 3,
+        spectator_count: 1,
+    }
+}
+
+fn audit(reason: Option<&str>) -> AdminAudit {
+    AdminAudit {
+        action_id: "act_1".into(),
+        admin_id: BOB,
+        performed_at: at(15),
+        reason: reason.map(Into::into),
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+fn client_samples() -> Vec<ClientMessage> {
+    use ClientMessage as C;
+    vec![
+        C::Identify {
+            resume_seq: Some(41),
+        },
+        C::Heartbeat,
+        C::Ack { seq: 17 },
+        C::RequestSync,
+        C::DebugState,
+        C::JoinChannelLobby {
+            channel_id: "1100000000000000001".into(),
+            guild_id: Some("1200000000000000002".into()),
+        },
+        C::CreateCustomLobby,
+        C::JoinCustomLobby {
+            lobby_code: "ABC123".into(),
+        },
+        C::LeaveLobby,
+        C::JoinGamePool {
+            game_type: GameType::Sandbox,
+        },
+        C::LeaveGamePool,
+        C::CreateGame {
+            mode: GameMode::Solo,
+        },
+        C::SetSandboxConfig {
+            config: game_config(),
+        },
+        C::TriggerAdventureEvent {
+            kind: AdventureEventKind::Snake,
+        },
+        C::StartGame {
+            config: Some(GameConfig::default()),
+        },
+        C::SubmitWord {
+            game_id: game_id(),
+            word: "RUNE".into(),
+            positions: vec![pos(0, 0), pos(0, 1), pos(0, 2), pos(1, 0)],
+        },
+        C::PassTurn { game_id: game_id() },
+        C::ShuffleBoard { game_id: game_id() },
+        C::EnterSwapMode { game_id: game_id() },
+        C::ExitSwapMode { game_id: game_id() },
+        C::SwapTile {
+            game_id: game_id(),
+            row: 1,
+            col: 2,
+            new_letter: 'Z',
+        },
+        C::SpectateGame { game_id: game_id() },
+        C::JoinGame { game_id: game_id() },
+        C::LeaveSpectator { game_id: game_id() },
+        C::LeaveGame { game_id: game_id() },
+        C::SelectionUpdate {
+            game_id: game_id(),
+            positions: vec![pos(0, 0), pos(1, 1)],
+        },
+        C::InitiateTimerVote { game_id: game_id() },
+        C::VoteForTimer { game_id: game_id() },
+        C::TriggerRematch {
+            previous_game_id: game_id(),
+        },
+        C::LeaveRematch {
+            previous_game_id: game_id(),
+        },
+        C::AdminGetGames,
+        C::AdminDeleteGame {
+            game_id: game_id(),
+            reason: Some("stuck".into()),
+        },
+        C::AdminListLobbies {
+            filter: AdminLobbyFilter {
+                lobby_type: Some(LobbyType::Custom),
+                player_id: Some(ALICE),
+                has_active_game: Some(true),
+            },
+            page: PageRequest {
+                offset: 50,
+                limit: 25,
+            },
+        },
+        C::AdminInspectLobby {
+            lobby_id: "lobby_1".into(),
+        },
+        C::AdminInspectGame { game_id: game_id() },
+        C::AdminKickPlayer {
+            player_id: ALICE,
+            reason: "afk".into(),
+        },
+        C::AdminBanPlayer {
+            player_id: ALICE,
+            reason: "abuse".into(),
+            expires_at: Some(at(20)),
+        },
+        C::AdminForceEndGame {
+            game_id: game_id(),
+            winner: WinnerPolicy::HighestScore,
+            reason: "server restart".into(),
+        },
+        C::AdminPushNews { item: news_item() },
+    ]
+}
+
+#[allow(clippy::too_many_lines)]
+fn server_samples() -> Vec<ServerMessage> {
+    use ServerMessage as S;
+    vec![
+        S::Hello {
+            heartbeat_interval_ms: 30_000,
+            server_version: Some("0.16.0".into()),
+        },
+        S::Ready {
+            session_id: "sess_1".into(),
+            player_id: ALICE,
+            lobby: Some(lobby_snapshot()),
+            game: Some(game_snapshot()),
+        },
+        S::Resumed {
+            missed_events: vec![S::TurnPassed {
+                player_id: BOB,
+                game_id: game_id(),
+            }],
+        },
+        S::HeartbeatAck {
+            server_time: 1_773_497_709_000,
+        },
+        S::InvalidSession {
+            reason: "session expired".into(),
+        },
+        S::LobbyJoined {
+            lobby_id: "lobby_1".into(),
+            lobby_code: Some("ABC123".into()),
+            lobby: lobby_snapshot(),
+        },
+        S::LobbySnapshot {
+            lobby: lobby_snapshot(),
+        },
+        S::LobbyDelta {
+            changes: vec![LobbyChange::HostChanged {
+                new_host_id: BOB.to_string(),
+            }],
+        },
+        S::LobbyLeft,
+        S::CustomLobbyCreated {
+            lobby_id: "lobby_1".into(),
+            lobby_code: "ABC123".into(),
+        },
+        S::GameStarted {
+            game_id: game_id(),
+            grid: grid(),
+            players: vec![game_player()],
+            your_turn_order: 1,
+            current_turn: ALICE,
+            round: 1,
+            max_rounds: 5,
+            turn_time_limit: Some(60),
+            custom: Some(CustomMeta {}),
+            time_remaining_ms: Some(300_000),
+        },
+        S::GameSnapshot {
+            game_id: game_id(),
+            game: game_snapshot(),
+        },
+        S::GameDelta {
+            game_id: game_id(),
+            changes: vec![
+                GameChange::WordUsed {
+                    word: "RUNE".into(),
+                },
+                GameChange::TurnChanged { player_id: BOB },
+            ],
+        },
+        S::GameOver {
+            game_id: game_id(),
+            final_scores: vec![score()],
+            winner_id: ALICE,
+            is_draw: false,
+        },
+        S::GameCancelled {
+            game_id: game_id(),
+            reason: "host left".into(),
+        },
+        S::AdventureLevelResult {
+            level: 3,
+            score: 120,
+            stars: 2,
+            personal_best: true,
+            unlocked_level: Some(4),
+            duration_ms: Some(95_000),
+        },
+        S::DailyResult {
+            seed: 20_260_314,
+            score: 140,
+            personal_best: 150,
+            rank: 12,
+            attempts_used: 1,
+            attempts_max: 3,
+        },
+        S::AdventureEvent {
+            game_id: game_id(),
+            kind: AdventureEventKind::Bomb,
+            affected_positions: vec![pos(0, 2)],
+            new_grid: grid(),
+            label: "Bomb!".into(),
+        },
+        S::PlayerJoined {
+            player: lobby_player(),
+        },
+        S::PlayerLeft {
+            player_id: BOB,
+            reason: Some("left".into()),
+        },
+        S::PlayerReconnected { player_id: BOB },
+        S::PlayerDisconnected {
+            game_id: Some(game_id()),
+            player_id: BOB,
+            grace_period_seconds: 60,
+        },
+        S::WordScored {
+            player_id: ALICE,
+            game_id: game_id(),
+            word: "RUNE".into(),
+            score: 14,
+            path: vec![pos(0, 0), pos(0, 1), pos(0, 2), pos(1, 0)],
+            total_score: 51,
+            gems_earned: 1,
+            total_gems: 5,
+            new_grid: grid(),
+        },
+        S::TurnChanged {
+            player_id: BOB,
+            game_id: game_id(),
+            round: 2,
+            time_remaining: Some(60),
+        },
+        S::TurnPassed {
+            player_id: BOB,
+            game_id: game_id(),
+        },
+        S::RoundChanged {
+            game_id: game_id(),
+            round: 3,
+            max_rounds: 5,
+            new_grid: Some(grid()),
+        },
+        S::BoardShuffled {
+            player_id: ALICE,
+            game_id: game_id(),
+            new_grid: grid(),
+            gems_spent: 1,
+            total_gems: 3,
+        },
+        S::TileSwapped {
+            player_id: ALICE,
+            game_id: game_id(),
+            row: 1,
+            col: 2,
+            old_letter: 'S',
+            new_letter: 'Z',
+            gems_spent: 3,
+            total_gems: 1,
+        },
+        S::SwapModeEntered {
+            player_id: ALICE,
+            game_id: game_id(),
+        },
+        S::SwapModeExited {
+            player_id: ALICE,
+            game_id: game_id(),
+        },
+        S::SpectatorJoined {
+            game_id: game_id(),
+            game: game_snapshot(),
+        },
+        S::SpectatorAdded {
+            spectator: spectator(),
+            game_id: game_id(),
+        },
+        S::SpectatorRemoved {
+            spectator_id: BOB,
+            game_id: game_id(),
+        },
+        S::SpectatorBecamePlayer {
+            player_id: BOB,
+            username: "bob".into(),
+            game_id: game_id(),
+        },
+        S::SpectatorLeft,
+        S::SelectionUpdate {
+            player_id: ALICE,
+            game_id: game_id(),
+            positions: vec![pos(0, 0), pos(1, 1)],
+        },
+        S::TimerVoteUpdate {
+            state: vote_in_progress(),
+            game_id: game_id(),
+        },
+        S::TurnTimerStarted {
+            target_player_id: ALICE,
+            game_id: game_id(),
+            seconds: 30,
+        },
+        S::TurnTimerExpired {
+            player_id: ALICE,
+            game_id: game_id(),
+        },
+        S::RematchCountdownUpdate {
+            state: RematchCountdownState::Active {
+                expires_at: at(14),
+                seconds_remaining: 15,
+                player_ids: vec![ALICE, BOB],
+                game_type: GameType::Open,
+            },
+            previous_game_id: game_id(),
+        },
+        S::PlayerLeftRematch {
+            player_id: BOB,
+            previous_game_id: game_id(),
+        },
+        S::RematchStarting {
+            triggered_by: Some(ALICE),
+            previous_game_id: game_id(),
+        },
+        S::PlayerPoolChanged {
+            player_id: BOB,
+            old_pool: None,
+            new_pool: Some(GameType::Daily),
+        },
+        S::PoolJoined {
+            position: 2,
+            total_in_pool: 3,
+            game_id: game_id(),
+        },
+        S::PoolUpdate {
+            position: 1,
+            total_in_pool: 3,
+            game_id: game_id(),
+        },
+        S::PoolLeft,
+        S::AdminGamesList {
+            games: vec![admin_game()],
+        },
+        S::AdminGameDeleted {
+            game_id: game_id(),
+            audit: Some(audit(Some("stuck"))),
+        },
+        S::AdminLobbiesList {
+            lobbies: vec![admin_lobby()],
+            page: PageInfo {
+                offset: 50,
+                limit: 25,
+                total: 80,
+                next_offset: Some(75),
+            },
+        },
+        S::AdminLobbyInspected {
+            lobby: lobby_snapshot(),
+        },
+        S::AdminGameInspected {
+            game: GameSnapshot {
+                your_player: None,
+                ..game_snapshot()
+            },
+        },
+        S::AdminPlayerKicked {
+            player_id: ALICE,
+            lobby_id: Some("lobby_1".into()),
+            game_id: Some(game_id()),
+            audit: audit(Some("afk")),
+        },
+        S::AdminPlayerBanned {
+            player_id: ALICE,
+            expires_at: None,
+            audit: audit(Some("abuse")),
+        },
+        S::AdminGameForceEnded {
+            game_id: game_id(),
+            winner: WinnerPolicy::Player { player_id: BOB },
+            winner_ids: vec![BOB],
+            final_scores: vec![score()],
+            audit: audit(Some("server restart")),
+        },
+        S::AdminNewsPushed {
+            news_id: "news_1".into(),
+            recipients: 312,
+            audit: audit(None),
+        },
+        S::GameStateUpdate {
+            game_id: game_id(),
+            state: "in_progress".into(),
+            grid: grid(),
+            players: vec![player_info()],
+            current_turn: ALICE,
+            round: 2,
+            max_rounds: 5,
+            used_words: vec!["RUNE".into()],
+            spectators: vec![spectator()],
+            timer_vote_state: TimerVoteState::Disabled,
+        },
+        S::LobbyStateUpdate {
+            lobby_id: "lobby_1".into(),
+            players: vec![lobby_player()],
+            games: vec![lobby_game()],
+        },
+        S::DebugStateResponse {
+            timestamp: "2026-03-14T10:15:09Z".into(),
+            player: DebugPlayerInfo {
+                user_id: ALICE,
+                username: "alice".into(),
+            },
+            websocket_context: DebugWebsocketContext {
+                lobby_id: Some("lobby_1".into()),
+                game_id: None,
+                is_spectating: false,
+            },
+            lobby_state: Some(debug_lobby()),
+            backend_game_state: Some(DebugBackendGameState::Error {
+                error: "no session".into(),
+            }),
+            handler_game_state: None,
+        },
+        S::NewsAnnounced { item: news_item() },
+        S::Error {
+            code: ErrorCode::LobbyNotFound,
+            message: "Lobby not found".into(),
+            details: Some(serde_json::from_str(r#"{"lobby_code":"ZZZ999"}"#).unwrap()),
+        },
+    ]
+}
+
+/// `tag` of a serialized variant, for naming its fixture.
+fn tag_of<T: Serialize>(value: &T, key: &str) -> String {
+    let value = serde_json::to_value(value).unwrap();
+    value[key]
+        .as_str()
+        .or_else(|| value.get("error").map(|_| "error"))
+        .unwrap_or("found")
+        .to_string()
+}
+
+/// Nested type fixtures, named `Type` or `Type.variant`.
+#[allow(clippy::too_many_lines)]
+fn check_types(corpus: &mut Corpus) {
+    macro_rules! variants {
+        ($corpus:expr, $name:literal, $key:literal, [$($sample:expr),* $(,)?]) => {
+            $(
+                let sample = $sample;
+                let file = format!(concat!("types/", $name, ".{}"), tag_of(&sample, $key));
+                $corpus.check(&file, &sample);
+            )*
+        };
+    }
+
+    corpus.check("types/Position", &pos(2, 3));
+    corpus.check("types/Grid", &grid());
+    corpus.check("types/GridCell", &grid()[0][0]);
+    corpus.check(
+        "types/Multiplier",
+        &vec![
+            Multiplier::DoubleLetter,
+            Multiplier::TripleLetter,
+            Multiplier::DoubleWord,
+        ],
+    );
+    corpus.check(
+        "types/AdventureEventKind",
+        &vec![
+            AdventureEventKind::Bomb,
+            AdventureEventKind::Snake,
+            AdventureEventKind::Ufo,
+            AdventureEventKind::Unknown,
+        ],
+    );
+    corpus.check(
+        "types/GameMode",
+        &vec![GameMode::Solo, GameMode::Multiplayer, GameMode::Adventure],
+    );
+    corpus.check(
+        "types/LobbyType",
+        &vec![LobbyType::Channel, LobbyType::Custom],
+    );
+    corpus.check(
+        "types/GameType",
+        &vec![
+            GameType::Open,
+            GameType::TwoVTwo,
+            GameType::Adventure,
+            GameType::Sandbox,
+            GameType::Daily,
+            GameType::Unknown,
+        ],
+    );
+    corpus.check(
+        "types/GameState",
+        &vec![
+            GameState::Idle,
+            GameState::Queueing,
+            GameState::Starting,
+            GameState::InProgress,
+            GameState::Finished,
+            GameState::Cancelled,
+        ],
+    );
+    corpus.check(
+        "types/ErrorCode",
+        &vec![
+            ErrorCode::NotAuthenticated,
+            ErrorCode::SessionExpired,
+            ErrorCode::InvalidSession,
+            ErrorCode::LobbyNotFound,
+            ErrorCode::LobbyFull,
+            ErrorCode::NotInLobby,
+            ErrorCode::AlreadyInLobby,
+            ErrorCode::GameNotFound,
+            ErrorCode::GameInProgress,
+            ErrorCode::GameNotActive,
+            ErrorCode::NotInGame,
+            ErrorCode::AlreadyInGame,
+            ErrorCode::NotYourTurn,
+            ErrorCode::InvalidAction,
+            ErrorCode::ActionTimeout,
+            ErrorCode::TimerVoteInProgress,
+            ErrorCode::TimerVoteCooldown,
+            ErrorCode::NoTimerVote,
+            ErrorCode::AlreadyVoted,
+            ErrorCode::IsYourTurn,
+            ErrorCode::InvalidPath,
+            ErrorCode::PathTooShort,
+            ErrorCode::WordNotInDictionary,
+            ErrorCode::WordAlreadyUsed,
+            ErrorCode::NotHost,
+            ErrorCode::NotEnoughPlayers,
+            ErrorCode::TooManyPlayers,
+            ErrorCode::NotAuthorized,
+            ErrorCode::InsufficientGems,
+            ErrorCode::PlayerNotFound,
+            ErrorCode::TooManyRequests,
+            ErrorCode::MessageTooLarge,
+            ErrorCode::InvalidRequest,
+            ErrorCode::InternalError,
+        ],
+    );
+    corpus.check(
+        "types/BotDifficulty",
+        &vec![
+            BotDifficulty::Easy,
+            BotDifficulty::Medium,
+            BotDifficulty::Hard,
+        ],
+    );
+    corpus.check(
+        "types/NewsNotificationType",
+        &vec![
+            NewsNotificationType::Maintenance,
+            NewsNotificationType::Announcement,
+            NewsNotificationType::Update,
+            NewsNotificationType::Unknown,
+        ],
+    );
+
+    corpus.check("types/LobbyPlayerInfo", &lobby_player());
+    corpus.check("types/GameSummary", &game_summary());
+    corpus.check("types/PlayerInfo", &player_info());
+    corpus.check("types/GamePlayerInfo", &game_player());
+    corpus.check("types/SpectatorInfo", &spectator());
+    corpus.check("types/ScoreInfo", &score());
+    corpus.check("types/LobbyGamePlayerInfo", &lobby_game().players[0]);
+    corpus.check("types/LobbyGameInfo", &lobby_game());
+    corpus.check("types/LobbySnapshot", &lobby_snapshot());
+    corpus.check("types/GameSnapshot", &game_snapshot());
+    corpus.check("types/AdminGameInfo", &admin_game());
+    corpus.check("types/AdminLobbyInfo", &admin_lobby());
+    corpus.check("types/AdminLobbyFilter", &AdminLobbyFilter::default());
+    corpus.check("types/PageRequest", &PageRequest::default());
+    corpus.check(
+        "types/PageInfo",
+        &PageInfo {
+            offset: 0,
+            limit: 50,
+            total: 3,
+            next_offset: None,
+        },
+    );
+    corpus.check("types/AdminAudit", &audit(Some("afk")));
+    corpus.check(
+        "types/BotSpec",
+        &BotSpec {
+            difficulty: BotDifficulty::Medium,
+        },
+    );
+    corpus.check("types/EventConfig", &game_config().events.unwrap());
+    corpus.check("types/GameConfig", &game_config());
+    corpus.check("types/GameConfig.default", &GameConfig::default());
+    corpus.check("types/CustomMeta", &CustomMeta {});
+    corpus.check("types/LevelTargets", &game_config().level_targets.unwrap());
+    corpus.check(
+        "types/DebugPlayerInfo",
+        &DebugPlayerInfo {
+            user_id: ALICE,
+            username: "alice".into(),
+        },
+    );
+    corpus.check(
+        "types/DebugWebsocketContext",
+        &DebugWebsocketContext {
+            lobby_id: None,
+            game_id: Some(game_id()),
+            is_spectating: true,
+        },
+    );
+    corpus.check("types/NewsItemPayload", &news_item());
+    corpus.check(
+        "types/BuildInfo",
+        &BuildInfo {
+            version: "0.16.0".into(),
+            git_sha: "a1b2c3d".into(),
+            git_describe: "v0.16.0-2-ga1b2c3d".into(),
+            git_branch: "master".into(),
+            dirty: false,
+            built_at: "2026-03-14T10:15:09Z".into(),
+        },
+    );
+
+    variants!(
+        corpus,
+        "TimerVoteState",
+        "status",
+        [
+            TimerVoteState::Idle,
+            vote_in_progress(),
+            TimerVoteState::TimerActive {
+                expires_at: at(12),
+                target_player_id: ALICE,
+            },
+            TimerVoteState::Cooldown { expires_at: at(12) },
+            TimerVoteState::Disabled,
+        ]
+    );
+    variants!(
+        corpus,
+        "RematchCountdownState",
+        "status",
+        [
+            RematchCountdownState::Idle,
+            RematchCountdownState::Active {
+                expires_at: at(14),
+                seconds_remaining: 9,
+                player_ids: vec![ALICE, BOB],
+                game_type: GameType::Sandbox,
+            },
+            RematchCountdownState::Starting { triggered_by: None },
+        ]
+    );
+    variants!(
+        corpus,
+        "WinnerPolicy",
+        "policy",
+        [
+            WinnerPolicy::HighestScore,
+            WinnerPolicy::NoWinner,
+            WinnerPolicy::Player { player_id: ALICE },
+        ]
+    );
+    variants!(
+        corpus,
+        "LobbyChange",
+        "change_type",
+        [
+            LobbyChange::PlayerJoined {
+                player: lobby_player(),
+            },
+            LobbyChange::PlayerLeft {
+                player_id: BOB,
+                reason: Some("kicked".into()),
+            },
+            LobbyChange::PlayerConnectionChanged {
+                player_id: BOB,
+                is_connected: false,
+            },
+            LobbyChange::GameStateChanged {
+                game_id: game_id(),
+                state: GameState::Finished,
+            },
+            LobbyChange::PoolUpdated {
+                game_id: game_id(),
+                pool_count: 4,
+            },
+            LobbyChange::HostChanged {
+                new_host_id: ALICE.to_string(),
+            },
+        ]
+    );
+    variants!(
+        corpus,
+        "GameChange",
+        "change_type",
+        [
+            GameChange::GridUpdated {
+                grid: grid(),
+                replaced_positions: Some(vec![pos(1, 2)]),
+            },
+            GameChange::ScoreUpdated {
+                player_id: ALICE,
+                score: 51,
+                gems: 5,
+            },
+            GameChange::TurnChanged { player_id: BOB },
+            GameChange::RoundChanged { round: 3 },
+            GameChange::WordUsed { word: "SUN".into() },
+            GameChange::SpectatorJoined {
+                spectator: spectator(),
+            },
+            GameChange::SpectatorLeft { spectator_id: BOB },
+            GameChange::PlayerConnectionChanged {
+                player_id: ALICE,
+                is_connected: true,
+            },
+        ]
+    );
+    variants!(
+        corpus,
+        "DebugLobbyState",
+        "",
+        [
+            debug_lobby(),
+            DebugLobbyState::Error {
+                error: "lobby gone".into(),
+            },
+        ]
+    );
+    variants!(
+        corpus,
+        "DebugBackendGameState",
+        "",
+        [
+            debug_backend_game(),
+            DebugBackendGameState::Error {
+                error: "no session".into(),
+            },
+        ]
+    );
+    variants!(
+        corpus,
+        "DebugHandlerGameState",
+        "",
+        [
+            debug_handler_game(),
+            DebugHandlerGameState::Error {
+                error: "no handler".into(),
+            },
+        ]
+    );
+}
+
+fn check_envelopes(corpus: &mut Corpus) {
+    corpus.check(
+        "envelope/client_enveloped",
+        &MaybeEnveloped::Enveloped(Envelope {
+            seq: 42,
+            ack: Some(41),
+            timestamp: 1_773_497_709_000,
+            payload: ClientMessage::Heartbeat,
+        }),
+    );
+    corpus.check(
+        "envelope/client_raw",
+        &MaybeEnveloped::Raw(ClientMessage::PassTurn { game_id: game_id() }),
+    );
+    corpus.check(
+        "envelope/server",
+        &Envelope {
+            seq: 7,
+            ack: None,
+            timestamp: 1_773_497_709_000,
+            payload: ServerMessage::HeartbeatAck {
+                server_time: 1_773_497_709_000,
+            },
+        },
+    );
+}
+
+#[test]
+fn golden_wire_corpus() {
+    let mut corpus = Corpus::new();
+    for msg in client_samples() {
+        corpus.check(&format!("client/{}", msg.message_type()), &msg);
+    }
+    for msg in server_samples() {
+        let tag = tag_of(&msg, "type");
+        corpus.check(&format!("server/{tag}"), &msg);
+    }
+    check_types(&mut corpus);
+    check_envelopes(&mut corpus);
+    corpus.check_legacy();
+    corpus.finish();
+}
+
+#[test]
+fn samples_cover_every_client_message() {
+    let sampled: BTreeSet<&str> = client_samples()
+        .iter()
+        .map(ClientMessage::message_type)
+        .collect();
+    let expected: BTreeSet<&str> = EXCHANGES.iter().map(|e| e.request).collect();
+    assert_eq!(sampled, expected);
+    for msg in client_samples() {
+        assert_eq!(msg.exchange().request, msg.message_type());
+        let policy = permission::policy_for(msg.message_type()).unwrap();
+        assert_eq!(policy.permission, msg.permission());
+    }
+    let policies: BTreeSet<&str> = PERMISSIONS.iter().map(|p| p.request).collect();
+    assert_eq!(sampled, policies);
+}
+
+#[test]
+fn samples_are_distinct_per_variant() {
+    let server = server_samples();
+    let tags: BTreeSet<String> = server.iter().map(|m| tag_of(m, "type")).collect();
+    assert_eq!(tags.len(), server.len());
+    assert_eq!(client_samples().len(), EXCHANGES.len());
+}
+
+/// Every schema'd type and message variant has a fixture.
+#[cfg(feature = "schema")]
+#[test]
+fn samples_cover_every_schema() {
+    use schemars::schema_for;
+
+    fn tags(schema: &schemars::Schema) -> BTreeSet<String> {
+        schema.as_value()["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|b| b["properties"]["type"]["const"].as_str())
+            .map(String::from)
+            .collect()
+    }
+
+    let server: BTreeSet<String> = server_samples().iter().map(|m| tag_of(m, "type")).collect();
+    assert_eq!(server, tags(&schema_for!(ServerMessage)));
+    let client: BTreeSet<String> = client_samples()
+        .iter()
+        .map(|m| m.message_type().to_string())
+        .collect();
+    assert_eq!(client, tags(&schema_for!(ClientMessage)));
+
+    let types_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(ROOT)
+        .join("types");
+    let fixtures: BTreeSet<String> = json_files(&types_dir)
+        .iter()
+        .filter_map(|p| p.file_stem()?.to_str()?.split('.').next().map(String::from))
+        .collect();
+    for name in super::schema::schemas().keys() {
+        let covered = match *name {
+            "ClientMessage" | "ServerMessage" | "ClientEnvelope" | "ServerEnvelope" => true,
+            other => fixtures.contains(other),
+        };
+        assert!(covered, "no fixture for {name}");
+    }
+}
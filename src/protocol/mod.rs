@@ -13,6 +13,15 @@
 //! │  types.rs        - Shared data types (Grid, Position, etc.)         │
 //! │  client_messages - Client → Server message definitions              │
 //! │  server_messages - Server → Client message definitions              │
+//! │  exchange.rs     - Request → reply/error pairings                   │
+//! │  system.rs       - Server-synthesized handler inputs                │
+//! │  error.rs        - Client frame parse errors                        │
+//! │  permission.rs   - Who may send each client message                 │
+//! │  version.rs      - Wire versions, upgraders and downgraders         │
+//! │  adapter.rs      - Per-connection output shaping                    │
+//! │  schema.rs       - JSON Schema export (feature `schema`)            │
+//! │  typescript.rs   - TypeScript bindings (feature `typescript`)       │
+//! │  asyncapi.rs     - AsyncAPI document (feature `asyncapi`)           │
 //! └─────────────────────────────────────────────────────────────────────┘
 //! ```
 //!
@@ -56,16 +65,36 @@
 //! 2. Compatibility functions convert between old and new formats
 //! 3. Once migration is complete, remove legacy module
 
+pub mod adapter;
+#[cfg(feature = "asyncapi")]
+pub mod asyncapi;
 pub mod client_messages;
 pub mod envelope;
+pub mod error;
+pub mod exchange;
+#[cfg(test)]
+mod golden;
+pub mod permission;
+#[cfg(feature = "schema")]
+pub mod schema;
 pub mod server_messages;
+pub mod system;
 pub mod types;
+#[cfg(feature = "typescript")]
+pub mod typescript;
+pub mod version;
 
 // Re-export main types for convenient access
+pub use adapter::{Capabilities, OutputAdapter};
 pub use client_messages::ClientMessage;
 pub use envelope::{Envelope, MaybeEnveloped};
+pub use error::ProtocolError;
+pub use exchange::{Exchange, EXCHANGES};
+pub use permission::{authorize, Permission, PERMISSIONS};
 pub use server_messages::{LobbySnapshot, ServerMessage};
+pub use system::{HandlerInput, SystemMessage};
 pub use types::*;
+pub use version::{VersionPipeline, CURRENT_WIRE_VERSION};
 
 // ============================================================================
 // Protocol Constants
@@ -94,7 +123,12 @@ pub const PROTOCOL_VERSION: &str = "1.0.0";
 ///
 /// This allows gradual migration without breaking the existing frontend.
 pub mod compat {
-    use super::{ClientMessage, Envelope, GameSnapshot, GameState, MaybeEnveloped, ServerMessage};
+    use super::server_messages::default_max_players;
+    use super::system::reject_server_only;
+    use super::{
+        ClientMessage, Envelope, GameSnapshot, GameState, LobbySnapshot, LobbyType, MaybeEnveloped,
+        ProtocolError, ServerMessage,
+    };
     use serde_json::Value;
 
     /// Parse a raw JSON message, handling both legacy and new formats.
@@ -103,10 +137,12 @@ pub mod compat {
     ///
     /// # Errors
     ///
-    /// Returns a `serde_json::Error` if the message cannot be parsed.
+    /// [`ProtocolError::ServerOnly`] for server-only message types such as
+    /// `player_disconnected`, and [`ProtocolError::Json`] for anything else
+    /// that cannot be parsed.
     pub fn parse_client_message(
         json: &str,
-    ) -> Result<(ClientMessage, Option<u64>, Option<u64>), serde_json::Error> {
+    ) -> Result<(ClientMessage, Option<u64>, Option<u64>), ProtocolError> {
         // First try to parse as enveloped
         if let Ok(enveloped) = serde_json::from_str::<MaybeEnveloped<ClientMessage>>(json) {
             match enveloped {
@@ -120,8 +156,13 @@ pub mod compat {
         }
 
         // Fall back to legacy parsing
-        let msg: ClientMessage = serde_json::from_str(json)?;
-        Ok((msg, None, None))
+        match serde_json::from_str::<ClientMessage>(json) {
+            Ok(msg) => Ok((msg, None, None)),
+            Err(e) => {
+                reject_server_only(&serde_json::from_str(json)?)?;
+                Err(e.into())
+            }
+        }
     }
 
     /// Serialize a server message, optionally wrapping in an envelope.
@@ -226,6 +267,66 @@ pub mod compat {
             timer_vote_state: snapshot.timer_vote_state.clone(),
         }
     }
+
+    /// Convert a legacy `lobby_state` message to a `LobbySnapshot`.
+    ///
+    /// The legacy message only carries `lobby_id`, `players` and `games`.
+    /// The other snapshot fields are filled in as follows:
+    ///
+    /// - `lobby_type`: `Custom` if the message has a `lobby_code` (some
+    ///   servers attached one), otherwise `Channel`, the only kind of lobby
+    ///   that existed when `lobby_state` was introduced.
+    /// - `host_id`: `None`; legacy lobbies had no host.
+    /// - `sandbox_config`: `None`; sandbox games are newer than the format.
+    /// - `max_players`: the snapshot default (6).
+    ///
+    /// `lobby_id` may be a string or a number (old channel ids); a missing
+    /// `games` list means no games.
+    #[must_use]
+    pub fn legacy_lobby_state_to_snapshot(value: &Value) -> Option<LobbySnapshot> {
+        let lobby_id = match value.get("lobby_id")? {
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            _ => return None,
+        };
+        let lobby_code = value
+            .get("lobby_code")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        Some(LobbySnapshot {
+            lobby_id,
+            lobby_type: if lobby_code.is_some() {
+                LobbyType::Custom
+            } else {
+                LobbyType::Channel
+            },
+            lobby_code,
+            players: serde_json::from_value(value.get("players")?.clone()).ok()?,
+            games: match value.get("games") {
+                Some(games) => serde_json::from_value(games.clone()).ok()?,
+                None => Vec::new(),
+            },
+            max_players: default_max_players(),
+            sandbox_config: None,
+            host_id: None,
+        })
+    }
+
+    /// Convert a `LobbySnapshot` to the legacy `lobby_state` message.
+    ///
+    /// `lobby_type`, `lobby_code`, `host_id`, `sandbox_config` and
+    /// `max_players` are dropped: legacy clients have no host-only controls
+    /// or sandbox UI, and learn that a lobby is full from the `LobbyFull`
+    /// error when joining.
+    #[must_use]
+    pub fn snapshot_to_legacy_lobby_state(snapshot: &LobbySnapshot) -> ServerMessage {
+        ServerMessage::LobbyStateUpdate {
+            lobby_id: snapshot.lobby_id.clone(),
+            players: snapshot.players.clone(),
+            games: snapshot.games.clone(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -267,6 +368,65 @@ mod tests {
         assert!(json.contains("payload"));
     }
 
+    fn lobby_snapshot() -> LobbySnapshot {
+        LobbySnapshot {
+            lobby_type: LobbyType::Custom,
+            lobby_code: Some("ABC123".into()),
+            players: vec![LobbyPlayerInfo {
+                user_id: 42,
+                username: "ada".into(),
+                avatar_url: None,
+                banner_url: None,
+                accent_color: None,
+                current_game_pool: Some(GameType::Open),
+                active_game_id: None,
+                spectate_game_id: None,
+            }],
+            max_players: 4,
+            host_id: Some(42),
+            ..crate::testing::lobby_snapshot("123456789012345678")
+        }
+    }
+
+    #[test]
+    fn test_lobby_snapshot_to_legacy_and_back() {
+        let legacy = compat::snapshot_to_legacy_lobby_state(&lobby_snapshot());
+        let value = serde_json::to_value(&legacy).unwrap();
+        assert_eq!(value["type"], "lobby_state");
+        assert!(value.get("host_id").is_none());
+
+        let back = compat::legacy_lobby_state_to_snapshot(&value).unwrap();
+        assert_eq!(back.lobby_id, "123456789012345678");
+        assert_eq!(back.players.len(), 1);
+        assert_eq!(back.players[0].user_id, 42);
+        // Fields with no legacy equivalent fall back to the documented policy.
+        assert_eq!(back.lobby_type, LobbyType::Channel);
+        assert_eq!(back.lobby_code, None);
+        assert_eq!(back.host_id, None);
+        assert_eq!(back.max_players, 6);
+        assert!(back.sandbox_config.is_none());
+    }
+
+    #[test]
+    fn test_legacy_lobby_state_variants() {
+        let numeric: serde_json::Value =
+            serde_json::from_str(r#"{"lobby_id":987654321,"players":[]}"#).unwrap();
+        let snap = compat::legacy_lobby_state_to_snapshot(&numeric).unwrap();
+        assert_eq!(snap.lobby_id, "987654321");
+        assert!(snap.games.is_empty());
+
+        let custom: serde_json::Value = serde_json::from_str(
+            r#"{"lobby_id":"l1","lobby_code":"XYZ789","players":[],"games":[]}"#,
+        )
+        .unwrap();
+        let snap = compat::legacy_lobby_state_to_snapshot(&custom).unwrap();
+        assert_eq!(snap.lobby_type, LobbyType::Custom);
+        assert_eq!(snap.lobby_code.as_deref(), Some("XYZ789"));
+
+        let missing: serde_json::Value = serde_json::from_str(r#"{"players":[]}"#).unwrap();
+        assert!(compat::legacy_lobby_state_to_snapshot(&missing).is_none());
+    }
+
     #[test]
     fn test_constants() {
         assert_eq!(HEARTBEAT_INTERVAL_MS, 30_000);
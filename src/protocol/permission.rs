@@ -0,0 +1,247 @@
+//! Who may send each client message.
+//!
+//! [`PERMISSIONS`] gives every client message tag a [`Permission`], and
+//! [`authorize`] checks a message against the sender and their lobby before
+//! it reaches a handler:
+//!
+//! ```text
+//! AdminGetGames     ──▶ Admin  ──▶ Error(NotAuthorized) unless is_admin()
+//! SetSandboxConfig  ──▶ Host   ──▶ Error(NotHost) unless lobby.host_id
+//! ```
+//!
+//! Server-synthesized events are not client messages at all; see
+//! [`SystemMessage`](super::SystemMessage).
+//!
+//! With the `tracing` feature each check runs in a `validate` span, and
+//! denials are logged at `DEBUG`.
+//!
+//! Only the sender's role is checked here. Lobby, game and turn state are
+//! left to the handlers (see [`ClientMessage::requires_lobby`] and friends).
+
+use super::types::ErrorCode;
+use super::{ClientMessage, LobbySnapshot};
+use crate::player::PlayerContext;
+
+/// Who may send a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    /// Any connected player.
+    Anyone,
+    /// The host of the sender's current lobby.
+    Host,
+    /// Players with admin privileges.
+    Admin,
+}
+
+impl Permission {
+    /// The error a sender who lacks this permission gets.
+    #[must_use]
+    pub fn denied_with(self) -> Option<ErrorCode> {
+        match self {
+            Self::Anyone => None,
+            Self::Host => Some(ErrorCode::NotHost),
+            Self::Admin => Some(ErrorCode::NotAuthorized),
+        }
+    }
+}
+
+/// The permission for one client message type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    /// The client message tag, e.g. `"admin_get_games"`.
+    pub request: &'static str,
+    pub permission: Permission,
+}
+
+const fn policy(request: &'static str, permission: Permission) -> Policy {
+    Policy {
+        request,
+        permission,
+    }
+}
+
+use Permission::{Admin, Anyone, Host};
+
+/// Declares [`PERMISSIONS`] and [`ClientMessage::permission`] from one
+/// list, so a variant without a policy fails to compile.
+macro_rules! permissions {
+    ($($variant:ident => ($request:literal, $permission:ident)),* $(,)?) => {
+        /// Every client message type, in declaration order.
+        pub const PERMISSIONS: &[Policy] = &[$(policy($request, $permission)),*];
+
+        impl ClientMessage {
+            /// Who may send this message; see [`PERMISSIONS`].
+            #[must_use]
+            pub fn permission(&self) -> Permission {
+                match self {
+                    $(Self::$variant { .. } => $permission,)*
+                }
+            }
+        }
+    };
+}
+
+permissions! {
+    // Connection
+    Identify => ("identify", Anyone),
+    Heartbeat => ("heartbeat", Anyone),
+    Ack => ("ack", Anyone),
+    RequestSync => ("request_sync", Anyone),
+    DebugState => ("debug_state", Anyone),
+    // Lobby
+    JoinChannelLobby => ("join_channel_lobby", Anyone),
+    CreateCustomLobby => ("create_custom_lobby", Anyone),
+    JoinCustomLobby => ("join_custom_lobby", Anyone),
+    LeaveLobby => ("leave_lobby", Anyone),
+    JoinGamePool => ("join_game_pool", Anyone),
+    LeaveGamePool => ("leave_game_pool", Anyone),
+    // Game lifecycle. `start_game` is open by default; servers that restrict
+    // it to the host check that themselves.
+    CreateGame => ("create_game", Anyone),
+    SetSandboxConfig => ("set_sandbox_config", Host),
+    TriggerAdventureEvent => ("trigger_adventure_event", Host),
+    StartGame => ("start_game", Anyone),
+    // Game actions
+    SubmitWord => ("submit_word", Anyone),
+    PassTurn => ("pass_turn", Anyone),
+    ShuffleBoard => ("shuffle_board", Anyone),
+    EnterSwapMode => ("enter_swap_mode", Anyone),
+    ExitSwapMode => ("exit_swap_mode", Anyone),
+    SwapTile => ("swap_tile", Anyone),
+    // Spectating
+    SpectateGame => ("spectate_game", Anyone),
+    JoinGame => ("join_game", Anyone),
+    LeaveSpectator => ("leave_spectator", Anyone),
+    LeaveGame => ("leave_game", Anyone),
+    SelectionUpdate => ("selection_update", Anyone),
+    // Timer vote
+    InitiateTimerVote => ("initiate_timer_vote", Anyone),
+    VoteForTimer => ("vote_for_timer", Anyone),
+    // Rematch
+    TriggerRematch => ("trigger_rematch", Anyone),
+    LeaveRematch => ("leave_rematch", Anyone),
+    // Admin
+    AdminGetGames => ("admin_get_games", Admin),
+    AdminDeleteGame => ("admin_delete_game", Admin),
+    AdminListLobbies => ("admin_list_lobbies", Admin),
+    AdminInspectLobby => ("admin_inspect_lobby", Admin),
+    AdminInspectGame => ("admin_inspect_game", Admin),
+    AdminKickPlayer => ("admin_kick_player", Admin),
+    AdminBanPlayer => ("admin_ban_player", Admin),
+    AdminForceEndGame => ("admin_force_end_game", Admin),
+    AdminPushNews => ("admin_push_news", Admin),
+}
+
+/// Look up the policy for a client message tag.
+#[must_use]
+pub fn policy_for(message_type: &str) -> Option<&'static Policy> {
+    PERMISSIONS.iter().find(|p| p.request == message_type)
+}
+
+/// Check that `ctx` may send `msg`.
+///
+/// `lobby` is the sender's current lobby; host-only messages are allowed
+/// when `ctx` is in that lobby and is its `host_id`. Admins get no host
+/// rights.
+///
+/// # Errors
+///
+/// `NotHost` for host-only messages from anyone else, and `NotAuthorized`
+/// for admin-only messages from non-admins.
+pub fn authorize(
+    msg: &ClientMessage,
+    ctx: &dyn PlayerContext,
+    lobby: &LobbySnapshot,
+) -> Result<(), ErrorCode> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!(
+        "validate",
+        message_type = msg.message_type(),
+        player_id = ctx.player_id(),
+        lobby_id = ctx.lobby_id(),
+        game_id = ctx.game_id(),
+    )
+    .entered();
+    let permission = msg.permission();
+    let allowed = match permission {
+        Anyone => true,
+        Host => {
+            ctx.lobby_id() == Some(lobby.lobby_id.as_str())
+                && lobby.host_id == Some(ctx.player_id())
+        }
+        Admin => ctx.is_admin(),
+    };
+    let result = match permission.denied_with() {
+        Some(code) if !allowed => Err(code),
+        _ => Ok(()),
+    };
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok(()) => tracing::trace!(?permission, "allowed"),
+        Err(code) => tracing::debug!(?permission, ?code, "denied"),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::protocol::{AdventureEventKind, LobbyType, EXCHANGES};
+    use crate::testing::{lobby_snapshot, MockPlayerContext};
+
+    fn lobby(host_id: i64) -> LobbySnapshot {
+        LobbySnapshot {
+            lobby_type: LobbyType::Custom,
+            lobby_code: Some("ABC123".into()),
+            host_id: Some(host_id),
+            ..lobby_snapshot("l1")
+        }
+    }
+
+    #[test]
+    fn every_message_type_has_one_policy() {
+        let policies: Vec<&str> = PERMISSIONS.iter().map(|p| p.request).collect();
+        let unique: BTreeSet<&str> = policies.iter().copied().collect();
+        assert_eq!(unique.len(), policies.len(), "duplicate policy");
+        let expected: BTreeSet<&str> = EXCHANGES.iter().map(|e| e.request).collect();
+        assert_eq!(unique, expected);
+    }
+
+    #[test]
+    fn host_only_messages() {
+        let msg = ClientMessage::TriggerAdventureEvent {
+            kind: AdventureEventKind::Bomb,
+        };
+        let host = MockPlayerContext::new(1).with_lobby("l1");
+        let guest = MockPlayerContext::new(2).with_lobby("l1");
+        let admin = MockPlayerContext::new(3).with_lobby("l1").with_admin(true);
+        let elsewhere = MockPlayerContext::new(1).with_lobby("l2");
+
+        assert_eq!(authorize(&msg, &host, &lobby(1)), Ok(()));
+        assert_eq!(authorize(&msg, &guest, &lobby(1)), Err(ErrorCode::NotHost));
+        assert_eq!(authorize(&msg, &admin, &lobby(1)), Err(ErrorCode::NotHost));
+        assert_eq!(
+            authorize(&msg, &elsewhere, &lobby(1)),
+            Err(ErrorCode::NotHost)
+        );
+    }
+
+    #[test]
+    fn admin_only_messages() {
+        let admin = MockPlayerContext::new(1).with_admin(true);
+        let player = MockPlayerContext::new(2);
+        let list = ClientMessage::AdminGetGames;
+        assert_eq!(authorize(&list, &admin, &lobby(9)), Ok(()));
+        assert_eq!(
+            authorize(&list, &player, &lobby(9)),
+            Err(ErrorCode::NotAuthorized)
+        );
+
+        assert_eq!(
+            authorize(&ClientMessage::Heartbeat, &player, &lobby(1)),
+            Ok(())
+        );
+    }
+}
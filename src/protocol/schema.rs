@@ -0,0 +1,198 @@
+//! JSON Schema export for the wire types (feature `schema`).
+//!
+//! Schemas are derived from the same serde attributes that drive the wire
+//! format, so they can't drift from it:
+//!
+//! - `DisplayFromStr` ids (`i64` as string) come out as `"type": "string"`
+//!   via `serde_with`'s schemars integration.
+//! - `#[serde(tag = "type" | "status" | "change_type")]` enums become a
+//!   `oneOf` whose branches pin the tag with `const`.
+//! - Enums with a `#[serde(other)]` fallback accept any string; see
+//!   [`open_enum`].
+//!
+//! Run `cargo run --features schema --bin export-schemas -- <dir>` to write
+//! one `<Type>.schema.json` per entry in [`schemas`].
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use schemars::{schema_for, Schema};
+use serde_json::{json, Map, Value};
+
+use super::types::{
+    AdminAudit, AdminGameInfo, AdminLobbyFilter, AdminLobbyInfo, AdventureEventKind, BotDifficulty,
+    BotSpec, BuildInfo, CustomMeta, DebugBackendGameState, DebugHandlerGameState, DebugLobbyState,
+    DebugPlayerInfo, DebugWebsocketContext, ErrorCode, EventConfig, GameChange, GameConfig,
+    GameMode, GamePlayerInfo, GameSnapshot, GameState, GameSummary, GameType, GridCell,
+    LevelTargets, LobbyChange, LobbyGameInfo, LobbyGamePlayerInfo, LobbyPlayerInfo, LobbyType,
+    Multiplier, NewsItemPayload, NewsNotificationType, PageInfo, PageRequest, PlayerInfo, Position,
+    RematchCountdownState, ScoreInfo, SpectatorInfo, TimerVoteState, WinnerPolicy,
+};
+use super::{ClientMessage, Envelope, LobbySnapshot, MaybeEnveloped, ServerMessage};
+
+/// Marker added to [`open_enum`] schemas so generators can tell a
+/// forward-compatible enum from a plain string.
+pub const OPEN_ENUM_KEY: &str = "x-open-enum";
+
+/// Schema transform for enums with a `#[serde(other)]` fallback.
+///
+/// The derived schema only lists the known variants, but serde accepts any
+/// string (decoding unknown ones to the fallback). Wrap it as
+/// `anyOf: [<known>, {"type": "string"}]` so validators agree with serde
+/// while the known values stay visible to tooling.
+pub fn open_enum(schema: &mut Schema) {
+    let mut known = std::mem::take(schema).to_value();
+    let mut open = Map::new();
+    if let Some(obj) = known.as_object_mut() {
+        for key in ["title", "description"] {
+            if let Some(v) = obj.remove(key) {
+                open.insert(key.to_string(), v);
+            }
+        }
+    }
+    open.insert("anyOf".into(), json!([known, { "type": "string" }]));
+    open.insert(OPEN_ENUM_KEY.into(), Value::Bool(true));
+    *schema = Schema::from(open);
+}
+
+macro_rules! schema_map {
+    ($($name:literal => $ty:ty),* $(,)?) => {{
+        let mut map = BTreeMap::new();
+        $(map.insert($name, schema_for!($ty));)*
+        map
+    }};
+}
+
+/// A standalone schema for every wire type, keyed by type name.
+///
+/// Each schema carries its dependencies in `$defs`, so any single file can
+/// be used on its own.
+#[must_use]
+pub fn schemas() -> BTreeMap<&'static str, Schema> {
+    schema_map! {
+        "ClientMessage" => ClientMessage,
+        "ServerMessage" => ServerMessage,
+        "ClientEnvelope" => MaybeEnveloped<ClientMessage>,
+        "ServerEnvelope" => Envelope<ServerMessage>,
+        "LobbySnapshot" => LobbySnapshot,
+        "Position" => Position,
+        "Multiplier" => Multiplier,
+        "GridCell" => GridCell,
+        "AdventureEventKind" => AdventureEventKind,
+        "GameMode" => GameMode,
+        "LobbyType" => LobbyType,
+        "GameType" => GameType,
+        "LobbyPlayerInfo" => LobbyPlayerInfo,
+        "GameSummary" => GameSummary,
+        "GameState" => GameState,
+        "PlayerInfo" => PlayerInfo,
+        "GamePlayerInfo" => GamePlayerInfo,
+        "SpectatorInfo" => SpectatorInfo,
+        "ScoreInfo" => ScoreInfo,
+        "LobbyGamePlayerInfo" => LobbyGamePlayerInfo,
+        "LobbyGameInfo" => LobbyGameInfo,
+        "GameSnapshot" => GameSnapshot,
+        "TimerVoteState" => TimerVoteState,
+        "RematchCountdownState" => RematchCountdownState,
+        "LobbyChange" => LobbyChange,
+        "GameChange" => GameChange,
+        "AdminGameInfo" => AdminGameInfo,
+        "AdminLobbyInfo" => AdminLobbyInfo,
+        "AdminLobbyFilter" => AdminLobbyFilter,
+        "PageRequest" => PageRequest,
+        "PageInfo" => PageInfo,
+        "WinnerPolicy" => WinnerPolicy,
+        "AdminAudit" => AdminAudit,
+        "ErrorCode" => ErrorCode,
+        "BotDifficulty" => BotDifficulty,
+        "BotSpec" => BotSpec,
+        "EventConfig" => EventConfig,
+        "GameConfig" => GameConfig,
+        "CustomMeta" => CustomMeta,
+        "LevelTargets" => LevelTargets,
+        "DebugPlayerInfo" => DebugPlayerInfo,
+        "DebugWebsocketContext" => DebugWebsocketContext,
+        "DebugLobbyState" => DebugLobbyState,
+        "DebugBackendGameState" => DebugBackendGameState,
+        "DebugHandlerGameState" => DebugHandlerGameState,
+        "NewsNotificationType" => NewsNotificationType,
+        "NewsItemPayload" => NewsItemPayload,
+        "BuildInfo" => BuildInfo,
+    }
+}
+
+/// Write every schema from [`schemas`] to `dir` as pretty-printed
+/// `<Type>.schema.json`, creating `dir` if needed. Returns the paths written.
+///
+/// # Errors
+///
+/// Returns any I/O error from creating the directory or writing a file.
+pub fn write_schemas(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    schemas()
+        .into_iter()
+        .map(|(name, schema)| {
+            let path = dir.join(format!("{name}.schema.json"));
+            let mut text = serde_json::to_string_pretty(&schema).map_err(io::Error::from)?;
+            text.push('\n');
+            std::fs::write(&path, text)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `oneOf` branch of a tagged enum whose tag property is `tag`.
+    fn branch<'a>(schema: &'a Value, prop: &str, tag: &str) -> &'a Value {
+        schema["oneOf"]
+            .as_array()
+            .expect("tagged enum is a oneOf")
+            .iter()
+            .find(|b| b["properties"][prop]["const"] == tag)
+            .unwrap_or_else(|| panic!("no branch with {prop} = {tag}"))
+    }
+
+    #[test]
+    fn ids_are_strings() {
+        let schema = schema_for!(ServerMessage).to_value();
+        let scored = branch(&schema, "type", "word_scored");
+        assert_eq!(scored["properties"]["player_id"]["type"], "string");
+        assert_eq!(scored["properties"]["score"]["type"], "integer");
+    }
+
+    #[test]
+    fn tag_discriminators() {
+        let client = schema_for!(ClientMessage).to_value();
+        branch(&client, "type", "submit_word");
+
+        let timer = schema_for!(TimerVoteState).to_value();
+        branch(&timer, "status", "vote_in_progress");
+
+        let change = schema_for!(GameChange).to_value();
+        branch(&change, "change_type", "grid_updated");
+    }
+
+    #[test]
+    fn serde_other_enums_accept_any_string() {
+        let schema = schema_for!(GameType).to_value();
+        assert_eq!(schema[OPEN_ENUM_KEY], true);
+        let any_of = schema["anyOf"].as_array().unwrap();
+        assert_eq!(any_of[1], json!({ "type": "string" }));
+        assert!(any_of[0].to_string().contains("two_v_two"));
+        assert!(schema.get("description").is_some());
+    }
+
+    #[test]
+    fn every_schema_is_written() {
+        let dir = std::env::temp_dir().join(format!("runecast-schemas-{}", std::process::id()));
+        let written = write_schemas(&dir).unwrap();
+        assert_eq!(written.len(), schemas().len());
+        let text = std::fs::read_to_string(dir.join("ClientMessage.schema.json")).unwrap();
+        assert!(text.ends_with("}\n"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
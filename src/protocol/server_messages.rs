@@ -16,16 +16,18 @@ use serde::{Deserialize, Serialize};
 use crate::protocol::GameType;
 
 use super::types::{
-    AdminGameInfo, AdventureEventKind, CustomMeta, DebugBackendGameState, DebugHandlerGameState,
-    DebugLobbyState, DebugPlayerInfo, DebugWebsocketContext, ErrorCode, GameChange, GameConfig,
-    GamePlayerInfo, GameSnapshot, Grid, LobbyChange, LobbyGameInfo, LobbyPlayerInfo, LobbyType,
-    NewsItemPayload, NewsNotificationType, PlayerInfo, Position, RematchCountdownState, ScoreInfo,
-    SpectatorInfo, TimerVoteState,
+    AdminAudit, AdminGameInfo, AdminLobbyInfo, AdventureEventKind, CustomMeta,
+    DebugBackendGameState, DebugHandlerGameState, DebugLobbyState, DebugPlayerInfo,
+    DebugWebsocketContext, ErrorCode, GameChange, GameConfig, GamePlayerInfo, GameSnapshot,
+    GameState, Grid, LobbyChange, LobbyGameInfo, LobbyPlayerInfo, LobbyType, NewsItemPayload,
+    PageInfo, PlayerInfo, Position, RematchCountdownState, ScoreInfo, SpectatorInfo,
+    TimerVoteState, WinnerPolicy,
 };
 
 /// Messages sent from server to client.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ServerMessage {
     // ========================================================================
@@ -303,8 +305,9 @@ pub enum ServerMessage {
         game_id: String,
         round: u8,
         max_rounds: u8,
-        /// New grid if board was regenerated at the start of this round.
-        /// Only present when the game is configured with `regenerate_board_each_round: true`.
+        /// New grid if the board changed at the start of this round: either
+        /// regenerated (`regenerate_board_each_round: true`) or adventure
+        /// effects (poison, abduction) expired. Omitted when unchanged.
         #[serde(skip_serializing_if = "Option::is_none")]
         new_grid: Option<Grid>,
     },
@@ -490,7 +493,64 @@ pub enum ServerMessage {
     AdminGamesList { games: Vec<AdminGameInfo> },
 
     /// Game was deleted by admin.
-    AdminGameDeleted { game_id: String },
+    AdminGameDeleted {
+        game_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        audit: Option<AdminAudit>,
+    },
+
+    /// Response to `AdminListLobbies`.
+    AdminLobbiesList {
+        lobbies: Vec<AdminLobbyInfo>,
+        page: PageInfo,
+    },
+
+    /// Response to `AdminInspectLobby`.
+    AdminLobbyInspected { lobby: LobbySnapshot },
+
+    /// Response to `AdminInspectGame`. `your_player` is always absent.
+    AdminGameInspected { game: GameSnapshot },
+
+    /// A player was kicked by admin.
+    AdminPlayerKicked {
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        player_id: i64,
+        /// The lobby they were removed from, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        lobby_id: Option<String>,
+        /// The game they were removed from, if any
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        game_id: Option<String>,
+        audit: AdminAudit,
+    },
+
+    /// A player was banned (and kicked) by admin.
+    AdminPlayerBanned {
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        player_id: i64,
+        /// When the ban lifts; `None` if permanent
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        audit: AdminAudit,
+    },
+
+    /// A game was ended early by admin.
+    AdminGameForceEnded {
+        game_id: String,
+        winner: WinnerPolicy,
+        /// Who the policy made the winners (empty for `no_winner`)
+        #[serde_as(as = "Vec<serde_with::DisplayFromStr>")]
+        winner_ids: Vec<i64>,
+        final_scores: Vec<ScoreInfo>,
+        audit: AdminAudit,
+    },
+
+    /// A news item was announced by admin.
+    AdminNewsPushed {
+        news_id: String,
+        /// Connected clients it was sent to
+        recipients: u32,
+        audit: AdminAudit,
+    },
 
     // ========================================================================
     // Legacy Compatibility Messages
@@ -646,6 +706,13 @@ impl ServerMessage {
             Self::PoolLeft => "pool_left",
             Self::AdminGamesList { .. } => "admin_games_list",
             Self::AdminGameDeleted { .. } => "admin_game_deleted",
+            Self::AdminLobbiesList { .. } => "admin_lobbies_list",
+            Self::AdminLobbyInspected { .. } => "admin_lobby_inspected",
+            Self::AdminGameInspected { .. } => "admin_game_inspected",
+            Self::AdminPlayerKicked { .. } => "admin_player_kicked",
+            Self::AdminPlayerBanned { .. } => "admin_player_banned",
+            Self::AdminGameForceEnded { .. } => "admin_game_force_ended",
+            Self::AdminNewsPushed { .. } => "admin_news_pushed",
             Self::GameStateUpdate { .. } => "game_state",
             Self::LobbyStateUpdate { .. } => "lobby_state",
             Self::DebugStateResponse { .. } => "debug_state_response",
@@ -714,6 +781,7 @@ impl TryFrom<serde_json::Value> for ServerMessage {
 /// Complete lobby state snapshot.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LobbySnapshot {
     pub lobby_id: String,
     pub lobby_type: LobbyType,
@@ -738,14 +806,42 @@ pub struct LobbySnapshot {
     pub host_id: Option<i64>,
 }
 
-fn default_max_players() -> u8 {
+impl LobbySnapshot {
+    /// Apply one delta change, as a client receiving `LobbyDelta` would.
+    ///
+    /// The snapshot has no per-player connection flag or pool counts, so
+    /// `PlayerConnectionChanged` and `PoolUpdated` leave it unchanged; a
+    /// game reaching `Finished` or `Cancelled` is removed from `games`.
+    pub fn apply_change(&mut self, change: &LobbyChange) {
+        match change {
+            LobbyChange::PlayerJoined { player } => {
+                self.players.retain(|p| p.user_id != player.user_id);
+                self.players.push(player.clone());
+            }
+            LobbyChange::PlayerLeft { player_id, .. } => {
+                self.players.retain(|p| p.user_id != *player_id);
+            }
+            LobbyChange::GameStateChanged { game_id, state } => {
+                if matches!(state, GameState::Finished | GameState::Cancelled) {
+                    self.games.retain(|g| g.game_id != *game_id);
+                }
+            }
+            LobbyChange::HostChanged { new_host_id } => {
+                self.host_id = new_host_id.parse().ok();
+            }
+            LobbyChange::PlayerConnectionChanged { .. } | LobbyChange::PoolUpdated { .. } => {}
+        }
+    }
+}
+
+pub(crate) fn default_max_players() -> u8 {
     6
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::protocol::{types, GameType};
+    use crate::protocol::{types, GameType, NewsNotificationType};
 
     #[test]
     fn test_server_message_into_json() {
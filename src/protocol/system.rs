@@ -0,0 +1,157 @@
+//! Handler inputs the server produces itself.
+//!
+//! Some events reach game and lobby handlers without a client sending
+//! anything, such as a socket closing. They are [`SystemMessage`]s, kept
+//! off the wire so a client cannot forge them; handlers take a
+//! [`HandlerInput`], which is one or the other:
+//!
+//! ```text
+//! socket frame ──parse──▶ ClientMessage ──┐
+//!                                         ├──▶ HandlerInput ──▶ handler
+//! socket closed ────────▶ SystemMessage ──┘
+//! ```
+//!
+//! Client frames carrying a system message type are rejected with
+//! [`ProtocolError::ServerOnly`].
+
+use serde_json::Value;
+
+use super::error::ProtocolError;
+use super::ClientMessage;
+
+/// An event the server synthesizes for its handlers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SystemMessage {
+    /// A player's WebSocket closed unexpectedly.
+    ///
+    /// Handlers start the reconnect grace period and schedule cleanup.
+    PlayerDisconnected {
+        /// The lobby the player was in (if any)
+        lobby_id: Option<String>,
+        /// The game the player was in (if any)
+        game_id: Option<String>,
+    },
+}
+
+impl SystemMessage {
+    /// Get the message type as a string (for logging/debugging).
+    #[must_use]
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            Self::PlayerDisconnected { .. } => "player_disconnected",
+        }
+    }
+}
+
+/// Everything a handler can be asked to process.
+#[derive(Debug, Clone)]
+pub enum HandlerInput {
+    /// Sent by the player.
+    Client(ClientMessage),
+    /// Synthesized by the server on the player's behalf.
+    System(SystemMessage),
+}
+
+impl HandlerInput {
+    /// The wrapped message's type.
+    #[must_use]
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            Self::Client(msg) => msg.message_type(),
+            Self::System(msg) => msg.message_type(),
+        }
+    }
+
+    /// The client message, if a client sent this.
+    #[must_use]
+    pub fn as_client(&self) -> Option<&ClientMessage> {
+        match self {
+            Self::Client(msg) => Some(msg),
+            Self::System(_) => None,
+        }
+    }
+}
+
+impl From<ClientMessage> for HandlerInput {
+    fn from(msg: ClientMessage) -> Self {
+        Self::Client(msg)
+    }
+}
+
+impl From<SystemMessage> for HandlerInput {
+    fn from(msg: SystemMessage) -> Self {
+        Self::System(msg)
+    }
+}
+
+/// Message types that only the server may produce.
+pub const SERVER_ONLY_TYPES: &[&str] = &["player_disconnected"];
+
+/// Whether `message_type` is a [`SystemMessage`] tag.
+#[must_use]
+pub fn is_server_only(message_type: &str) -> bool {
+    SERVER_ONLY_TYPES.contains(&message_type)
+}
+
+/// Reject a raw or enveloped client frame that names a server-only type.
+pub(crate) fn reject_server_only(frame: &Value) -> Result<(), ProtocolError> {
+    let tag = frame
+        .get("payload")
+        .and_then(|payload| payload.get("type"))
+        .or_else(|| frame.get("type"))
+        .and_then(Value::as_str);
+    match tag {
+        Some(tag) if is_server_only(tag) => Err(ProtocolError::ServerOnly(tag.to_string())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::compat::parse_client_message;
+    use crate::protocol::VersionPipeline;
+
+    #[test]
+    fn system_messages_are_server_only() {
+        let msg = SystemMessage::PlayerDisconnected {
+            lobby_id: Some("l1".into()),
+            game_id: None,
+        };
+        assert!(is_server_only(msg.message_type()));
+        let input = HandlerInput::from(msg);
+        assert_eq!(input.message_type(), "player_disconnected");
+        assert!(input.as_client().is_none());
+        assert!(HandlerInput::from(ClientMessage::Heartbeat)
+            .as_client()
+            .is_some());
+    }
+
+    #[test]
+    fn wire_parsing_rejects_forged_disconnects() {
+        let raw = r#"{"type":"player_disconnected","lobby_id":"l1"}"#;
+        let enveloped = r#"{"seq":1,"ts":0,"payload":{"type":"player_disconnected"}}"#;
+        for frame in [raw, enveloped] {
+            let err = parse_client_message(frame).unwrap_err();
+            assert!(
+                matches!(&err, ProtocolError::ServerOnly(tag) if tag == "player_disconnected"),
+                "{err}"
+            );
+            assert_eq!(err.kind(), "server_only");
+            assert!(matches!(
+                VersionPipeline::default().parse_client_message(frame),
+                Err(ProtocolError::ServerOnly(_))
+            ));
+        }
+        assert_eq!(
+            parse_client_message(r#"{"type":"nope"}"#)
+                .unwrap_err()
+                .kind(),
+            "invalid_message"
+        );
+        assert_eq!(
+            parse_client_message("{").unwrap_err().kind(),
+            "malformed_json"
+        );
+    }
+}
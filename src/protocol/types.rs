@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 
 /// Grid position (row, column).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Position {
     pub row: usize,
     pub col: usize,
@@ -18,6 +19,7 @@ pub struct Position {
 
 /// Letter multiplier on a grid cell.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Multiplier {
     DoubleLetter,
@@ -26,7 +28,8 @@ pub enum Multiplier {
 }
 
 /// A single cell in the game grid.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GridCell {
     pub letter: char,
     pub value: u8,
@@ -68,6 +71,8 @@ pub type Grid = Vec<Vec<GridCell>>;
 /// matching on this enum should always handle the `Unknown` arm (e.g.
 /// by rendering a generic "Something happened!" toast).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(transform = crate::protocol::schema::open_enum))]
 #[serde(rename_all = "snake_case")]
 pub enum AdventureEventKind {
     /// 3×3 blast centered on a random cell; those cells reroll.
@@ -84,6 +89,7 @@ pub enum AdventureEventKind {
 
 /// Game mode variants.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum GameMode {
     Solo,
@@ -98,6 +104,7 @@ pub enum GameMode {
 
 /// Type of lobby - determines how players join.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum LobbyType {
     /// Lobby tied to a specific Discord channel
@@ -111,6 +118,8 @@ pub enum LobbyType {
 /// Each lobby can have multiple game pools, one per game type. Players wait in pools for matchmaking.
 /// Players join a game pool to find matches for that game type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(transform = crate::protocol::schema::open_enum))]
 #[serde(rename_all = "snake_case")]
 pub enum GameType {
     /// Free-for-all open game (default)
@@ -144,6 +153,7 @@ impl std::fmt::Display for GameType {
 /// Player information in the lobby (pre-game).
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LobbyPlayerInfo {
     /// User ID
     #[serde_as(as = "serde_with::DisplayFromStr")]
@@ -168,6 +178,7 @@ pub struct LobbyPlayerInfo {
 
 /// Summary of a game visible from the lobby.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GameSummary {
     pub game_id: String,
     pub state: GameState,
@@ -179,6 +190,7 @@ pub struct GameSummary {
 
 /// High-level game state (not the full game data).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum GameState {
     /// Waiting for players / not started
@@ -202,6 +214,7 @@ pub enum GameState {
 /// Player information during a game.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PlayerInfo {
     /// User ID (string to preserve JS number precision)
     #[serde_as(as = "serde_with::DisplayFromStr")]
@@ -228,6 +241,7 @@ fn default_true() -> bool {
 /// Player info specifically for `GameStarted` message (includes turn order).
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GamePlayerInfo {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     pub user_id: i64,
@@ -245,6 +259,7 @@ pub struct GamePlayerInfo {
 /// Spectator information.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SpectatorInfo {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     pub user_id: i64,
@@ -256,6 +271,7 @@ pub struct SpectatorInfo {
 /// Score information for results/leaderboards.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScoreInfo {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     pub user_id: i64,
@@ -266,6 +282,7 @@ pub struct ScoreInfo {
 /// Player info in lobby game list (simplified).
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LobbyGamePlayerInfo {
     #[serde_as(as = "serde_with::DisplayFromStr")]
     pub user_id: i64,
@@ -275,6 +292,7 @@ pub struct LobbyGamePlayerInfo {
 
 /// Game info as shown in lobby games list.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LobbyGameInfo {
     pub game_id: String,
     /// Which game type / pool this game belongs to (Open/Sandbox/Adventure),
@@ -292,6 +310,7 @@ pub struct LobbyGameInfo {
 /// Complete snapshot of the game state.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GameSnapshot {
     pub game_id: String,
     pub state: GameState,
@@ -321,6 +340,74 @@ pub struct GameSnapshot {
     pub time_remaining_ms: Option<u64>,
 }
 
+impl GameSnapshot {
+    /// An idle game with no grid or players, for filling in from events.
+    #[must_use]
+    pub fn empty(game_id: impl Into<String>) -> Self {
+        Self {
+            game_id: game_id.into(),
+            state: GameState::Idle,
+            grid: Vec::new(),
+            players: Vec::new(),
+            spectators: Vec::new(),
+            current_turn: 0,
+            round: 0,
+            max_rounds: 0,
+            used_words: Vec::new(),
+            timer_vote_state: TimerVoteState::Idle,
+            your_player: None,
+            timer_expiration_time: None,
+            custom: None,
+            time_remaining_ms: None,
+        }
+    }
+
+    /// Apply one delta change, as a client receiving `GameDelta` would.
+    /// Changes naming unknown players are ignored.
+    pub fn apply_change(&mut self, change: &GameChange) {
+        match change {
+            GameChange::GridUpdated { grid, .. } => self.grid.clone_from(grid),
+            GameChange::ScoreUpdated {
+                player_id,
+                score,
+                gems,
+            } => {
+                if let Some(p) = self.player_mut(*player_id) {
+                    p.score = *score;
+                    p.gems = *gems;
+                }
+            }
+            GameChange::TurnChanged { player_id } => self.current_turn = *player_id,
+            GameChange::RoundChanged { round } => self.round = *round,
+            GameChange::WordUsed { word } => self.used_words.push(word.clone()),
+            GameChange::SpectatorJoined { spectator } => {
+                if !self
+                    .spectators
+                    .iter()
+                    .any(|s| s.user_id == spectator.user_id)
+                {
+                    self.spectators.push(spectator.clone());
+                }
+            }
+            GameChange::SpectatorLeft { spectator_id } => {
+                self.spectators.retain(|s| s.user_id != *spectator_id);
+            }
+            GameChange::PlayerConnectionChanged {
+                player_id,
+                is_connected,
+            } => {
+                if let Some(p) = self.player_mut(*player_id) {
+                    p.is_connected = *is_connected;
+                }
+            }
+        }
+    }
+
+    fn player_mut(&mut self, id: i64) -> Option<&mut PlayerInfo> {
+        self.players.iter_mut().find(|p| p.user_id == id)
+    }
+}
+
 // ============================================================================
 // Timer Vote Types
 // ============================================================================
@@ -331,6 +418,7 @@ pub struct GameSnapshot {
 /// on the current player. This prevents indefinite stalling.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum TimerVoteState {
     /// No vote in progress, button is idle
@@ -380,6 +468,7 @@ pub enum TimerVoteState {
 /// Any player can trigger an early start, or opt out to return to the lobby.
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum RematchCountdownState {
     /// No rematch countdown active
@@ -415,6 +504,7 @@ pub enum RematchCountdownState {
 /// Changes to lobby state (for delta updates instead of full snapshots).
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "change_type", rename_all = "snake_case")]
 pub enum LobbyChange {
     /// A player joined the lobby
@@ -448,6 +538,7 @@ pub enum LobbyChange {
 /// Changes to game state (for delta updates).
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "change_type", rename_all = "snake_case")]
 pub enum GameChange {
     /// Grid was updated (after word submission)
@@ -502,12 +593,176 @@ pub enum GameChange {
 /// Admin game info (for admin panel).
 #[serde_with::serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AdminGameInfo {
     pub game_id: String,
     pub state: GameState,
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde_as(as = "Vec<serde_with::DisplayFromStr>")]
     pub players: Vec<i64>,
+    /// Lobby the game belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lobby_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_type: Option<GameType>,
+    #[serde(default)]
+    pub spectator_count: u32,
+}
+
+/// Admin lobby info, as listed by `AdminListLobbies`.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdminLobbyInfo {
+    pub lobby_id: String,
+    pub lobby_type: LobbyType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lobby_code: Option<String>,
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_id: Option<i64>,
+    #[serde_as(as = "Vec<serde_with::DisplayFromStr>")]
+    pub players: Vec<i64>,
+    pub games: Vec<GameSummary>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl AdminLobbyInfo {
+    /// Whether any of the lobby's games is starting or in progress.
+    #[must_use]
+    pub fn has_active_game(&self) -> bool {
+        self.games
+            .iter()
+            .any(|g| matches!(g.state, GameState::Starting | GameState::InProgress))
+    }
+}
+
+/// Which lobbies `AdminListLobbies` returns. Unset fields match everything.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdminLobbyFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lobby_type: Option<LobbyType>,
+    /// Only lobbies this player is in
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_id: Option<i64>,
+    /// Only lobbies with (`true`) or without (`false`) an active game
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_active_game: Option<bool>,
+}
+
+impl AdminLobbyFilter {
+    /// Whether `lobby` passes every set field.
+    #[must_use]
+    pub fn matches(&self, lobby: &AdminLobbyInfo) -> bool {
+        self.lobby_type.is_none_or(|t| t == lobby.lobby_type)
+            && self.player_id.is_none_or(|id| lobby.players.contains(&id))
+            && self
+                .has_active_game
+                .is_none_or(|active| active == lobby.has_active_game())
+    }
+}
+
+/// The largest page an admin list returns, whatever the request asks for.
+pub const MAX_PAGE_LIMIT: u32 = 200;
+
+fn default_page_limit() -> u32 {
+    50
+}
+
+/// Offset pagination for admin lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PageRequest {
+    /// Items to skip
+    #[serde(default)]
+    pub offset: u32,
+    /// Items to return, clamped to `1..=`[`MAX_PAGE_LIMIT`]
+    #[serde(default = "default_page_limit")]
+    pub limit: u32,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: default_page_limit(),
+        }
+    }
+}
+
+impl PageRequest {
+    /// Take this page out of `items`, which should already be filtered
+    /// and sorted.
+    #[must_use]
+    pub fn slice<T>(&self, items: Vec<T>) -> (Vec<T>, PageInfo) {
+        let total = u32::try_from(items.len()).unwrap_or(u32::MAX);
+        let limit = self.limit.clamp(1, MAX_PAGE_LIMIT);
+        let page: Vec<T> = items
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(limit as usize)
+            .collect();
+        let end = self.offset.saturating_add(limit);
+        let info = PageInfo {
+            offset: self.offset,
+            limit,
+            total,
+            next_offset: (end < total).then_some(end),
+        };
+        (page, info)
+    }
+}
+
+/// Where a returned page sits in the full list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PageInfo {
+    pub offset: u32,
+    /// The limit applied (after clamping)
+    pub limit: u32,
+    /// Matching items across all pages
+    pub total: u32,
+    /// Offset of the next page, if there is one
+    pub next_offset: Option<u32>,
+}
+
+/// Who won a game an admin ends early.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum WinnerPolicy {
+    /// Whoever leads on score (ties share the win)
+    HighestScore,
+    /// Nobody; the game is recorded as abandoned
+    NoWinner,
+    /// A specific player in the game
+    Player {
+        #[serde_as(as = "serde_with::DisplayFromStr")]
+        player_id: i64,
+    },
+}
+
+/// Audit trail attached to the result of every admin action.
+///
+/// Servers fill it in when the action succeeds; it is meant to be logged
+/// as is.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdminAudit {
+    /// Server-assigned id for this action
+    pub action_id: String,
+    /// The admin who performed it
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub admin_id: i64,
+    pub performed_at: chrono::DateTime<chrono::Utc>,
+    /// The reason given with the request, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
 }
 
 // ============================================================================
@@ -515,7 +770,8 @@ pub struct AdminGameInfo {
 // ============================================================================
 
 /// Standard error codes for protocol errors.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ErrorCode {
     // Connection errors
@@ -541,6 +797,18 @@ pub enum ErrorCode {
     InvalidAction,
     ActionTimeout,
 
+    // Timer vote errors
+    /// A vote is running or its turn timer is already counting down.
+    TimerVoteInProgress,
+    /// A failed vote is cooling down; try again later.
+    TimerVoteCooldown,
+    /// `VoteForTimer` with no vote in progress.
+    NoTimerVote,
+    /// Caller already voted (the initiator counts as having voted).
+    AlreadyVoted,
+    /// The player whose turn it is can't start or join a vote on themselves.
+    IsYourTurn,
+
     // Word submission errors
     InvalidPath,
     PathTooShort,
@@ -559,6 +827,10 @@ pub enum ErrorCode {
     // Resource errors
     InsufficientGems,
 
+    // Admin errors
+    /// The target player is not connected or does not exist.
+    PlayerNotFound,
+
     // Rate limiting
     TooManyRequests,
     MessageTooLarge,
@@ -588,6 +860,11 @@ impl ErrorCode {
             Self::NotYourTurn => "It's not your turn",
             Self::InvalidAction => "Invalid action",
             Self::ActionTimeout => "Action timed out",
+            Self::TimerVoteInProgress => "A timer vote is already active",
+            Self::TimerVoteCooldown => "Timer vote is cooling down",
+            Self::NoTimerVote => "No timer vote in progress",
+            Self::AlreadyVoted => "You have already voted",
+            Self::IsYourTurn => "You can't do this on your own turn",
             Self::InvalidPath => "Invalid path - letters must be adjacent",
             Self::PathTooShort => "Word must be at least 3 letters",
             Self::WordNotInDictionary => "Word not found in dictionary",
@@ -597,6 +874,7 @@ impl ErrorCode {
             Self::TooManyPlayers => "Too many players",
             Self::NotAuthorized => "You are not authorized to do this",
             Self::InsufficientGems => "Not enough gems",
+            Self::PlayerNotFound => "Player not found",
             Self::TooManyRequests => "Too many requests",
             Self::MessageTooLarge => "Message too large",
             Self::InvalidRequest => "Invalid request",
@@ -618,6 +896,7 @@ impl std::fmt::Display for ErrorCode {
 /// Bot difficulty on the wire. Lowercase to match the backend's
 /// `game_players.bot_difficulty` CHECK constraint (`easy|medium|hard`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum BotDifficulty {
     Easy,
@@ -627,6 +906,7 @@ pub enum BotDifficulty {
 
 /// One bot seat requested for a custom game.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BotSpec {
     pub difficulty: BotDifficulty,
 }
@@ -637,6 +917,7 @@ pub struct BotSpec {
 
 /// Random-event config for a custom (sandbox) game. Absent for FFA/Adventure.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EventConfig {
     /// Kinds eligible to fire (ambient or fire-now). Empty = none.
     #[serde(default)]
@@ -650,6 +931,7 @@ pub struct EventConfig {
 ///
 /// These options customize game behavior for a single game session.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct GameConfig {
     /// If true, regenerate the entire board at the start of each round.
     /// Default is false (board persists across rounds).
@@ -715,6 +997,7 @@ impl Default for GameConfig {
 /// (not a bare bool) so we can carry custom metadata later without a
 /// wire break.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CustomMeta {}
 
 /// Per-level score thresholds for Adventure Mode star awards.
@@ -722,6 +1005,7 @@ pub struct CustomMeta {}
 /// Hitting `one_star` completes the level; `two_star` and `three_star`
 /// are progressive bonuses. Stars are awarded server-side at session end.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LevelTargets {
     pub one_star: i32,
     pub two_star: i32,
@@ -734,6 +1018,7 @@ pub struct LevelTargets {
 
 /// Player info in debug state response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DebugPlayerInfo {
     pub user_id: i64,
     pub username: String,
@@ -741,6 +1026,7 @@ pub struct DebugPlayerInfo {
 
 /// WebSocket connection context in debug state response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DebugWebsocketContext {
     pub lobby_id: Option<String>,
     pub game_id: Option<String>,
@@ -749,6 +1035,7 @@ pub struct DebugWebsocketContext {
 
 /// Lobby state in debug state response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum DebugLobbyState {
     Found {
@@ -764,6 +1051,7 @@ pub enum DebugLobbyState {
 
 /// Backend game state in debug state response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum DebugBackendGameState {
     Found {
@@ -781,6 +1069,7 @@ pub enum DebugBackendGameState {
 
 /// Handler game state in debug state response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum DebugHandlerGameState {
     Found {
@@ -805,6 +1094,8 @@ pub enum DebugHandlerGameState {
 /// `Unknown` instead of erroring out. Mirrors the
 /// `AdventureEventKind::Unknown` pattern already in this crate.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(transform = crate::protocol::schema::open_enum))]
 #[serde(rename_all = "snake_case")]
 pub enum NewsNotificationType {
     Maintenance,
@@ -853,6 +1144,7 @@ impl From<&String> for NewsNotificationType {
 /// compatible with what `/api/news` returns, so the same UI code path
 /// can render either source.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct NewsItemPayload {
     pub id: String,
     pub title: String,
@@ -875,6 +1167,7 @@ pub struct NewsItemPayload {
 /// rather than failing a build, so a build without git context still produces a
 /// well-formed value.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct BuildInfo {
     /// Semver from the manifest (Cargo.toml / package.json), e.g. `"0.16.0"`.
     pub version: String,
@@ -1575,4 +1868,76 @@ mod tests {
         let back: GameSnapshot = serde_json::from_str(&json).unwrap();
         assert_eq!(back.time_remaining_ms, Some(45_000));
     }
+
+    #[test]
+    fn admin_lobby_filter_and_pagination() {
+        let lobby = |id: &str, lobby_type, players: Vec<i64>, state| AdminLobbyInfo {
+            lobby_id: id.into(),
+            lobby_type,
+            lobby_code: None,
+            host_id: None,
+            players,
+            games: vec![GameSummary {
+                game_id: format!("{id}_game"),
+                state,
+                current_round: 1,
+                max_rounds: 3,
+                player_count: 2,
+                spectator_count: 0,
+            }],
+            created_at: chrono::DateTime::UNIX_EPOCH,
+        };
+        let lobbies = [
+            lobby("a", LobbyType::Channel, vec![1, 2], GameState::InProgress),
+            lobby("b", LobbyType::Custom, vec![2], GameState::Finished),
+            lobby("c", LobbyType::Custom, vec![3], GameState::Starting),
+        ];
+        let filter = |filter: AdminLobbyFilter| -> Vec<&str> {
+            lobbies
+                .iter()
+                .filter(|l| filter.matches(l))
+                .map(|l| l.lobby_id.as_str())
+                .collect()
+        };
+        assert_eq!(filter(AdminLobbyFilter::default()), ["a", "b", "c"]);
+        let in_game_with_2 = AdminLobbyFilter {
+            player_id: Some(2),
+            has_active_game: Some(true),
+            ..AdminLobbyFilter::default()
+        };
+        assert_eq!(filter(in_game_with_2), ["a"]);
+        let idle_custom = AdminLobbyFilter {
+            lobby_type: Some(LobbyType::Custom),
+            has_active_game: Some(false),
+            ..AdminLobbyFilter::default()
+        };
+        assert_eq!(filter(idle_custom), ["b"]);
+
+        // Omitted fields fall back to the first page of 50.
+        let page: PageRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(page, PageRequest::default());
+        let (items, info) = PageRequest {
+            offset: 2,
+            limit: 2,
+        }
+        .slice((0..5).collect());
+        assert_eq!(items, [2, 3]);
+        assert_eq!(info.next_offset, Some(4));
+        let (items, info) = PageRequest {
+            offset: 4,
+            limit: 1_000,
+        }
+        .slice((0..5).collect());
+        assert_eq!(items, [4]);
+        assert_eq!((info.limit, info.total), (MAX_PAGE_LIMIT, 5));
+        assert_eq!(info.next_offset, None);
+        // A zero limit still makes progress.
+        let (items, info) = PageRequest {
+            offset: 1,
+            limit: 0,
+        }
+        .slice((0..5).collect());
+        assert_eq!(items, [1]);
+        assert_eq!((info.limit, info.next_offset), (1, Some(2)));
+    }
 }
@@ -0,0 +1,309 @@
+//! TypeScript bindings for the browser client (feature `typescript`).
+//!
+//! The bindings are rendered from the JSON Schemas in
+//! [`schema`](super::schema), generated with the *serialize* contract so
+//! they describe exactly what serde puts on the wire:
+//!
+//! - internally tagged enums become discriminated unions on their
+//!   snake_case tag (`type`, `status`, `change_type`)
+//! - `DisplayFromStr` ids are `string`
+//! - fields with `skip_serializing_if` are optional (`field?: T`); plain
+//!   `Option` fields are always present and typed `T | null`
+//! - `#[serde(other)]` enums add `(string & {})` so unknown values type-check
+//!
+//! The generated file is checked in at [`BINDINGS_PATH`]; regenerate it with
+//! `cargo run --features typescript --bin gen-ts`. A test fails when the
+//! checked-in copy is stale.
+
+use std::fmt::Write as _;
+
+use schemars::generate::SchemaSettings;
+use serde_json::{Map, Value};
+
+use super::schema::OPEN_ENUM_KEY;
+use super::{ClientMessage, LobbySnapshot, ServerMessage};
+
+/// Where the checked-in bindings live, relative to the crate root.
+pub const BINDINGS_PATH: &str = "bindings/runecast-protocol.ts";
+
+const HEADER: &str = "\
+// Generated by `cargo run --features typescript --bin gen-ts`. Do not edit.
+// Source of truth: the Rust types in runecast-protocol.
+
+/** Delivery wrapper; see `Envelope<T>` in envelope.rs. */
+export interface Envelope<T> {
+  seq: number;
+  ack?: number;
+  ts: number;
+  payload: T;
+}
+
+/** A message either wrapped in an envelope or sent raw (legacy). */
+export type MaybeEnveloped<T> = Envelope<T> | T;
+";
+
+/// Render the TypeScript bindings for every type reachable from
+/// `ClientMessage`, `ServerMessage` and `LobbySnapshot`.
+#[must_use]
+pub fn generate() -> String {
+    let mut generator = SchemaSettings::default().for_serialize().into_generator();
+    generator.subschema_for::<ClientMessage>();
+    generator.subschema_for::<ServerMessage>();
+    generator.subschema_for::<LobbySnapshot>();
+    let definitions = generator.take_definitions(true);
+
+    let mut names: Vec<&String> = definitions.keys().collect();
+    names.sort();
+
+    let mut out = String::from(HEADER);
+    for name in names {
+        out.push('\n');
+        declaration(&mut out, name, &definitions[name.as_str()]);
+    }
+    out
+}
+
+fn declaration(out: &mut String, name: &str, schema: &Value) {
+    doc_comment(out, schema, 0);
+    if schema.get("properties").is_some() {
+        let _ = writeln!(out, "export interface {name} {}", object_body(schema, 0));
+    } else {
+        let body = ts_type(schema, 0);
+        let sep = if body.starts_with('\n') { "" } else { " " };
+        let _ = writeln!(out, "export type {name} ={sep}{body};");
+    }
+}
+
+fn doc_comment(out: &mut String, schema: &Value, indent: usize) {
+    let Some(text) = schema.get("description").and_then(Value::as_str) else {
+        return;
+    };
+    let pad = "  ".repeat(indent);
+    let text = text.replace("*/", "*\\/");
+    let lines: Vec<&str> = text.lines().collect();
+    if let [line] = lines.as_slice() {
+        let _ = writeln!(out, "{pad}/** {line} */");
+    } else {
+        let _ = writeln!(out, "{pad}/**");
+        for line in lines {
+            let _ = writeln!(
+                out,
+                "{pad} *{}{line}",
+                if line.is_empty() { "" } else { " " }
+            );
+        }
+        let _ = writeln!(out, "{pad} */");
+    }
+}
+
+/// `{ ... }` for an object schema, one property per line.
+fn object_body(schema: &Value, indent: usize) -> String {
+    let empty = Map::new();
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    if properties.is_empty() {
+        return "Record<string, never>".into();
+    }
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    // Discriminator (`const`) properties first, then the rest by name.
+    let mut ordered: Vec<(&String, &Value)> = properties.iter().collect();
+    ordered.sort_by_key(|(key, prop)| (prop.get("const").is_none(), key.as_str()));
+
+    let pad = "  ".repeat(indent + 1);
+    let mut out = String::from("{\n");
+    for (key, prop) in ordered {
+        doc_comment(&mut out, prop, indent + 1);
+        let mut ty = ts_type(prop, indent + 1);
+        let optional = if required.contains(&key.as_str()) {
+            ""
+        } else {
+            // Skipped when `None`, so `null` never reaches the wire.
+            if let Some(inner) = ty.strip_suffix(" | null") {
+                ty = inner.to_string();
+            }
+            "?"
+        };
+        let _ = writeln!(out, "{pad}{}{optional}: {ty};", property_key(key));
+    }
+    let _ = write!(out, "{}}}", "  ".repeat(indent));
+    out
+}
+
+fn property_key(key: &str) -> String {
+    let is_ident = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_ident {
+        key.to_string()
+    } else {
+        Value::String(key.into()).to_string()
+    }
+}
+
+fn ts_type(schema: &Value, indent: usize) -> String {
+    let Some(obj) = schema.as_object() else {
+        // `true` (anything) or `false` (nothing).
+        return if schema == &Value::Bool(false) {
+            "never"
+        } else {
+            "unknown"
+        }
+        .into();
+    };
+
+    if let Some(reference) = obj.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    if let Some(value) = obj.get("const") {
+        return value.to_string();
+    }
+    if let Some(values) = obj.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if obj.get(OPEN_ENUM_KEY) == Some(&Value::Bool(true)) {
+        if let Some(known) = obj.get("anyOf").and_then(|a| a.get(0)) {
+            return format!("{} | (string & {{}})", ts_type(known, indent));
+        }
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(branches) = obj.get(key).and_then(Value::as_array) {
+            return union(branches, indent);
+        }
+    }
+    if obj.contains_key("properties") {
+        return object_body(schema, indent);
+    }
+
+    match obj.get("type") {
+        Some(Value::String(t)) => primitive(t, obj, indent),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|t| primitive(t, obj, indent))
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => "unknown".into(),
+    }
+}
+
+fn primitive(t: &str, obj: &Map<String, Value>, indent: usize) -> String {
+    match t {
+        "string" => "string".into(),
+        "integer" | "number" => "number".into(),
+        "boolean" => "boolean".into(),
+        "null" => "null".into(),
+        "array" => match obj.get("items") {
+            Some(items) => {
+                let inner = ts_type(items, indent);
+                if inner.contains(' ') {
+                    format!("({inner})[]")
+                } else {
+                    format!("{inner}[]")
+                }
+            }
+            None => "unknown[]".into(),
+        },
+        "object" => match obj.get("additionalProperties") {
+            Some(Value::Bool(false)) | None => "Record<string, never>".into(),
+            Some(values) => format!("Record<string, {}>", ts_type(values, indent)),
+        },
+        _ => "unknown".into(),
+    }
+}
+
+/// A union, one branch per line when any branch is an object or documented.
+fn union(branches: &[Value], indent: usize) -> String {
+    let rendered: Vec<(String, &Value)> = branches
+        .iter()
+        .map(|b| (ts_type(b, indent + 1), b))
+        .collect();
+    if rendered
+        .iter()
+        .all(|(t, b)| !t.contains('\n') && b.get("description").is_none())
+    {
+        return rendered
+            .into_iter()
+            .map(|(t, _)| t)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    let pad = "  ".repeat(indent + 1);
+    let mut out = String::new();
+    for (t, branch) in rendered {
+        // Undocumented unit variants arrive grouped in one `enum`; give
+        // each its own line like their documented siblings.
+        if let Some(values) = branch.get("enum").and_then(Value::as_array) {
+            if branch.get("description").is_none() {
+                for value in values {
+                    let _ = write!(out, "\n{pad}| {value}");
+                }
+                continue;
+            }
+        }
+        out.push('\n');
+        doc_comment(&mut out, branch, indent + 1);
+        let _ = write!(out, "{pad}| {t}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discriminated_unions_use_snake_case_tags() {
+        let ts = generate();
+        assert!(ts.contains("export type ServerMessage ="));
+        assert!(ts.contains("type: \"word_scored\";"));
+        assert!(ts.contains("status: \"vote_in_progress\";"));
+        assert!(ts.contains("change_type: \"grid_updated\";"));
+    }
+
+    #[test]
+    fn ids_are_strings_and_skipped_fields_optional() {
+        let ts = generate();
+        let snapshot = ts
+            .split("export interface LobbySnapshot {")
+            .nth(1)
+            .and_then(|rest| rest.split("\n}").next())
+            .unwrap();
+        assert!(snapshot.contains("  host_id?: string;"));
+        assert!(snapshot.contains("  lobby_id: string;"));
+        assert!(snapshot.contains("  lobby_code?: string;"));
+    }
+
+    #[test]
+    fn serde_other_enums_stay_open() {
+        let ts = generate();
+        assert!(ts.contains("| \"unknown\" | (string & {})"), "{ts}");
+    }
+
+    #[test]
+    fn checked_in_bindings_are_fresh() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_PATH);
+        let on_disk = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            on_disk == generate(),
+            "{} is stale; run `cargo run --features typescript --bin gen-ts`",
+            path.display()
+        );
+    }
+}
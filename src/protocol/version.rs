@@ -0,0 +1,394 @@
+//! Wire versions and the upgrade/downgrade pipeline.
+//!
+//! Clients that were loaded before a deploy keep running the old bundle
+//! (Discord Activities are rarely reloaded), so the server has to accept
+//! their payloads and speak their dialect until they refresh.
+//!
+//! Every inbound message has a wire version, either declared with a `"v"`
+//! field (on the envelope, or on the message itself when sent raw) or
+//! inferred:
+//!
+//! | Version | Shape |
+//! |---------|-------|
+//! | 1 | Pre-envelope frontend. Raw messages; ids may be JSON numbers; state arrives as flat `game_state` / `lobby_state`. |
+//! | 2 | Current. Enveloped; ids are strings; snapshots and deltas. |
+//!
+//! An undeclared enveloped message is version 2 (envelopes arrived with
+//! it); an undeclared raw message is treated as version 1, which is safe
+//! because every upgrader leaves current shapes untouched.
+//!
+//! ```text
+//! inbound:  Value@v1 ──upgrader(1)──▶ Value@v2 ──serde──▶ ClientMessage
+//! outbound: ServerMessage@v2 ──downgrader(1)?──▶ capabilities(v1) ──▶ frame (or dropped)
+//! ```
+//!
+//! Outbound messages are rendered by an [`OutputAdapter`] from
+//! [`VersionPipeline::output_adapter`]: registered downgraders rewrite
+//! newer shapes first, then [`Capabilities::for_version`] covers envelopes,
+//! snapshots, deltas and grid flags. The built-in 2 → 1 step needs no
+//! downgrader; a service adding a version registers one alongside its
+//! upgrader.
+//!
+//! [`VersionPipeline::default`] registers the built-in steps; services can
+//! add their own with [`VersionPipeline::with_upgrader`] and
+//! [`VersionPipeline::with_downgrader`].
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use super::system::reject_server_only;
+use super::{Capabilities, ClientMessage, Envelope, OutputAdapter, ProtocolError, ServerMessage};
+
+/// The wire version this crate speaks natively.
+pub const CURRENT_WIRE_VERSION: u32 = 2;
+
+/// The oldest wire version the default pipeline can still serve.
+pub const OLDEST_WIRE_VERSION: u32 = 1;
+
+/// Field carrying a declared wire version.
+pub const VERSION_KEY: &str = "v";
+
+/// Rewrite a message `Value` from version `n` to `n + 1`.
+pub type Upgrader = fn(Value) -> Result<Value, VersionError>;
+
+/// Render a version `n + 1` message for a version `n` client, or `None`
+/// when that version has no equivalent and the message should be dropped.
+pub type Downgrader = fn(ServerMessage) -> Option<ServerMessage>;
+
+/// An inbound message lifted to the current version.
+#[derive(Debug, Clone)]
+pub struct Upgraded {
+    pub message: ClientMessage,
+    /// Envelope sequence number, if the message was enveloped.
+    pub seq: Option<u64>,
+    /// Piggybacked acknowledgment, if any.
+    pub ack: Option<u64>,
+    /// The version the client spoke.
+    pub version: u32,
+    /// Whether `version` was declared (`true`) or inferred.
+    pub declared: bool,
+}
+
+/// Errors from [`VersionPipeline`].
+#[derive(Debug)]
+pub enum VersionError {
+    /// The text is not JSON, or the upgraded value is not a valid message.
+    Json(serde_json::Error),
+    /// The declared `"v"` is not a non-negative integer.
+    InvalidVersion(Value),
+    /// The version is outside what this pipeline can serve.
+    Unsupported(u32),
+    /// No upgrader is registered between these versions.
+    MissingStep { from: u32, to: u32 },
+    /// An upgrader rejected the payload.
+    Upgrade { from: u32, reason: String },
+}
+
+impl std::fmt::Display for VersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid message: {e}"),
+            Self::InvalidVersion(v) => write!(f, "invalid wire version {v}"),
+            Self::Unsupported(v) => write!(f, "unsupported wire version {v}"),
+            Self::MissingStep { from, to } => {
+                write!(
+                    f,
+                    "no conversion registered from wire version {from} to {to}"
+                )
+            }
+            Self::Upgrade { from, reason } => {
+                write!(f, "cannot upgrade from wire version {from}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VersionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for VersionError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+/// Registered upgraders and downgraders, keyed by the lower version of
+/// each step.
+#[derive(Debug, Clone)]
+pub struct VersionPipeline {
+    upgraders: BTreeMap<u32, Upgrader>,
+    downgraders: BTreeMap<u32, Downgrader>,
+}
+
+impl Default for VersionPipeline {
+    /// The built-in steps between [`OLDEST_WIRE_VERSION`] and
+    /// [`CURRENT_WIRE_VERSION`].
+    fn default() -> Self {
+        Self::empty().with_upgrader(1, upgrade_v1)
+    }
+}
+
+impl VersionPipeline {
+    /// A pipeline with no steps; only current-version traffic passes.
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            upgraders: BTreeMap::new(),
+            downgraders: BTreeMap::new(),
+        }
+    }
+
+    /// Register the step from version `from` to `from + 1`, replacing any
+    /// existing one.
+    #[must_use]
+    pub fn with_upgrader(mut self, from: u32, upgrader: Upgrader) -> Self {
+        self.upgraders.insert(from, upgrader);
+        self
+    }
+
+    /// Register the step from version `to + 1` down to `to`, replacing any
+    /// existing one. Steps without a downgrader only apply
+    /// [`Capabilities::for_version`].
+    #[must_use]
+    pub fn with_downgrader(mut self, to: u32, downgrader: Downgrader) -> Self {
+        self.downgraders.insert(to, downgrader);
+        self
+    }
+
+    /// Whether messages from `version` can be upgraded to the current one.
+    #[must_use]
+    pub fn supports(&self, version: u32) -> bool {
+        version <= CURRENT_WIRE_VERSION
+            && (version..CURRENT_WIRE_VERSION).all(|v| self.upgraders.contains_key(&v))
+    }
+
+    /// Parse a client frame (raw or enveloped), detect its version and
+    /// upgrade it to the current [`ClientMessage`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProtocolError::Json`] for malformed input,
+    /// [`ProtocolError::ServerOnly`] for server-only message types, and
+    /// [`ProtocolError::Version`] when it cannot be upgraded.
+    pub fn parse_client_message(&self, json: &str) -> Result<Upgraded, ProtocolError> {
+        let value: Value = serde_json::from_str(json)?;
+        let enveloped = value.get("payload").is_some() && value.get("seq").is_some();
+        let declared = if enveloped {
+            value
+                .get(VERSION_KEY)
+                .or_else(|| value["payload"].get(VERSION_KEY))
+        } else {
+            value.get(VERSION_KEY)
+        }
+        .map(|v| {
+            v.as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| VersionError::InvalidVersion(v.clone()))
+        })
+        .transpose()?;
+        let version = declared.unwrap_or(if enveloped {
+            CURRENT_WIRE_VERSION
+        } else {
+            OLDEST_WIRE_VERSION
+        });
+
+        let (payload, seq, ack) = if enveloped {
+            let envelope: Envelope<Value> = serde_json::from_value(value)?;
+            (envelope.payload, Some(envelope.seq), envelope.ack)
+        } else {
+            (value, None, None)
+        };
+        reject_server_only(&payload)?;
+        Ok(Upgraded {
+            message: self.upgrade(payload, version)?,
+            seq,
+            ack,
+            version,
+            declared: declared.is_some(),
+        })
+    }
+
+    /// Upgrade a version `from` message value to the current
+    /// [`ClientMessage`].
+    ///
+    /// # Errors
+    ///
+    /// [`VersionError::Unsupported`] for versions newer than current,
+    /// [`VersionError::MissingStep`] when no upgrader covers a step, and any
+    /// error an upgrader or deserialization returns.
+    pub fn upgrade(&self, mut value: Value, from: u32) -> Result<ClientMessage, VersionError> {
+        if from > CURRENT_WIRE_VERSION {
+            return Err(VersionError::Unsupported(from));
+        }
+        for version in from..CURRENT_WIRE_VERSION {
+            let upgrader = self
+                .upgraders
+                .get(&version)
+                .ok_or(VersionError::MissingStep {
+                    from: version,
+                    to: version + 1,
+                })?;
+            value = upgrader(value)?;
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Apply the registered downgraders from the current version down to
+    /// `to`, newest first. Returns `None` if a step drops `msg`.
+    ///
+    /// This covers only registered steps; send through
+    /// [`output_adapter`](Self::output_adapter) for the full rendering.
+    #[must_use]
+    pub fn downgrade(&self, msg: ServerMessage, to: u32) -> Option<ServerMessage> {
+        self.downgraders
+            .range(to..)
+            .rev()
+            .try_fold(msg, |msg, (_, downgrader)| downgrader(msg))
+    }
+
+    /// An [`OutputAdapter`] rendering for a client speaking `version`: the
+    /// registered downgraders, then [`Capabilities::for_version`].
+    #[must_use]
+    pub fn output_adapter(&self, version: u32) -> OutputAdapter {
+        OutputAdapter::new(Capabilities::for_version(version)).with_downgraders(
+            self.downgraders
+                .range(version..)
+                .rev()
+                .map(|(_, d)| *d)
+                .collect(),
+        )
+    }
+}
+
+/// Id fields the version 1 frontend could send as JSON numbers.
+const V1_ID_FIELDS: &[&str] = &[
+    "game_id",
+    "previous_game_id",
+    "lobby_id",
+    "channel_id",
+    "guild_id",
+];
+
+/// Version 1 → 2: ids become strings.
+///
+/// The old frontend sent Discord snowflakes and game ids as JSON numbers
+/// where it had them as numbers; version 2 always uses strings.
+fn upgrade_v1(mut value: Value) -> Result<Value, VersionError> {
+    let Some(fields) = value.as_object_mut() else {
+        return Err(VersionError::Upgrade {
+            from: 1,
+            reason: "message is not an object".into(),
+        });
+    };
+    fields.remove(VERSION_KEY);
+    for key in V1_ID_FIELDS {
+        if let Some(id) = fields.get_mut(*key) {
+            if id.is_number() {
+                *id = Value::String(id.to_string());
+            }
+        }
+    }
+    Ok(value)
+}
+
+/// Parse a client frame with the default pipeline; see
+/// [`VersionPipeline::parse_client_message`].
+///
+/// # Errors
+///
+/// As for [`VersionPipeline::parse_client_message`].
+pub fn parse_versioned_client_message(json: &str) -> Result<Upgraded, ProtocolError> {
+    VersionPipeline::default().parse_client_message(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_v1_ids_are_stringified() {
+        let up = parse_versioned_client_message(r#"{"type":"pass_turn","game_id":17}"#).unwrap();
+        assert_eq!(up.version, 1);
+        assert!(!up.declared);
+        assert!(matches!(up.message, ClientMessage::PassTurn { game_id } if game_id == "17"));
+    }
+
+    #[test]
+    fn current_raw_messages_pass_through_v1_upgrade() {
+        let up = parse_versioned_client_message(
+            r#"{"type":"join_channel_lobby","channel_id":"1100000000000000001"}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            up.message,
+            ClientMessage::JoinChannelLobby { channel_id, guild_id: None }
+                if channel_id == "1100000000000000001"
+        ));
+    }
+
+    #[test]
+    fn version_is_declared_or_inferred_from_envelope() {
+        let pipeline = VersionPipeline::default();
+        let up = pipeline
+            .parse_client_message(r#"{"seq":3,"ack":2,"ts":0,"payload":{"type":"heartbeat"}}"#)
+            .unwrap();
+        assert_eq!((up.version, up.declared), (CURRENT_WIRE_VERSION, false));
+        assert_eq!((up.seq, up.ack), (Some(3), Some(2)));
+
+        let up = pipeline
+            .parse_client_message(
+                r#"{"seq":4,"ts":0,"v":1,"payload":{"type":"leave_game","game_id":9}}"#,
+            )
+            .unwrap();
+        assert_eq!((up.version, up.declared), (1, true));
+        assert!(matches!(up.message, ClientMessage::LeaveGame { game_id } if game_id == "9"));
+
+        assert!(matches!(
+            pipeline.parse_client_message(r#"{"type":"heartbeat","v":3}"#),
+            Err(ProtocolError::Version(VersionError::Unsupported(3)))
+        ));
+        assert!(matches!(
+            pipeline.parse_client_message(r#"{"type":"heartbeat","v":"2"}"#),
+            Err(ProtocolError::Version(VersionError::InvalidVersion(_)))
+        ));
+    }
+
+    #[test]
+    fn empty_pipeline_only_accepts_current() {
+        let pipeline = VersionPipeline::empty();
+        assert!(pipeline.supports(CURRENT_WIRE_VERSION));
+        assert!(!pipeline.supports(1));
+        assert!(matches!(
+            pipeline.parse_client_message(r#"{"type":"heartbeat"}"#),
+            Err(ProtocolError::Version(VersionError::MissingStep {
+                from: 1,
+                to: 2
+            }))
+        ));
+        assert!(VersionPipeline::default().supports(1));
+    }
+
+    #[test]
+    fn output_adapter_runs_registered_downgraders() {
+        fn drop_lobby_left(msg: ServerMessage) -> Option<ServerMessage> {
+            (!matches!(msg, ServerMessage::LobbyLeft)).then_some(msg)
+        }
+        let pipeline = VersionPipeline::default().with_downgrader(1, drop_lobby_left);
+        assert!(pipeline.downgrade(ServerMessage::LobbyLeft, 1).is_none());
+        assert!(pipeline.downgrade(ServerMessage::LobbyLeft, 2).is_some());
+
+        let mut legacy = pipeline.output_adapter(1);
+        assert!(!legacy.capabilities().envelope);
+        assert!(legacy.adapt(ServerMessage::LobbyLeft).is_none());
+        let mut current = pipeline.output_adapter(CURRENT_WIRE_VERSION);
+        assert_eq!(current.capabilities(), Capabilities::CURRENT);
+        assert!(current.adapt(ServerMessage::LobbyLeft).is_some());
+    }
+}
@@ -0,0 +1,420 @@
+//! Broadcasting to a set of players.
+//!
+//! A [`Room`] holds the [`PlayerContext`]s of everyone in a lobby or game,
+//! each with a [`Role`]. Broadcasts skip disconnected members and come in
+//! three shapes:
+//!
+//! - [`broadcast`](Room::broadcast): one message for everyone, serialized once.
+//! - [`broadcast_with`](Room::broadcast_with): a closure builds each
+//!   recipient's message (or skips them).
+//! - [`broadcast_template`](Room::broadcast_template): a [`Template`] sorts
+//!   recipients into variants; each variant is built and serialized once,
+//!   however many members share it.
+//!
+//! [`SnapshotTemplate`] covers the common case:
+//!
+//! ```ignore
+//! // Players see their own `your_player`; spectators share one snapshot.
+//! room.broadcast_template(&SnapshotTemplate { game: &snapshot }).await;
+//! ```
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use crate::player::PlayerContext;
+use crate::protocol::GameSnapshot;
+use crate::ServerMessage;
+
+/// What a member is doing in the room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// Playing.
+    Player,
+    /// Playing, and in charge of the room's settings.
+    Host,
+    /// Watching.
+    Spectator,
+}
+
+impl Role {
+    /// Hosts are players too.
+    #[must_use]
+    pub fn is_player(self) -> bool {
+        matches!(self, Self::Player | Self::Host)
+    }
+}
+
+/// One player in a [`Room`].
+#[derive(Clone)]
+pub struct Member {
+    pub context: Arc<dyn PlayerContext>,
+    pub role: Role,
+}
+
+impl Member {
+    #[must_use]
+    pub fn player_id(&self) -> i64 {
+        self.context.player_id()
+    }
+}
+
+impl std::fmt::Debug for Member {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Member")
+            .field("player_id", &self.player_id())
+            .field("role", &self.role)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tailors a broadcast to groups of recipients.
+///
+/// Members with equal keys receive the same message, which is rendered and
+/// serialized once for the group.
+pub trait Template: Send + Sync {
+    /// Identifies a variant of the message.
+    type Key: Eq + Hash + Send;
+
+    /// Which variant `member` gets, or `None` to skip them.
+    fn key(&self, member: &Member) -> Option<Self::Key>;
+
+    /// Build the message for one variant.
+    fn render(&self, key: &Self::Key) -> ServerMessage;
+}
+
+/// A `GameSnapshot` carrying each player's own `your_player`.
+///
+/// Spectators, and members who are not among `game.players`, share one
+/// copy without it.
+#[derive(Debug, Clone, Copy)]
+pub struct SnapshotTemplate<'a> {
+    pub game: &'a GameSnapshot,
+}
+
+impl Template for SnapshotTemplate<'_> {
+    type Key = Option<i64>;
+
+    fn key(&self, member: &Member) -> Option<Option<i64>> {
+        let id = member.player_id();
+        let playing = member.role.is_player() && self.game.players.iter().any(|p| p.user_id == id);
+        Some(playing.then_some(id))
+    }
+
+    fn render(&self, key: &Option<i64>) -> ServerMessage {
+        let mut game = self.game.clone();
+        game.your_player =
+            key.and_then(|id| game.players.iter().find(|p| p.user_id == id).cloned());
+        ServerMessage::GameSnapshot {
+            game_id: game.game_id.clone(),
+            game,
+        }
+    }
+}
+
+/// Everyone in a lobby or game.
+#[derive(Debug, Clone, Default)]
+pub struct Room {
+    members: Vec<Member>,
+}
+
+impl Room {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `context` with `role`, replacing any member with the same player
+    /// id.
+    pub fn join(&mut self, context: Arc<dyn PlayerContext>, role: Role) {
+        let player_id = context.player_id();
+        self.members.retain(|m| m.player_id() != player_id);
+        self.members.push(Member { context, role });
+    }
+
+    /// Remove a member; returns it if it was present.
+    pub fn leave(&mut self, player_id: i64) -> Option<Member> {
+        let index = self
+            .members
+            .iter()
+            .position(|m| m.player_id() == player_id)?;
+        Some(self.members.remove(index))
+    }
+
+    /// Change a member's role. Returns false if they are not in the room.
+    pub fn set_role(&mut self, player_id: i64, role: Role) -> bool {
+        match self.members.iter_mut().find(|m| m.player_id() == player_id) {
+            Some(member) => {
+                member.role = role;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, player_id: i64) -> Option<&Member> {
+        self.members.iter().find(|m| m.player_id() == player_id)
+    }
+
+    /// Members in the order they joined.
+    #[must_use]
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// Players, including the host.
+    pub fn players(&self) -> impl Iterator<Item = &Member> {
+        self.members.iter().filter(|m| m.role.is_player())
+    }
+
+    pub fn spectators(&self) -> impl Iterator<Item = &Member> {
+        self.members.iter().filter(|m| m.role == Role::Spectator)
+    }
+
+    #[must_use]
+    pub fn host(&self) -> Option<&Member> {
+        self.members.iter().find(|m| m.role == Role::Host)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    fn connected(&self) -> impl Iterator<Item = &Member> {
+        self.members.iter().filter(|m| m.context.is_connected())
+    }
+
+    /// Send `msg` to every connected member. Returns how many were sent.
+    pub async fn broadcast(&self, msg: &ServerMessage) -> usize {
+        let encoded = Encoded::new(msg.clone());
+        let mut sent = 0;
+        for member in self.connected() {
+            encoded.send(member).await;
+            sent += 1;
+        }
+        sent
+    }
+
+    /// Send each connected member the message `tailor` builds for them;
+    /// `None` skips the member. Returns how many were sent.
+    pub async fn broadcast_with(
+        &self,
+        tailor: impl Fn(&Member) -> Option<ServerMessage> + Send + Sync,
+    ) -> usize {
+        let mut sent = 0;
+        for member in self.connected() {
+            if let Some(msg) = tailor(member) {
+                member.context.send_message_as(msg, member.role).await;
+                sent += 1;
+            }
+        }
+        sent
+    }
+
+    /// Send each connected member its variant of `template`, rendering and
+    /// serializing each variant once. Returns how many were sent.
+    pub async fn broadcast_template<T: Template>(&self, template: &T) -> usize {
+        let mut variants: HashMap<T::Key, Encoded> = HashMap::new();
+        let mut sent = 0;
+        for member in self.connected() {
+            let Some(key) = template.key(member) else {
+                continue;
+            };
+            let encoded = variants
+                .entry(key)
+                .or_insert_with_key(|key| Encoded::new(template.render(key)));
+            encoded.send(member).await;
+            sent += 1;
+        }
+        sent
+    }
+}
+
+/// A message with its JSON, when it serializes.
+struct Encoded {
+    msg: ServerMessage,
+    json: Option<Arc<str>>,
+}
+
+impl Encoded {
+    fn new(msg: ServerMessage) -> Self {
+        let json = serde_json::to_string(&msg).ok().map(Arc::from);
+        Self { msg, json }
+    }
+
+    async fn send(&self, member: &Member) {
+        match &self.json {
+            Some(json) => {
+                member
+                    .context
+                    .send_serialized_as(&self.msg, json, member.role)
+                    .await;
+            }
+            None => {
+                member
+                    .context
+                    .send_message_as(self.msg.clone(), member.role)
+                    .await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::protocol::{GameState, TimerVoteState};
+    use crate::testing::{player_info, MockPlayerContext};
+
+    fn room() -> (Room, Vec<Arc<MockPlayerContext>>) {
+        let contexts = vec![
+            Arc::new(MockPlayerContext::new(1)),
+            Arc::new(MockPlayerContext::new(2)),
+            Arc::new(MockPlayerContext::new(3)),
+            Arc::new(MockPlayerContext::new(4).with_connected(false)),
+        ];
+        let mut room = Room::new();
+        room.join(contexts[0].clone(), Role::Host);
+        room.join(contexts[1].clone(), Role::Player);
+        room.join(contexts[2].clone(), Role::Spectator);
+        room.join(contexts[3].clone(), Role::Player);
+        (room, contexts)
+    }
+
+    /// `TurnChanged` that tells players whose turn it is relative to them.
+    struct TurnTemplate {
+        renders: AtomicUsize,
+    }
+
+    impl Template for TurnTemplate {
+        type Key = Option<i64>;
+
+        fn key(&self, member: &Member) -> Option<Option<i64>> {
+            Some(member.role.is_player().then(|| member.player_id()))
+        }
+
+        fn render(&self, key: &Option<i64>) -> ServerMessage {
+            self.renders.fetch_add(1, Ordering::SeqCst);
+            ServerMessage::TurnChanged {
+                player_id: key.unwrap_or(0),
+                game_id: "g1".into(),
+                round: 1,
+                time_remaining: None,
+            }
+        }
+    }
+
+    #[test]
+    fn membership_and_roles() {
+        let (mut room, contexts) = room();
+        assert_eq!(room.len(), 4);
+        assert_eq!(room.host().unwrap().player_id(), 1);
+        assert_eq!(room.players().count(), 3);
+        assert_eq!(room.spectators().count(), 1);
+
+        assert!(room.set_role(3, Role::Player));
+        assert!(!room.set_role(9, Role::Player));
+        assert_eq!(room.spectators().count(), 0);
+
+        // Joining again replaces the old membership.
+        room.join(contexts[1].clone(), Role::Spectator);
+        assert_eq!(room.len(), 4);
+        assert_eq!(room.get(2).unwrap().role, Role::Spectator);
+        assert_eq!(room.leave(2).unwrap().player_id(), 2);
+        assert!(room.leave(2).is_none());
+    }
+
+    #[tokio::test]
+    async fn broadcast_skips_disconnected_and_shares_json() {
+        let (room, contexts) = room();
+        assert_eq!(room.broadcast(&ServerMessage::LobbyLeft).await, 3);
+        assert_eq!(contexts[0].sent_types(), ["lobby_left"]);
+        contexts[3].assert_nothing_sent();
+
+        let json: Vec<_> = contexts[..3].iter().map(|c| c.sent_json()).collect();
+        assert!(Arc::ptr_eq(&json[0][0], &json[1][0]));
+        assert!(Arc::ptr_eq(&json[0][0], &json[2][0]));
+        assert_eq!(&*json[0][0], r#"{"type":"lobby_left"}"#);
+    }
+
+    #[tokio::test]
+    async fn broadcast_with_tailors_each_recipient() {
+        let (room, contexts) = room();
+        let sent = room
+            .broadcast_with(|m| {
+                (m.role != Role::Host).then(|| ServerMessage::PlayerLeft {
+                    player_id: m.player_id(),
+                    reason: None,
+                })
+            })
+            .await;
+        assert_eq!(sent, 2);
+        contexts[0].assert_nothing_sent();
+        contexts[2].assert_sent(|m| matches!(m, ServerMessage::PlayerLeft { player_id: 3, .. }));
+    }
+
+    #[tokio::test]
+    async fn templates_render_each_variant_once() {
+        let (mut room, contexts) = room();
+        let spectator = Arc::new(MockPlayerContext::new(5));
+        room.join(spectator.clone(), Role::Spectator);
+        let template = TurnTemplate {
+            renders: AtomicUsize::new(0),
+        };
+
+        assert_eq!(room.broadcast_template(&template).await, 4);
+        // Two connected players, and one shared spectator variant.
+        assert_eq!(template.renders.load(Ordering::SeqCst), 3);
+        contexts[1].assert_sent(|m| matches!(m, ServerMessage::TurnChanged { player_id: 2, .. }));
+        assert!(Arc::ptr_eq(
+            &contexts[2].sent_json()[0],
+            &spectator.sent_json()[0]
+        ));
+        contexts[3].assert_nothing_sent();
+    }
+
+    #[tokio::test]
+    async fn snapshot_template_fills_your_player() {
+        let (room, contexts) = room();
+        let game = GameSnapshot {
+            game_id: "g1".into(),
+            state: GameState::InProgress,
+            grid: Vec::new(),
+            players: vec![
+                player_info(1, "p1"),
+                player_info(2, "p2"),
+                player_info(4, "p4"),
+            ],
+            spectators: Vec::new(),
+            current_turn: 1,
+            round: 1,
+            max_rounds: 3,
+            used_words: Vec::new(),
+            timer_vote_state: TimerVoteState::Idle,
+            your_player: None,
+            timer_expiration_time: None,
+            custom: None,
+            time_remaining_ms: None,
+        };
+
+        let sent = room
+            .broadcast_template(&SnapshotTemplate { game: &game })
+            .await;
+        assert_eq!(sent, 3);
+        for (context, expected) in contexts.iter().zip([Some(1), Some(2), None]) {
+            context.assert_sent(|m| {
+                matches!(m, ServerMessage::GameSnapshot { game, .. }
+                    if game.your_player.as_ref().map(|p| p.user_id) == expected)
+            });
+        }
+    }
+}
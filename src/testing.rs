@@ -0,0 +1,363 @@
+//! Test doubles for code built on this crate (feature `testing`).
+//!
+//! [`MockPlayerContext`] stands in for a connected player in handler unit
+//! tests. It records every message sent to it:
+//!
+//! ```
+//! use runecast_protocol::testing::MockPlayerContext;
+//! use runecast_protocol::{ErrorCode, PlayerContext, ServerMessage};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let ctx = MockPlayerContext::new(42).with_lobby("lobby-1").with_game("g1");
+//! ctx.send_message(ServerMessage::error(ErrorCode::NotYourTurn)).await;
+//!
+//! ctx.assert_sent(|m| matches!(m, ServerMessage::Error { code: ErrorCode::NotYourTurn, .. }));
+//! assert_eq!(ctx.sent_types(), ["error"]);
+//! # }
+//! ```
+//!
+//! [`lobby_snapshot`] and [`player_info`] build minimal wire values for
+//! tests to adjust with struct update syntax.
+
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::player::{PlayerContext, PlayerIdentity};
+use crate::protocol::{LobbySnapshot, LobbyType, PlayerInfo};
+use crate::ServerMessage;
+
+/// The identity half of a [`MockPlayerContext`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockIdentity {
+    pub player_id: i64,
+    pub username: String,
+    pub avatar_url: String,
+}
+
+impl PlayerIdentity for MockIdentity {
+    fn player_id(&self) -> i64 {
+        self.player_id
+    }
+
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    fn avatar_url(&self) -> &str {
+        &self.avatar_url
+    }
+}
+
+/// A [`PlayerContext`] that records what is sent to it.
+///
+/// Starts connected, not an admin, and in no lobby or game; the `with_*`
+/// methods change that. Messages are recorded whatever the connection
+/// state, so tests can check what a handler *tried* to send.
+#[derive(Debug)]
+pub struct MockPlayerContext {
+    identity: MockIdentity,
+    is_admin: bool,
+    lobby_id: Option<String>,
+    game_id: Option<String>,
+    is_spectating: bool,
+    is_connected: bool,
+    sent: Mutex<Vec<ServerMessage>>,
+    sent_json: Mutex<Vec<Arc<str>>>,
+}
+
+impl MockPlayerContext {
+    /// A connected player named `player<id>`.
+    #[must_use]
+    pub fn new(player_id: i64) -> Self {
+        Self {
+            identity: MockIdentity {
+                player_id,
+                username: format!("player{player_id}"),
+                avatar_url: String::new(),
+            },
+            is_admin: false,
+            lobby_id: None,
+            game_id: None,
+            is_spectating: false,
+            is_connected: true,
+            sent: Mutex::new(Vec::new()),
+            sent_json: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.identity.username = username.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_avatar_url(mut self, avatar_url: impl Into<String>) -> Self {
+        self.identity.avatar_url = avatar_url.into();
+        self
+    }
+
+    #[must_use]
+    pub fn with_admin(mut self, is_admin: bool) -> Self {
+        self.is_admin = is_admin;
+        self
+    }
+
+    #[must_use]
+    pub fn with_lobby(mut self, lobby_id: impl Into<String>) -> Self {
+        self.lobby_id = Some(lobby_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_game(mut self, game_id: impl Into<String>) -> Self {
+        self.game_id = Some(game_id.into());
+        self
+    }
+
+    #[must_use]
+    pub fn with_spectating(mut self, is_spectating: bool) -> Self {
+        self.is_spectating = is_spectating;
+        self
+    }
+
+    #[must_use]
+    pub fn with_connected(mut self, is_connected: bool) -> Self {
+        self.is_connected = is_connected;
+        self
+    }
+
+    fn log(&self) -> MutexGuard<'_, Vec<ServerMessage>> {
+        self.sent.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Everything sent so far, oldest first.
+    #[must_use]
+    pub fn sent(&self) -> Vec<ServerMessage> {
+        self.log().clone()
+    }
+
+    /// The pre-serialized JSON of messages delivered through
+    /// [`PlayerContext::send_serialized`], oldest first. Those messages are
+    /// also recorded in [`sent`](Self::sent).
+    #[must_use]
+    pub fn sent_json(&self) -> Vec<Arc<str>> {
+        self.sent_json
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// The `message_type()` of everything sent so far.
+    #[must_use]
+    pub fn sent_types(&self) -> Vec<&'static str> {
+        self.log().iter().map(ServerMessage::message_type).collect()
+    }
+
+    /// Everything sent so far, clearing the record.
+    pub fn take_sent(&self) -> Vec<ServerMessage> {
+        self.clear_json();
+        std::mem::take(&mut *self.log())
+    }
+
+    /// Forget everything sent so far.
+    pub fn clear(&self) {
+        self.clear_json();
+        self.log().clear();
+    }
+
+    fn clear_json(&self) {
+        self.sent_json
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// The first sent message matching `predicate`, if any.
+    pub fn find_sent(&self, predicate: impl Fn(&ServerMessage) -> bool) -> Option<ServerMessage> {
+        self.log().iter().find(|m| predicate(m)).cloned()
+    }
+
+    /// Assert that some sent message matches `predicate`.
+    ///
+    /// # Panics
+    ///
+    /// If none does, listing the types that were sent.
+    #[track_caller]
+    pub fn assert_sent(&self, predicate: impl Fn(&ServerMessage) -> bool) {
+        let sent = self.log();
+        assert!(
+            sent.iter().any(predicate),
+            "no matching message sent to player {}; sent: {:?}",
+            self.identity.player_id,
+            sent.iter()
+                .map(ServerMessage::message_type)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Assert that no sent message matches `predicate`.
+    ///
+    /// # Panics
+    ///
+    /// On the first message that does.
+    #[track_caller]
+    pub fn assert_not_sent(&self, predicate: impl Fn(&ServerMessage) -> bool) {
+        if let Some(msg) = self.log().iter().find(|m| predicate(m)) {
+            panic!(
+                "unexpected message sent to player {}: {msg:?}",
+                self.identity.player_id
+            );
+        }
+    }
+
+    /// Assert that nothing was sent.
+    ///
+    /// # Panics
+    ///
+    /// If anything was, listing the types that were sent.
+    #[track_caller]
+    pub fn assert_nothing_sent(&self) {
+        let sent = self.log();
+        assert!(
+            sent.is_empty(),
+            "expected nothing sent to player {}; sent: {:?}",
+            self.identity.player_id,
+            sent.iter()
+                .map(ServerMessage::message_type)
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[async_trait::async_trait]
+impl PlayerContext for MockPlayerContext {
+    async fn send_message(&self, msg: ServerMessage) {
+        self.log().push(msg);
+    }
+
+    async fn send_serialized(&self, msg: &ServerMessage, json: &Arc<str>) {
+        self.sent_json
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::clone(json));
+        self.log().push(msg.clone());
+    }
+
+    fn identity(&self) -> &dyn PlayerIdentity {
+        &self.identity
+    }
+
+    fn is_admin(&self) -> bool {
+        self.is_admin
+    }
+
+    fn lobby_id(&self) -> Option<&str> {
+        self.lobby_id.as_deref()
+    }
+
+    fn game_id(&self) -> Option<&str> {
+        self.game_id.as_deref()
+    }
+
+    fn is_spectating(&self) -> bool {
+        self.is_spectating
+    }
+
+    fn is_connected(&self) -> bool {
+        self.is_connected
+    }
+}
+
+/// An empty channel lobby with room for six and no host.
+#[must_use]
+pub fn lobby_snapshot(lobby_id: &str) -> LobbySnapshot {
+    LobbySnapshot {
+        lobby_id: lobby_id.to_string(),
+        lobby_type: LobbyType::Channel,
+        lobby_code: None,
+        players: Vec::new(),
+        games: Vec::new(),
+        max_players: 6,
+        sandbox_config: None,
+        host_id: None,
+    }
+}
+
+/// A connected player with no score, gems or team.
+#[must_use]
+pub fn player_info(user_id: i64, username: &str) -> PlayerInfo {
+    PlayerInfo {
+        user_id,
+        username: username.to_string(),
+        avatar_url: None,
+        score: 0,
+        gems: 0,
+        team: None,
+        is_connected: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorCode;
+
+    #[test]
+    fn builder_sets_identity_and_state() {
+        let ctx = MockPlayerContext::new(7)
+            .with_username("ann")
+            .with_avatar_url("https://cdn/a.png")
+            .with_admin(true)
+            .with_lobby("l1")
+            .with_game("g1")
+            .with_spectating(true)
+            .with_connected(false);
+        let ctx: &dyn PlayerContext = &ctx;
+        assert_eq!(ctx.player_id(), 7);
+        assert_eq!(ctx.identity().username(), "ann");
+        assert_eq!(ctx.identity().avatar_url(), "https://cdn/a.png");
+        assert!(ctx.is_admin());
+        assert_eq!(ctx.lobby_id(), Some("l1"));
+        assert!(ctx.in_game());
+        assert!(ctx.is_spectating());
+        assert!(!ctx.is_connected());
+
+        let plain = MockPlayerContext::new(8);
+        assert!(!plain.in_lobby() && !plain.in_game() && plain.is_connected());
+    }
+
+    #[tokio::test]
+    async fn records_and_asserts_sent_messages() {
+        let ctx = MockPlayerContext::new(1);
+        ctx.assert_nothing_sent();
+        ctx.send_message(ServerMessage::LobbyLeft).await;
+        ctx.send_message(ServerMessage::error(ErrorCode::NotYourTurn))
+            .await;
+
+        assert_eq!(ctx.sent_types(), ["lobby_left", "error"]);
+        ctx.assert_sent(|m| {
+            matches!(
+                m,
+                ServerMessage::Error {
+                    code: ErrorCode::NotYourTurn,
+                    ..
+                }
+            )
+        });
+        ctx.assert_not_sent(|m| matches!(m, ServerMessage::PoolLeft));
+        assert!(ctx
+            .find_sent(|m| matches!(m, ServerMessage::LobbyLeft))
+            .is_some());
+
+        assert_eq!(ctx.take_sent().len(), 2);
+        ctx.assert_nothing_sent();
+    }
+
+    #[test]
+    #[should_panic(expected = "no matching message sent to player 3")]
+    fn assert_sent_reports_what_was_sent() {
+        let ctx = MockPlayerContext::new(3);
+        ctx.assert_sent(|m| matches!(m, ServerMessage::LobbyLeft));
+    }
+}
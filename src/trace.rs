@@ -0,0 +1,389 @@
+//! Structured traces (feature `tracing`).
+//!
+//! With this feature the crate emits [`tracing`] spans for the three steps a
+//! message goes through:
+//!
+//! | Span       | Where                                             | Fields                                   |
+//! |------------|---------------------------------------------------|------------------------------------------|
+//! | `parse`    | `ServerConnection::recv` (feature `transport`)    | `message_type`, `seq`, `ack`, `bytes`    |
+//! | `validate` | [`authorize`](crate::protocol::authorize)         | `message_type`, `player_id`, `lobby_id`, `game_id` |
+//! | `send`     | `ServerConnection::send`, [`TracedContext`]       | `message_type`, `seq`, `ack` or the player fields |
+//!
+//! Enter a [`session_span`] around a connection's loop so the connection's
+//! spans carry the player too. Each span holds a `DEBUG` event with its
+//! outcome and a `TRACE` event with the message itself, rendered through
+//! [`Redacted`] so avatars and (at [`Redaction::Strict`]) words stay out of
+//! the logs.
+
+use std::fmt;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{Instrument, Span};
+
+use crate::player::{PlayerContext, PlayerIdentity};
+use crate::room::Role;
+use crate::ServerMessage;
+
+/// Fields hidden at [`Redaction::Personal`] and above.
+pub const PERSONAL_FIELDS: &[&str] = &["avatar_url", "banner_url"];
+
+/// Fields additionally hidden at [`Redaction::Strict`]: usernames, and
+/// anything that reveals a word or the path that spells it.
+pub const CONTENT_FIELDS: &[&str] = &["username", "word", "used_words", "positions", "path"];
+
+/// What traced message payloads leave out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Redaction {
+    /// Payloads are logged as sent.
+    Off,
+    /// Hide [`PERSONAL_FIELDS`].
+    #[default]
+    Personal,
+    /// Hide [`PERSONAL_FIELDS`] and [`CONTENT_FIELDS`].
+    Strict,
+}
+
+impl Redaction {
+    /// What a hidden value is replaced with.
+    pub const PLACEHOLDER: &'static str = "[redacted]";
+
+    /// Whether a field named `key` is hidden at this level.
+    #[must_use]
+    pub fn hides(self, key: &str) -> bool {
+        match self {
+            Self::Off => false,
+            Self::Personal => PERSONAL_FIELDS.contains(&key),
+            Self::Strict => PERSONAL_FIELDS.contains(&key) || CONTENT_FIELDS.contains(&key),
+        }
+    }
+
+    fn scrub(self, value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (key, field) in fields.iter_mut() {
+                    if self.hides(key) {
+                        *field = Value::from(Self::PLACEHOLDER);
+                    } else {
+                        self.scrub(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.scrub(item)),
+            _ => {}
+        }
+    }
+}
+
+/// Displays a message as JSON with fields hidden per a [`Redaction`].
+///
+/// The message is only serialized when displayed, so passing one to a
+/// disabled event costs nothing.
+pub struct Redacted<'a, T: ?Sized> {
+    value: &'a T,
+    redaction: Redaction,
+}
+
+impl<'a, T: Serialize + ?Sized> Redacted<'a, T> {
+    #[must_use]
+    pub fn new(value: &'a T, redaction: Redaction) -> Self {
+        Self { value, redaction }
+    }
+}
+
+impl<T: Serialize + ?Sized> fmt::Display for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Ok(mut value) = serde_json::to_value(self.value) else {
+            return f.write_str("<unserializable>");
+        };
+        self.redaction.scrub(&mut value);
+        value.fmt(f)
+    }
+}
+
+impl<T: Serialize + ?Sized> fmt::Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// An `INFO` span for a player's connection, tagged with their
+/// `player_id` and the `lobby_id` and `game_id` they are in when it opens.
+///
+/// Lobby and game change during a session; per-message spans from
+/// [`TracedContext`] and [`authorize`](crate::protocol::authorize) record
+/// the current ones.
+#[must_use]
+pub fn session_span(ctx: &dyn PlayerContext) -> Span {
+    tracing::info_span!(
+        "session",
+        player_id = ctx.player_id(),
+        lobby_id = ctx.lobby_id(),
+        game_id = ctx.game_id(),
+    )
+}
+
+/// A [`PlayerContext`] that traces everything sent through it in a `send`
+/// span.
+#[derive(Debug)]
+pub struct TracedContext<C> {
+    inner: C,
+    redaction: Redaction,
+}
+
+impl<C: PlayerContext> TracedContext<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            redaction: Redaction::default(),
+        }
+    }
+
+    /// Redact logged payloads at `redaction` (default
+    /// [`Redaction::Personal`]).
+    #[must_use]
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    #[must_use]
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn redaction(&self) -> Redaction {
+        self.redaction
+    }
+
+    fn span(&self, msg: &ServerMessage) -> Span {
+        let span = tracing::debug_span!(
+            "send",
+            message_type = msg.message_type(),
+            player_id = self.inner.player_id(),
+            lobby_id = self.inner.lobby_id(),
+            game_id = self.inner.game_id(),
+        );
+        span.in_scope(|| {
+            tracing::debug!(connected = self.inner.is_connected(), "sending");
+            tracing::trace!(payload = %Redacted::new(msg, self.redaction));
+        });
+        span
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: PlayerContext> PlayerContext for TracedContext<C> {
+    async fn send_message(&self, msg: ServerMessage) {
+        let span = self.span(&msg);
+        self.inner.send_message(msg).instrument(span).await;
+    }
+
+    async fn send_serialized(&self, msg: &ServerMessage, json: &Arc<str>) {
+        let span = self.span(msg);
+        self.inner.send_serialized(msg, json).instrument(span).await;
+    }
+
+    async fn send_message_as(&self, msg: ServerMessage, role: Role) {
+        let span = self.span(&msg);
+        self.inner.send_message_as(msg, role).instrument(span).await;
+    }
+
+    async fn send_serialized_as(&self, msg: &ServerMessage, json: &Arc<str>, role: Role) {
+        let span = self.span(msg);
+        self.inner
+            .send_serialized_as(msg, json, role)
+            .instrument(span)
+            .await;
+    }
+
+    fn identity(&self) -> &dyn PlayerIdentity {
+        self.inner.identity()
+    }
+
+    fn is_admin(&self) -> bool {
+        self.inner.is_admin()
+    }
+
+    fn lobby_id(&self) -> Option<&str> {
+        self.inner.lobby_id()
+    }
+
+    fn game_id(&self) -> Option<&str> {
+        self.inner.game_id()
+    }
+
+    fn is_spectating(&self) -> bool {
+        self.inner.is_spectating()
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
+
+/// A subscriber that writes every span, record and event as a line, for
+/// asserting on what was traced.
+#[cfg(test)]
+pub(crate) mod capture {
+    use std::fmt::{self, Write};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex, PoisonError};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    #[derive(Clone, Default)]
+    pub(crate) struct Capture {
+        lines: Arc<Mutex<Vec<String>>>,
+        next_id: Arc<AtomicU64>,
+    }
+
+    impl Capture {
+        pub(crate) fn lines(&self) -> Vec<String> {
+            self.lines
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()
+        }
+
+        /// The first line starting with `prefix`.
+        pub(crate) fn find(&self, prefix: &str) -> String {
+            let lines = self.lines();
+            lines
+                .iter()
+                .find(|line| line.starts_with(prefix))
+                .cloned()
+                .unwrap_or_else(|| panic!("no `{prefix}` in {lines:#?}"))
+        }
+
+        fn push(&self, line: Line) {
+            self.lines
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(line.0);
+        }
+    }
+
+    struct Line(String);
+
+    impl Visit for Line {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            let _ = write!(self.0, " {}={value}", field.name());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut line = Line(format!("span {}", span.metadata().name()));
+            span.record(&mut line);
+            self.push(line);
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            let mut line = Line("record".into());
+            values.record(&mut line);
+            self.push(line);
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut line = Line(format!("event {}", event.metadata().level()));
+            event.record(&mut line);
+            self.push(line);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capture::Capture;
+    use super::*;
+    use crate::protocol::{authorize, ClientMessage, ErrorCode, LobbySnapshot, LobbyType};
+    use crate::protocol::{Position, SpectatorInfo};
+    use crate::testing::{lobby_snapshot, MockPlayerContext};
+
+    #[test]
+    fn redaction_levels() {
+        let msg = ServerMessage::SpectatorAdded {
+            game_id: "g1".into(),
+            spectator: SpectatorInfo {
+                user_id: 7,
+                username: "alice".into(),
+                avatar_url: Some("https://cdn.example/a.png".into()),
+            },
+        };
+        let off = Redacted::new(&msg, Redaction::Off).to_string();
+        assert!(off.contains("cdn.example") && off.contains("alice"));
+        let personal = Redacted::new(&msg, Redaction::Personal).to_string();
+        assert!(!personal.contains("cdn.example") && personal.contains("alice"));
+        assert!(personal.contains(Redaction::PLACEHOLDER));
+
+        let submit = ClientMessage::SubmitWord {
+            game_id: "g1".into(),
+            word: "RUNE".into(),
+            positions: vec![Position { row: 0, col: 1 }],
+        };
+        assert!(Redacted::new(&submit, Redaction::Personal)
+            .to_string()
+            .contains("RUNE"));
+        let strict = Redacted::new(&submit, Redaction::Strict).to_string();
+        assert!(!strict.contains("RUNE") && !strict.contains("col"));
+        assert!(strict.contains("\"game_id\":\"g1\""));
+    }
+
+    #[tokio::test]
+    async fn traced_context_tags_sends_with_the_player() {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+        let ctx = TracedContext::new(MockPlayerContext::new(7).with_lobby("l1").with_game("g1"));
+        ctx.send_message(ServerMessage::LobbyLeft).await;
+
+        assert_eq!(
+            capture.find("span send"),
+            "span send message_type=lobby_left player_id=7 lobby_id=l1 game_id=g1"
+        );
+        assert!(capture
+            .find("event TRACE")
+            .contains(r#"payload={"type":"lobby_left"}"#));
+        assert_eq!(ctx.inner().sent_types(), ["lobby_left"]);
+    }
+
+    #[test]
+    fn authorize_traces_denials() {
+        let capture = Capture::default();
+        let lobby = LobbySnapshot {
+            lobby_type: LobbyType::Custom,
+            host_id: Some(1),
+            ..lobby_snapshot("l1")
+        };
+        let player = MockPlayerContext::new(2).with_lobby("l1");
+        let result = tracing::subscriber::with_default(capture.clone(), || {
+            authorize(&ClientMessage::AdminGetGames, &player, &lobby)
+        });
+
+        assert_eq!(result, Err(ErrorCode::NotAuthorized));
+        assert_eq!(
+            capture.find("span validate"),
+            "span validate message_type=admin_get_games player_id=2 lobby_id=l1"
+        );
+        assert!(capture.find("event DEBUG").contains("code=NotAuthorized"));
+    }
+}
@@ -0,0 +1,491 @@
+//! Server side of one client connection.
+
+#[cfg(feature = "metrics")]
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use chrono::{DateTime, Utc};
+
+use super::{CloseReason, Frame, Transport, TransportError};
+use crate::game::clock::{Clock, SystemClock};
+#[cfg(feature = "metrics")]
+use crate::metrics::{AckLag, Direction, MetricsRecorder};
+use crate::protocol::version::{Upgraded, VersionPipeline};
+use crate::protocol::{
+    Capabilities, ClientMessage, OutputAdapter, ProtocolError, ServerMessage, CURRENT_WIRE_VERSION,
+    HEARTBEAT_INTERVAL_MS, HEARTBEAT_TIMEOUT_MS, MAX_MESSAGE_SIZE, PROTOCOL_VERSION,
+};
+#[cfg(feature = "tracing")]
+use crate::trace::{Redacted, Redaction};
+#[cfg(feature = "tracing")]
+use tracing::Instrument;
+
+/// Protocol handling over a [`Transport`], from the server's side.
+///
+/// - Incoming frames go through [`VersionPipeline`], so legacy and enveloped
+///   clients both arrive as current [`ClientMessage`]s.
+/// - `Heartbeat` is answered with `HeartbeatAck` and `Ack` is recorded;
+///   neither reaches the caller.
+/// - If no frame arrives within the heartbeat timeout the connection is
+///   closed with [`CloseReason::POLICY_VIOLATION`].
+/// - Outgoing messages go through an [`OutputAdapter`] and are numbered
+///   with envelope `seq`s, piggybacking the last client `seq` as `ack`.
+///   Unless capabilities are pinned, the adapter follows the wire version
+///   of the client's first message, so a version 1 client gets raw legacy
+///   frames.
+/// - With the `metrics` feature, traffic is reported to a
+///   [`MetricsRecorder`](crate::metrics::MetricsRecorder) set with
+///   `with_metrics`.
+/// - With the `tracing` feature, each frame is parsed in a `parse` span and
+///   each message sent in a `send` span; see [`crate::trace`].
+pub struct ServerConnection<T> {
+    transport: T,
+    pipeline: VersionPipeline,
+    output: OutputAdapter,
+    /// Capabilities fixed by the caller; `None` negotiates from the first
+    /// client message.
+    pinned: Option<Capabilities>,
+    clock: Arc<dyn Clock>,
+    heartbeat_timeout: Duration,
+    next_seq: u64,
+    last_client_seq: Option<u64>,
+    last_acked: Option<u64>,
+    client_version: Option<u32>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<ConnectionMetrics>,
+    #[cfg(feature = "tracing")]
+    redaction: Redaction,
+}
+
+impl<T: Transport> ServerConnection<T> {
+    /// Wrap `transport`.
+    ///
+    /// With `capabilities: None`, output follows the wire version of the
+    /// client's first message (and uses current capabilities until then,
+    /// e.g. for [`hello`](Self::hello)). `Some` pins them.
+    #[must_use]
+    pub fn new(transport: T, capabilities: Option<Capabilities>) -> Self {
+        let pipeline = VersionPipeline::default();
+        Self {
+            transport,
+            output: Self::adapter(&pipeline, capabilities),
+            pipeline,
+            pinned: capabilities,
+            clock: Arc::new(SystemClock),
+            heartbeat_timeout: Duration::from_millis(u64::from(HEARTBEAT_TIMEOUT_MS)),
+            next_seq: 1,
+            last_client_seq: None,
+            last_acked: None,
+            client_version: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            #[cfg(feature = "tracing")]
+            redaction: Redaction::default(),
+        }
+    }
+
+    /// Close the connection if no frame arrives for `timeout`
+    /// (default [`HEARTBEAT_TIMEOUT_MS`]).
+    #[must_use]
+    pub fn with_heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Use `pipeline` to upgrade incoming messages and, unless
+    /// capabilities are pinned, to render outgoing ones.
+    #[must_use]
+    pub fn with_pipeline(mut self, pipeline: VersionPipeline) -> Self {
+        self.output = Self::adapter(&pipeline, self.pinned);
+        self.pipeline = pipeline;
+        self
+    }
+
+    fn adapter(pipeline: &VersionPipeline, pinned: Option<Capabilities>) -> OutputAdapter {
+        match pinned {
+            Some(capabilities) => OutputAdapter::new(capabilities),
+            None => pipeline.output_adapter(CURRENT_WIRE_VERSION),
+        }
+    }
+
+    /// Read `server_time` for heartbeat acks from `clock`.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Report message counts, sizes, parse failures and ack lag to
+    /// `recorder`.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn with_metrics(mut self, recorder: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics = Some(ConnectionMetrics {
+            recorder,
+            sent_at: VecDeque::new(),
+        });
+        self
+    }
+
+    /// Redact traced payloads at `redaction` (default
+    /// [`Redaction::Personal`]).
+    #[cfg(feature = "tracing")]
+    #[must_use]
+    pub fn with_redaction(mut self, redaction: Redaction) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Send `Hello` with the heartbeat interval and protocol version.
+    ///
+    /// # Errors
+    ///
+    /// As for [`send`](Self::send).
+    pub async fn hello(&mut self) -> Result<(), TransportError> {
+        self.send(ServerMessage::Hello {
+            heartbeat_interval_ms: HEARTBEAT_INTERVAL_MS,
+            server_version: Some(PROTOCOL_VERSION.to_string()),
+        })
+        .await
+    }
+
+    /// Adapt, envelope and send `msg`. Messages the adapter drops (see
+    /// [`OutputAdapter::adapt`]) are not sent and use no `seq`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TransportError::Serialize`] if the message cannot be
+    /// serialized, or the transport's error if sending fails.
+    pub async fn send(&mut self, msg: ServerMessage) -> Result<(), TransportError> {
+        let seq = self.next_seq;
+        #[cfg(feature = "metrics")]
+        let message_type = msg.message_type();
+        #[cfg(feature = "tracing")]
+        let span = self.send_span(&msg);
+        let Some(json) = self.output.encode(msg, Some(seq), self.last_client_seq)? else {
+            #[cfg(feature = "tracing")]
+            span.in_scope(|| tracing::debug!("dropped by output adapter"));
+            return Ok(());
+        };
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &mut self.metrics {
+            let enveloped = self.output.capabilities().envelope;
+            metrics.sent(
+                message_type,
+                &json,
+                enveloped.then_some(seq),
+                self.clock.now(),
+            );
+        }
+        #[cfg(feature = "tracing")]
+        span.in_scope(|| tracing::debug!(bytes = json.len(), "sending"));
+        if self.output.capabilities().envelope {
+            self.next_seq += 1;
+        }
+        let sent = self.transport.send(Frame::Text(json));
+        #[cfg(feature = "tracing")]
+        let sent = sent.instrument(span);
+        sent.await
+    }
+
+    #[cfg(feature = "tracing")]
+    fn send_span(&self, msg: &ServerMessage) -> tracing::Span {
+        let enveloped = self.output.capabilities().envelope;
+        let span = tracing::debug_span!(
+            "send",
+            message_type = msg.message_type(),
+            seq = enveloped.then_some(self.next_seq),
+            ack = self.last_client_seq.filter(|_| enveloped),
+        );
+        span.in_scope(|| tracing::trace!(payload = %Redacted::new(msg, self.redaction)));
+        span
+    }
+
+    /// Wait for the next client message that needs handling.
+    ///
+    /// Returns `Ok(None)` when the client closes the connection.
+    ///
+    /// # Errors
+    ///
+    /// - [`TransportError::Parse`] or [`TransportError::InvalidUtf8`] for a
+    ///   frame that is not a valid message; the connection stays open and the
+    ///   caller may reply with an error.
+    /// - [`TransportError::MessageTooLarge`] or
+    ///   [`TransportError::HeartbeatTimeout`]; the connection has been closed.
+    /// - The transport's error if receiving fails.
+    pub async fn recv(&mut self) -> Result<Option<ClientMessage>, TransportError> {
+        loop {
+            let frame = tokio::time::timeout(self.heartbeat_timeout, self.transport.recv()).await;
+            let Ok(frame) = frame else {
+                self.close(CloseReason::new(
+                    CloseReason::POLICY_VIOLATION,
+                    "heartbeat timeout",
+                ))
+                .await?;
+                return Err(TransportError::HeartbeatTimeout);
+            };
+            let text = match frame? {
+                Frame::Text(text) => text,
+                Frame::Binary(bytes) => {
+                    String::from_utf8(bytes).map_err(|_| TransportError::InvalidUtf8)?
+                }
+                Frame::Close(_) => return Ok(None),
+            };
+            if text.len() > MAX_MESSAGE_SIZE {
+                self.close(CloseReason::new(
+                    CloseReason::MESSAGE_TOO_BIG,
+                    "message too large",
+                ))
+                .await?;
+                return Err(TransportError::MessageTooLarge(text.len()));
+            }
+
+            let upgraded = self.parse(&text)?;
+            if self.client_version.is_none() && self.pinned.is_none() {
+                self.output = self.pipeline.output_adapter(upgraded.version);
+            }
+            self.client_version = Some(upgraded.version);
+            if upgraded.seq.is_some() {
+                self.last_client_seq = upgraded.seq;
+            }
+            if let Some(ack) = upgraded.ack {
+                self.record_ack(ack);
+            }
+            match upgraded.message {
+                ClientMessage::Heartbeat => {
+                    let server_time =
+                        u64::try_from(self.clock.now().timestamp_millis()).unwrap_or_default();
+                    self.send(ServerMessage::HeartbeatAck { server_time })
+                        .await?;
+                }
+                ClientMessage::Ack { seq } => self.record_ack(seq),
+                message => return Ok(Some(message)),
+            }
+        }
+    }
+
+    /// Parse a client frame, reporting it to metrics and traces.
+    fn parse(&self, text: &str) -> Result<Upgraded, ProtocolError> {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "parse",
+            message_type = tracing::field::Empty,
+            seq = tracing::field::Empty,
+            ack = tracing::field::Empty,
+            bytes = text.len(),
+        );
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+
+        let result = self.pipeline.parse_client_message(text);
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            match &result {
+                Ok(upgraded) => metrics.recorder.message(
+                    Direction::Inbound,
+                    upgraded.message.message_type(),
+                    text.len(),
+                ),
+                Err(e) => metrics.recorder.parse_failure(e.kind()),
+            }
+        }
+        #[cfg(feature = "tracing")]
+        match &result {
+            Ok(upgraded) => {
+                span.record("message_type", upgraded.message.message_type());
+                span.record("seq", upgraded.seq);
+                span.record("ack", upgraded.ack);
+                tracing::debug!(version = upgraded.version, "parsed");
+                tracing::trace!(payload = %Redacted::new(&upgraded.message, self.redaction));
+            }
+            Err(e) => tracing::debug!(kind = e.kind(), error = %e, "parse failed"),
+        }
+        result
+    }
+
+    /// Close the connection with `reason`.
+    ///
+    /// # Errors
+    ///
+    /// The transport's error if the close frame cannot be sent.
+    pub async fn close(&mut self, reason: CloseReason) -> Result<(), TransportError> {
+        self.transport.close(reason).await
+    }
+
+    /// Highest server `seq` the client has acknowledged.
+    #[must_use]
+    pub fn last_acked(&self) -> Option<u64> {
+        self.last_acked
+    }
+
+    /// The `seq` the next enveloped message will carry.
+    #[must_use]
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// The wire version of the last message received, once one has arrived.
+    #[must_use]
+    pub fn client_version(&self) -> Option<u32> {
+        self.client_version
+    }
+
+    /// Unwrap the transport.
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+
+    fn record_ack(&mut self, seq: u64) {
+        #[cfg(feature = "metrics")]
+        if self.last_acked.is_none_or(|prev| seq > prev) {
+            if let Some(metrics) = &mut self.metrics {
+                let unacked = (self.next_seq - 1).saturating_sub(seq);
+                metrics.acked(seq, unacked, self.clock.now());
+            }
+        }
+        self.last_acked = Some(self.last_acked.map_or(seq, |prev| prev.max(seq)));
+    }
+}
+
+/// How many sent `seq`s to remember for ack latency. Older ones are
+/// forgotten, and acks for them are reported without a latency.
+#[cfg(feature = "metrics")]
+const ACK_LATENCY_WINDOW: usize = 256;
+
+#[cfg(feature = "metrics")]
+struct ConnectionMetrics {
+    recorder: Arc<dyn MetricsRecorder>,
+    /// Send times of recent enveloped messages, oldest first.
+    sent_at: VecDeque<(u64, DateTime<Utc>)>,
+}
+
+#[cfg(feature = "metrics")]
+impl ConnectionMetrics {
+    fn sent(
+        &mut self,
+        message_type: &'static str,
+        json: &str,
+        seq: Option<u64>,
+        now: DateTime<Utc>,
+    ) {
+        self.recorder
+            .message(Direction::Outbound, message_type, json.len());
+        if let Some(seq) = seq {
+            if self.sent_at.len() == ACK_LATENCY_WINDOW {
+                self.sent_at.pop_front();
+            }
+            self.sent_at.push_back((seq, now));
+        }
+    }
+
+    fn acked(&mut self, seq: u64, unacked: u64, now: DateTime<Utc>) {
+        let mut latency = None;
+        while let Some(&(sent, at)) = self.sent_at.front() {
+            if sent > seq {
+                break;
+            }
+            self.sent_at.pop_front();
+            if sent == seq {
+                latency = (now - at).to_std().ok();
+            }
+        }
+        self.recorder.ack_lag(AckLag { unacked, latency });
+    }
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::metrics::InMemoryRecorder;
+    use crate::transport::loopback;
+
+    #[tokio::test]
+    async fn reports_traffic_failures_and_ack_lag() {
+        let (mut client, server) = loopback();
+        let clock = Arc::new(ManualClock::new(
+            DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        ));
+        let recorder = Arc::new(InMemoryRecorder::new());
+        let mut conn = ServerConnection::new(server, None)
+            .with_clock(clock.clone())
+            .with_metrics(recorder.clone());
+
+        conn.hello().await.unwrap();
+        conn.send(ServerMessage::LobbyLeft).await.unwrap();
+        conn.send(ServerMessage::PoolLeft).await.unwrap();
+        clock.advance(TimeDelta::milliseconds(40));
+        for frame in [
+            "{nope",
+            r#"{"type":"player_disconnected"}"#,
+            r#"{"seq":1,"ack":2,"ts":0,"payload":{"type":"leave_lobby"}}"#,
+        ] {
+            client.send(Frame::Text(frame.into())).await.unwrap();
+        }
+        assert!(conn.recv().await.is_err());
+        assert!(conn.recv().await.is_err());
+        assert!(matches!(
+            conn.recv().await.unwrap(),
+            Some(ClientMessage::LeaveLobby)
+        ));
+        // A repeated ack is not a new sample.
+        client
+            .send(Frame::Text(r#"{"type":"ack","seq":2}"#.into()))
+            .await
+            .unwrap();
+        client
+            .send(Frame::Text(r#"{"type":"leave_game_pool"}"#.into()))
+            .await
+            .unwrap();
+        conn.recv().await.unwrap();
+
+        let snapshot = recorder.snapshot();
+        assert_eq!(snapshot.sent["hello"].count, 1);
+        assert_eq!(snapshot.sent["lobby_left"].count, 1);
+        assert!(snapshot.sent["hello"].bytes > snapshot.sent["lobby_left"].bytes);
+        assert_eq!(snapshot.received["leave_lobby"].count, 1);
+        assert_eq!(snapshot.received["ack"].count, 1);
+        assert_eq!(snapshot.parse_failures["malformed_json"], 1);
+        assert_eq!(snapshot.parse_failures["server_only"], 1);
+        assert_eq!(snapshot.ack_lag.samples, 1);
+        assert_eq!(snapshot.ack_lag.max_unacked, 1);
+        assert_eq!(snapshot.ack_lag.max_latency, Duration::from_millis(40));
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod trace_tests {
+    use super::*;
+    use crate::trace::capture::Capture;
+    use crate::transport::loopback;
+
+    #[tokio::test]
+    async fn traces_parse_and_send() {
+        let capture = Capture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+        let (mut client, server) = loopback();
+        let mut conn = ServerConnection::new(server, None).with_redaction(Redaction::Strict);
+
+        let submit = r#"{"seq":3,"ack":0,"ts":0,"payload":{"type":"submit_word","game_id":"g1","word":"RUNE","positions":[]}}"#;
+        client.send(Frame::Text(submit.into())).await.unwrap();
+        client.send(Frame::Text("{nope".into())).await.unwrap();
+        conn.recv().await.unwrap();
+        assert!(conn.recv().await.is_err());
+        conn.send(ServerMessage::LobbyLeft).await.unwrap();
+
+        let lines = capture.lines();
+        assert!(lines.contains(&format!("span parse bytes={}", submit.len())));
+        assert!(lines.contains(&"record message_type=submit_word".to_string()));
+        assert!(lines.contains(&"record seq=3".to_string()));
+        assert!(lines.iter().any(|l| l.contains("kind=malformed_json")));
+        assert!(!lines.iter().any(|l| l.contains("RUNE")));
+        assert_eq!(
+            capture.find("span send"),
+            "span send message_type=lobby_left seq=1 ack=3"
+        );
+    }
+}
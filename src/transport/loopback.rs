@@ -0,0 +1,89 @@
+//! In-memory [`Transport`] pair for tests.
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use super::{Frame, Transport, TransportError};
+
+/// One end of an in-memory connection made by [`loopback`].
+///
+/// Frames arrive in order and unchanged. Dropping one end is seen by the
+/// other as a close without a reason.
+#[derive(Debug)]
+pub struct LoopbackTransport {
+    tx: UnboundedSender<Frame>,
+    rx: UnboundedReceiver<Frame>,
+    sent_close: bool,
+    received_close: bool,
+}
+
+/// Create two connected transports; what one sends the other receives.
+#[must_use]
+pub fn loopback() -> (LoopbackTransport, LoopbackTransport) {
+    let (a_tx, b_rx) = unbounded_channel();
+    let (b_tx, a_rx) = unbounded_channel();
+    (
+        LoopbackTransport::new(a_tx, a_rx),
+        LoopbackTransport::new(b_tx, b_rx),
+    )
+}
+
+impl LoopbackTransport {
+    fn new(tx: UnboundedSender<Frame>, rx: UnboundedReceiver<Frame>) -> Self {
+        Self {
+            tx,
+            rx,
+            sent_close: false,
+            received_close: false,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for LoopbackTransport {
+    async fn send(&mut self, frame: Frame) -> Result<(), TransportError> {
+        if self.sent_close {
+            return Err(TransportError::Closed(None));
+        }
+        self.sent_close = matches!(frame, Frame::Close(_));
+        self.tx
+            .send(frame)
+            .map_err(|_| TransportError::Closed(None))
+    }
+
+    async fn recv(&mut self) -> Result<Frame, TransportError> {
+        if self.received_close {
+            return Err(TransportError::Closed(None));
+        }
+        let frame = self.rx.recv().await.unwrap_or(Frame::Close(None));
+        self.received_close = matches!(frame, Frame::Close(_));
+        Ok(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::CloseReason;
+
+    #[tokio::test]
+    async fn frames_cross_in_order_and_close_once() {
+        let (mut a, mut b) = loopback();
+        a.send(Frame::Text("one".into())).await.unwrap();
+        a.send(Frame::Binary(vec![2])).await.unwrap();
+        a.close(CloseReason::new(CloseReason::NORMAL, "done"))
+            .await
+            .unwrap();
+        assert!(a.send(Frame::Text("late".into())).await.is_err());
+
+        assert_eq!(b.recv().await.unwrap(), Frame::Text("one".into()));
+        assert_eq!(b.recv().await.unwrap(), Frame::Binary(vec![2]));
+        assert_eq!(
+            b.recv().await.unwrap(),
+            Frame::Close(Some(CloseReason::new(CloseReason::NORMAL, "done")))
+        );
+        assert!(matches!(b.recv().await, Err(TransportError::Closed(None))));
+
+        drop(b);
+        assert!(matches!(a.recv().await.unwrap(), Frame::Close(None)));
+    }
+}
@@ -0,0 +1,162 @@
+//! Socket I/O for the protocol.
+//!
+//! [`Transport`] is the frame-level seam: anything that can send and receive
+//! text, binary and close frames. [`ServerConnection`] sits on top of one and
+//! speaks the protocol — parsing client frames, enveloping replies, answering
+//! heartbeats and enforcing the heartbeat timeout — so a server only sees
+//! [`ClientMessage`](crate::ClientMessage)s and
+//! [`ServerMessage`](crate::ServerMessage)s.
+//!
+//! ```text
+//! ┌──────────────┐ ClientMessage  ┌──────────────────┐  Frame  ┌───────────┐
+//! │   handlers   │ ◀────────────  │ ServerConnection │ ◀─────▶ │ Transport │ ◀──▶ socket
+//! │              │ ───────────▶   │                  │         │           │
+//! └──────────────┘ ServerMessage  └──────────────────┘         └───────────┘
+//! ```
+//!
+//! For tests, [`loopback`] makes an in-memory transport pair and [`Script`]
+//! plays a scripted client against one end.
+//!
+//! Requires the `transport` feature; [`WsTransport`] (tokio-tungstenite)
+//! additionally requires `ws`.
+
+mod connection;
+mod loopback;
+mod script;
+#[cfg(feature = "ws")]
+mod ws;
+
+use std::fmt;
+
+pub use connection::ServerConnection;
+pub use loopback::{loopback, LoopbackTransport};
+pub use script::{Script, ScriptError, ScriptErrorKind};
+#[cfg(feature = "ws")]
+pub use ws::WsTransport;
+
+/// One WebSocket data or close frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// UTF-8 text; every protocol message is sent this way.
+    Text(String),
+    /// Raw bytes. Accepted from clients when they hold UTF-8 JSON.
+    Binary(Vec<u8>),
+    /// The peer closed (on receive) or we are closing (on send).
+    Close(Option<CloseReason>),
+}
+
+/// Close code and human-readable reason sent with a close frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseReason {
+    /// RFC 6455 status code.
+    pub code: u16,
+    /// Short explanation, at most 123 bytes on the wire.
+    pub reason: String,
+}
+
+impl CloseReason {
+    /// Normal closure.
+    pub const NORMAL: u16 = 1000;
+    /// The endpoint is going away (server shutdown, page navigation).
+    pub const GOING_AWAY: u16 = 1001;
+    /// The peer sent something that violates the protocol.
+    pub const PROTOCOL_ERROR: u16 = 1002;
+    /// The peer violated a policy, e.g. missed heartbeats.
+    pub const POLICY_VIOLATION: u16 = 1008;
+    /// The message exceeded [`MAX_MESSAGE_SIZE`](crate::protocol::MAX_MESSAGE_SIZE).
+    pub const MESSAGE_TOO_BIG: u16 = 1009;
+
+    /// Create a close reason.
+    #[must_use]
+    pub fn new(code: u16, reason: impl Into<String>) -> Self {
+        Self {
+            code,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.code, self.reason)
+    }
+}
+
+/// Errors from a [`Transport`] or [`ServerConnection`].
+#[derive(Debug)]
+pub enum TransportError {
+    /// The connection is closed; carries the reason if one was given.
+    Closed(Option<CloseReason>),
+    /// No frame arrived within the heartbeat timeout.
+    HeartbeatTimeout,
+    /// A frame exceeded the size limit.
+    MessageTooLarge(usize),
+    /// A binary frame did not hold UTF-8 text. The connection stays open.
+    InvalidUtf8,
+    /// A frame was not a valid protocol message. The connection stays open.
+    Parse(crate::protocol::ProtocolError),
+    /// A message could not be serialized.
+    Serialize(serde_json::Error),
+    /// The underlying socket failed.
+    Io(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed(Some(reason)) => write!(f, "connection closed: {reason}"),
+            Self::Closed(None) => write!(f, "connection closed"),
+            Self::HeartbeatTimeout => write!(f, "heartbeat timed out"),
+            Self::MessageTooLarge(len) => write!(f, "message of {len} bytes is too large"),
+            Self::InvalidUtf8 => write!(f, "binary frame is not UTF-8"),
+            Self::Parse(e) => write!(f, "invalid client message: {e}"),
+            Self::Serialize(e) => write!(f, "failed to serialize message: {e}"),
+            Self::Io(e) => write!(f, "transport error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::Serialize(e) => Some(e),
+            Self::Io(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<crate::protocol::ProtocolError> for TransportError {
+    fn from(e: crate::protocol::ProtocolError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<serde_json::Error> for TransportError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Serialize(e)
+    }
+}
+
+/// A bidirectional frame stream, such as one WebSocket.
+///
+/// Ping/pong control frames are the transport's business and never surface
+/// here.
+#[async_trait::async_trait]
+pub trait Transport: Send {
+    /// Send one frame. Sending [`Frame::Close`] starts the close handshake.
+    async fn send(&mut self, frame: Frame) -> Result<(), TransportError>;
+
+    /// Wait for the next frame. Returns [`Frame::Close`] once when the peer
+    /// closes, then [`TransportError::Closed`].
+    ///
+    /// Must be cancel-safe: dropping the future before it completes must not
+    /// lose a frame, so callers can `select!` on it.
+    async fn recv(&mut self) -> Result<Frame, TransportError>;
+
+    /// Close with `reason`.
+    async fn close(&mut self, reason: CloseReason) -> Result<(), TransportError> {
+        self.send(Frame::Close(Some(reason))).await
+    }
+}
@@ -0,0 +1,372 @@
+//! Scripted conversations for tests.
+//!
+//! A [`Script`] plays the client's side of a conversation over any
+//! [`Transport`], usually one end of a [`loopback`](super::loopback):
+//!
+//! ```ignore
+//! let transcript = Script::new()
+//!     .expect("hello")
+//!     .send(ClientMessage::Identify { resume_seq: None })
+//!     .expect_where("ready", |m| matches!(m, ServerMessage::Ready { player_id: 42, .. }))
+//!     .run(&mut client)
+//!     .await?;
+//! ```
+//!
+//! Each `expect` waits for the next server message (skipping ignored types)
+//! and fails the script if it has the wrong type, fails its predicate, or
+//! does not arrive within the step timeout.
+
+use std::fmt;
+use std::time::Duration;
+
+use super::{CloseReason, Frame, Transport, TransportError};
+use crate::protocol::{ClientMessage, Envelope, MaybeEnveloped, ServerMessage};
+
+type Predicate = Box<dyn Fn(&ServerMessage) -> bool + Send + Sync>;
+
+enum Step {
+    Send(ClientMessage),
+    Expect {
+        message_type: &'static str,
+        predicate: Option<Predicate>,
+    },
+    ExpectClose,
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Self::Send(msg) => format!("send {}", msg.message_type()),
+            Self::Expect {
+                message_type,
+                predicate: None,
+            } => (*message_type).to_string(),
+            Self::Expect { message_type, .. } => format!("{message_type} matching predicate"),
+            Self::ExpectClose => "close".to_string(),
+        }
+    }
+}
+
+/// The client side of a conversation, run step by step.
+pub struct Script {
+    steps: Vec<Step>,
+    ignored: Vec<&'static str>,
+    timeout: Duration,
+    enveloped: bool,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Script {
+    /// An empty script that envelopes what it sends and waits up to one
+    /// second per expectation.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            ignored: Vec::new(),
+            timeout: Duration::from_secs(1),
+            enveloped: true,
+        }
+    }
+
+    /// Send raw (version 1) messages instead of envelopes.
+    #[must_use]
+    pub fn raw(mut self) -> Self {
+        self.enveloped = false;
+        self
+    }
+
+    /// How long each expectation waits.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Skip server messages of this type wherever they arrive, e.g.
+    /// `"heartbeat_ack"`.
+    #[must_use]
+    pub fn ignore(mut self, message_type: &'static str) -> Self {
+        self.ignored.push(message_type);
+        self
+    }
+
+    /// Send `msg`.
+    #[must_use]
+    pub fn send(mut self, msg: ClientMessage) -> Self {
+        self.steps.push(Step::Send(msg));
+        self
+    }
+
+    /// Expect the next server message to have `message_type`.
+    #[must_use]
+    pub fn expect(mut self, message_type: &'static str) -> Self {
+        self.steps.push(Step::Expect {
+            message_type,
+            predicate: None,
+        });
+        self
+    }
+
+    /// Expect the next server message to have `message_type` and satisfy
+    /// `predicate`.
+    #[must_use]
+    pub fn expect_where(
+        mut self,
+        message_type: &'static str,
+        predicate: impl Fn(&ServerMessage) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.steps.push(Step::Expect {
+            message_type,
+            predicate: Some(Box::new(predicate)),
+        });
+        self
+    }
+
+    /// Expect the server to close the connection.
+    #[must_use]
+    pub fn expect_close(mut self) -> Self {
+        self.steps.push(Step::ExpectClose);
+        self
+    }
+
+    /// Run the steps in order over `transport`.
+    ///
+    /// Returns every expected message, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ScriptError`] naming the first step that failed.
+    pub async fn run<T: Transport>(
+        self,
+        transport: &mut T,
+    ) -> Result<Vec<ServerMessage>, ScriptError> {
+        let mut transcript = Vec::new();
+        let mut seq = 0;
+        for (index, step) in self.steps.iter().enumerate() {
+            let fail = |kind: ScriptErrorKind| ScriptError {
+                step: index,
+                expected: step.describe(),
+                kind,
+            };
+            match step {
+                Step::Send(msg) => {
+                    seq += 1;
+                    let json = if self.enveloped {
+                        serde_json::to_string(&Envelope::new(seq, msg))
+                    } else {
+                        serde_json::to_string(msg)
+                    }
+                    .map_err(|e| fail(ScriptErrorKind::Decode(e)))?;
+                    transport
+                        .send(Frame::Text(json))
+                        .await
+                        .map_err(|e| fail(ScriptErrorKind::Transport(e)))?;
+                }
+                Step::Expect {
+                    message_type,
+                    predicate,
+                } => {
+                    let msg = match self.next_message(transport).await {
+                        Ok(msg) => msg,
+                        Err(kind) => return Err(fail(kind)),
+                    };
+                    let matches = msg.message_type() == *message_type
+                        && predicate.as_ref().is_none_or(|p| p(&msg));
+                    if !matches {
+                        return Err(fail(ScriptErrorKind::Unexpected(Box::new(msg))));
+                    }
+                    transcript.push(msg);
+                }
+                Step::ExpectClose => match self.next_message(transport).await {
+                    Err(ScriptErrorKind::Closed(_)) => {}
+                    Ok(msg) => return Err(fail(ScriptErrorKind::Unexpected(Box::new(msg)))),
+                    Err(kind) => return Err(fail(kind)),
+                },
+            }
+        }
+        Ok(transcript)
+    }
+
+    /// The next server message that is not ignored.
+    async fn next_message<T: Transport>(
+        &self,
+        transport: &mut T,
+    ) -> Result<ServerMessage, ScriptErrorKind> {
+        loop {
+            let frame = tokio::time::timeout(self.timeout, transport.recv())
+                .await
+                .map_err(|_| ScriptErrorKind::Timeout)?
+                .map_err(|e| match e {
+                    TransportError::Closed(reason) => ScriptErrorKind::Closed(reason),
+                    other => ScriptErrorKind::Transport(other),
+                })?;
+            let text = match frame {
+                Frame::Text(text) => text,
+                Frame::Binary(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Frame::Close(reason) => return Err(ScriptErrorKind::Closed(reason)),
+            };
+            let msg: MaybeEnveloped<ServerMessage> =
+                serde_json::from_str(&text).map_err(ScriptErrorKind::Decode)?;
+            let msg = match msg {
+                MaybeEnveloped::Enveloped(envelope) => envelope.payload,
+                MaybeEnveloped::Raw(msg) => msg,
+            };
+            if !self.ignored.contains(&msg.message_type()) {
+                return Ok(msg);
+            }
+        }
+    }
+}
+
+/// A [`Script`] step that did not go as written.
+#[derive(Debug)]
+pub struct ScriptError {
+    /// Zero-based index of the failing step.
+    pub step: usize,
+    /// What the step wanted, e.g. `"ready"` or `"close"`.
+    pub expected: String,
+    /// What happened instead.
+    pub kind: ScriptErrorKind,
+}
+
+/// What went wrong in a [`ScriptError`].
+#[derive(Debug)]
+pub enum ScriptErrorKind {
+    /// A different message arrived, or it failed the predicate.
+    Unexpected(Box<ServerMessage>),
+    /// Nothing arrived within the step timeout.
+    Timeout,
+    /// The server closed the connection.
+    Closed(Option<CloseReason>),
+    /// A frame could not be encoded or decoded.
+    Decode(serde_json::Error),
+    /// The transport failed.
+    Transport(TransportError),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step {} ({}): ", self.step, self.expected)?;
+        match &self.kind {
+            ScriptErrorKind::Unexpected(msg) => write!(f, "got {msg:?}"),
+            ScriptErrorKind::Timeout => write!(f, "timed out"),
+            ScriptErrorKind::Closed(Some(reason)) => write!(f, "connection closed: {reason}"),
+            ScriptErrorKind::Closed(None) => write!(f, "connection closed"),
+            ScriptErrorKind::Decode(e) => write!(f, "invalid frame: {e}"),
+            ScriptErrorKind::Transport(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ScriptErrorKind::Decode(e) => Some(e),
+            ScriptErrorKind::Transport(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Capabilities;
+    use crate::transport::{loopback, LoopbackTransport, ServerConnection};
+
+    /// A server that greets, answers `Identify` with `Ready` and echoes
+    /// `LeaveLobby` with `LobbyLeft`.
+    async fn serve(transport: LoopbackTransport) {
+        let mut conn = ServerConnection::new(transport, None);
+        conn.hello().await.unwrap();
+        while let Ok(Some(msg)) = conn.recv().await {
+            let reply = match msg {
+                ClientMessage::Identify { .. } => ServerMessage::Ready {
+                    session_id: "s1".into(),
+                    player_id: 42,
+                    lobby: None,
+                    game: None,
+                },
+                ClientMessage::LeaveLobby => ServerMessage::LobbyLeft,
+                _ => continue,
+            };
+            conn.send(reply).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn scripted_handshake() {
+        let (mut client, server) = loopback();
+        tokio::spawn(serve(server));
+
+        let transcript = Script::new()
+            .ignore("heartbeat_ack")
+            .expect("hello")
+            .send(ClientMessage::Identify { resume_seq: None })
+            .send(ClientMessage::Heartbeat)
+            .expect_where("ready", |m| {
+                matches!(m, ServerMessage::Ready { player_id: 42, .. })
+            })
+            .send(ClientMessage::LeaveLobby)
+            .expect("lobby_left")
+            .run(&mut client)
+            .await
+            .unwrap();
+        assert_eq!(transcript.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn failures_name_the_step() {
+        let (mut client, server) = loopback();
+        tokio::spawn(serve(server));
+
+        let err = Script::new()
+            .expect("hello")
+            .send(ClientMessage::Identify { resume_seq: None })
+            .expect_where("ready", |m| {
+                matches!(m, ServerMessage::Ready { player_id: 7, .. })
+            })
+            .run(&mut client)
+            .await
+            .unwrap_err();
+        assert_eq!(err.step, 2);
+        assert!(matches!(err.kind, ScriptErrorKind::Unexpected(_)));
+        assert!(err
+            .to_string()
+            .starts_with("step 2 (ready matching predicate)"));
+
+        let err = Script::new()
+            .timeout(Duration::from_millis(10))
+            .expect("game_started")
+            .run(&mut client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.kind, ScriptErrorKind::Timeout));
+    }
+
+    #[tokio::test]
+    async fn legacy_script_sees_close() {
+        let (mut client, server) = loopback();
+        tokio::spawn(async move {
+            let mut conn = ServerConnection::new(server, Some(Capabilities::for_version(1)));
+            let _ = conn.recv().await;
+            conn.close(CloseReason::new(CloseReason::NORMAL, "bye"))
+                .await
+                .unwrap();
+        });
+
+        Script::new()
+            .raw()
+            .send(ClientMessage::LeaveLobby)
+            .expect_close()
+            .run(&mut client)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,226 @@
+//! [`Transport`] over a tokio-tungstenite WebSocket.
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::WebSocketStream;
+
+use super::{CloseReason, Frame, Transport, TransportError};
+
+/// A WebSocket stream as a [`Transport`].
+///
+/// Pings are answered by tungstenite itself; pongs are skipped.
+///
+/// ```ignore
+/// let ws = tokio_tungstenite::accept_async(tcp_stream).await?;
+/// let mut conn = ServerConnection::new(WsTransport::new(ws), None);
+/// conn.hello().await?;
+/// while let Some(msg) = conn.recv().await? { /* handle */ }
+/// ```
+#[derive(Debug)]
+pub struct WsTransport<S> {
+    stream: WebSocketStream<S>,
+    closed: bool,
+}
+
+impl<S> WsTransport<S> {
+    /// Wrap an accepted or connected WebSocket.
+    #[must_use]
+    pub fn new(stream: WebSocketStream<S>) -> Self {
+        Self {
+            stream,
+            closed: false,
+        }
+    }
+
+    /// Unwrap the WebSocket.
+    pub fn into_inner(self) -> WebSocketStream<S> {
+        self.stream
+    }
+}
+
+fn ws_error(e: WsError) -> TransportError {
+    match e {
+        WsError::ConnectionClosed | WsError::AlreadyClosed => TransportError::Closed(None),
+        other => TransportError::Io(Box::new(other)),
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> Transport for WsTransport<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    async fn send(&mut self, frame: Frame) -> Result<(), TransportError> {
+        let message = match frame {
+            Frame::Text(text) => Message::text(text),
+            Frame::Binary(bytes) => Message::binary(bytes),
+            Frame::Close(reason) => Message::Close(reason.map(|r| CloseFrame {
+                code: CloseCode::from(r.code),
+                reason: r.reason.into(),
+            })),
+        };
+        self.stream.send(message).await.map_err(ws_error)
+    }
+
+    async fn recv(&mut self) -> Result<Frame, TransportError> {
+        loop {
+            if self.closed {
+                return Err(TransportError::Closed(None));
+            }
+            let Some(message) = self.stream.next().await else {
+                self.closed = true;
+                return Ok(Frame::Close(None));
+            };
+            return match message.map_err(ws_error)? {
+                Message::Text(text) => Ok(Frame::Text(text.as_str().to_owned())),
+                Message::Binary(bytes) => Ok(Frame::Binary(bytes.to_vec())),
+                Message::Close(frame) => {
+                    self.closed = true;
+                    Ok(Frame::Close(frame.map(|f| {
+                        CloseReason::new(u16::from(f.code), f.reason.as_str())
+                    })))
+                }
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => continue,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::protocol::{Capabilities, ClientMessage, ServerMessage};
+    use crate::transport::ServerConnection;
+
+    type Stream = tokio::io::DuplexStream;
+
+    async fn pair() -> (WsTransport<Stream>, WsTransport<Stream>) {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let (client, server) = tokio::join!(
+            tokio_tungstenite::client_async("ws://localhost/ws", client_io),
+            tokio_tungstenite::accept_async(server_io),
+        );
+        (
+            WsTransport::new(client.unwrap().0),
+            WsTransport::new(server.unwrap()),
+        )
+    }
+
+    async fn recv_text(t: &mut WsTransport<Stream>) -> String {
+        match t.recv().await.unwrap() {
+            Frame::Text(text) => text,
+            other => panic!("expected text, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn server_connection_over_websocket() {
+        let (mut client, server) = pair().await;
+        let mut conn = ServerConnection::new(server, None);
+
+        conn.hello().await.unwrap();
+        let hello = recv_text(&mut client).await;
+        assert!(hello.contains(r#""seq":1"#), "{hello}");
+        assert!(hello.contains(r#""type":"hello""#), "{hello}");
+
+        // Heartbeats and acks are handled without reaching the caller.
+        for frame in [
+            r#"{"seq":1,"ts":0,"payload":{"type":"heartbeat"}}"#,
+            r#"{"type":"ack","seq":1}"#,
+            r#"{"seq":2,"ack":1,"ts":0,"payload":{"type":"leave_lobby"}}"#,
+        ] {
+            client.send(Frame::Text(frame.into())).await.unwrap();
+        }
+        let msg = conn.recv().await.unwrap();
+        assert!(matches!(msg, Some(ClientMessage::LeaveLobby)));
+        assert_eq!(conn.last_acked(), Some(1));
+        assert_eq!(conn.client_version(), Some(2));
+
+        let ack = recv_text(&mut client).await;
+        assert!(ack.contains(r#""type":"heartbeat_ack""#), "{ack}");
+        assert!(ack.contains(r#""seq":2,"ack":1"#), "{ack}");
+
+        conn.send(ServerMessage::LobbyLeft).await.unwrap();
+        let left = recv_text(&mut client).await;
+        assert!(left.contains(r#""seq":3,"ack":2"#), "{left}");
+
+        // Bad JSON is reported, and the connection keeps going.
+        client.send(Frame::Text("{nope".into())).await.unwrap();
+        assert!(matches!(conn.recv().await, Err(TransportError::Parse(_))));
+
+        client
+            .close(CloseReason::new(CloseReason::NORMAL, "bye"))
+            .await
+            .unwrap();
+        assert!(conn.recv().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn legacy_client_gets_raw_frames() {
+        let (mut client, server) = pair().await;
+        let mut conn = ServerConnection::new(server, Some(Capabilities::for_version(1)));
+
+        client
+            .send(Frame::Binary(br#"{"type":"heartbeat"}"#.to_vec()))
+            .await
+            .unwrap();
+        client
+            .send(Frame::Text(r#"{"type":"leave_lobby"}"#.into()))
+            .await
+            .unwrap();
+        assert!(matches!(
+            conn.recv().await.unwrap(),
+            Some(ClientMessage::LeaveLobby)
+        ));
+        assert_eq!(conn.client_version(), Some(1));
+        let ack = recv_text(&mut client).await;
+        assert!(ack.starts_with(r#"{"type":"heartbeat_ack""#), "{ack}");
+    }
+
+    #[tokio::test]
+    async fn unpinned_connection_negotiates_legacy_output() {
+        let (mut client, server) = pair().await;
+        let mut conn = ServerConnection::new(server, None);
+
+        for frame in [r#"{"type":"heartbeat"}"#, r#"{"type":"leave_lobby"}"#] {
+            client.send(Frame::Text(frame.into())).await.unwrap();
+        }
+        assert!(matches!(
+            conn.recv().await.unwrap(),
+            Some(ClientMessage::LeaveLobby)
+        ));
+        assert_eq!(conn.client_version(), Some(1));
+        let ack = recv_text(&mut client).await;
+        assert!(ack.starts_with(r#"{"type":"heartbeat_ack""#), "{ack}");
+
+        conn.send(ServerMessage::LobbyLeft).await.unwrap();
+        let left = recv_text(&mut client).await;
+        assert!(left.starts_with(r#"{"type":"lobby_left""#), "{left}");
+        assert!(!left.contains(r#""seq""#), "{left}");
+    }
+
+    #[tokio::test]
+    async fn silent_client_is_closed() {
+        let (mut client, server) = pair().await;
+        let mut conn =
+            ServerConnection::new(server, None).with_heartbeat_timeout(Duration::from_millis(20));
+
+        assert!(matches!(
+            conn.recv().await,
+            Err(TransportError::HeartbeatTimeout)
+        ));
+        let Frame::Close(Some(reason)) = client.recv().await.unwrap() else {
+            panic!("expected a close frame");
+        };
+        assert_eq!(reason.code, CloseReason::POLICY_VIOLATION);
+        assert!(matches!(
+            client.recv().await,
+            Err(TransportError::Closed(None))
+        ));
+    }
+}
@@ -0,0 +1,454 @@
+//! What spectators are allowed to see.
+//!
+//! Spectators watch games that are often streamed, so live information a
+//! player could use (another player's in-progress selection, who is about
+//! to swap a tile) must not reach them in real time. A
+//! [`VisibilityPolicy`] decides, per message and recipient [`Role`], whether
+//! to send a message as is, redact it, drop it, or hold it back for the
+//! spectator delay.
+//!
+//! [`VisibleContext`] wraps a [`PlayerContext`] and applies the policy to
+//! everything sent through it. [`Room`](crate::room::Room) broadcasts use
+//! the member's [`Role`]; direct sends fall back to the wrapped context's
+//! [`is_spectating`](PlayerContext::is_spectating) flag. Delayed messages
+//! are released by [`VisibleContext::tick`]; call it whenever
+//! [`VisibleContext::next_deadline`] passes.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::game::clock::Clock;
+use crate::player::{PlayerContext, PlayerIdentity};
+use crate::protocol::GameSnapshot;
+use crate::room::Role;
+use crate::ServerMessage;
+
+/// Rules for filtering messages sent to spectators.
+///
+/// Players always receive messages unchanged. By default spectators get no
+/// selections or swap-mode notices, a snapshot without `your_player`, and
+/// no delay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibilityPolicy {
+    /// Send `SelectionUpdate` to spectators.
+    pub spectator_selections: bool,
+    /// Send `SwapModeEntered` / `SwapModeExited` to spectators.
+    pub spectator_swap_mode: bool,
+    /// Hold game-progress messages back from spectators for this long.
+    pub spectator_delay: Duration,
+}
+
+impl Default for VisibilityPolicy {
+    fn default() -> Self {
+        Self {
+            spectator_selections: false,
+            spectator_swap_mode: false,
+            spectator_delay: Duration::zero(),
+        }
+    }
+}
+
+impl VisibilityPolicy {
+    #[must_use]
+    pub fn with_spectator_selections(mut self, shown: bool) -> Self {
+        self.spectator_selections = shown;
+        self
+    }
+
+    #[must_use]
+    pub fn with_spectator_swap_mode(mut self, shown: bool) -> Self {
+        self.spectator_swap_mode = shown;
+        self
+    }
+
+    #[must_use]
+    pub fn with_spectator_delay(mut self, delay: Duration) -> Self {
+        self.spectator_delay = delay;
+        self
+    }
+
+    /// The message `role` should receive in place of `msg`, or `None` if
+    /// they should not receive it at all.
+    #[must_use]
+    pub fn filter<'a>(&self, msg: &'a ServerMessage, role: Role) -> Option<Cow<'a, ServerMessage>> {
+        if role.is_player() {
+            return Some(Cow::Borrowed(msg));
+        }
+        match msg {
+            ServerMessage::SelectionUpdate { .. } if !self.spectator_selections => None,
+            ServerMessage::SwapModeEntered { .. } | ServerMessage::SwapModeExited { .. }
+                if !self.spectator_swap_mode =>
+            {
+                None
+            }
+            ServerMessage::GameSnapshot { game, .. }
+            | ServerMessage::SpectatorJoined { game, .. }
+                if needs_redaction(game) =>
+            {
+                let mut msg = msg.clone();
+                if let ServerMessage::GameSnapshot { game, .. }
+                | ServerMessage::SpectatorJoined { game, .. } = &mut msg
+                {
+                    redact_snapshot(game);
+                }
+                Some(Cow::Owned(msg))
+            }
+            _ => Some(Cow::Borrowed(msg)),
+        }
+    }
+
+    /// How long to hold `msg` back from `role`, if at all.
+    #[must_use]
+    pub fn delay(&self, msg: &ServerMessage, role: Role) -> Option<Duration> {
+        (!role.is_player() && self.spectator_delay > Duration::zero() && is_game_progress(msg))
+            .then_some(self.spectator_delay)
+    }
+}
+
+fn needs_redaction(game: &GameSnapshot) -> bool {
+    game.your_player.is_some()
+}
+
+/// Strip the parts of a snapshot that belong to one player.
+fn redact_snapshot(game: &mut GameSnapshot) {
+    game.your_player = None;
+}
+
+/// Messages that reveal how a game is going, and so fall under the
+/// spectator delay. Snapshots are included, even when they answer the
+/// spectator's own `SpectateGame` or `RequestSync`, so they stay in order
+/// with the delayed updates that follow them. Errors and connection
+/// housekeeping are never delayed.
+fn is_game_progress(msg: &ServerMessage) -> bool {
+    matches!(
+        msg,
+        ServerMessage::GameStarted { .. }
+            | ServerMessage::GameSnapshot { .. }
+            | ServerMessage::GameDelta { .. }
+            | ServerMessage::GameOver { .. }
+            | ServerMessage::AdventureEvent { .. }
+            | ServerMessage::WordScored { .. }
+            | ServerMessage::TurnChanged { .. }
+            | ServerMessage::TurnPassed { .. }
+            | ServerMessage::RoundChanged { .. }
+            | ServerMessage::BoardShuffled { .. }
+            | ServerMessage::TileSwapped { .. }
+            | ServerMessage::SwapModeEntered { .. }
+            | ServerMessage::SwapModeExited { .. }
+            | ServerMessage::SpectatorJoined { .. }
+            | ServerMessage::SelectionUpdate { .. }
+            | ServerMessage::TimerVoteUpdate { .. }
+            | ServerMessage::TurnTimerStarted { .. }
+            | ServerMessage::TurnTimerExpired { .. }
+            | ServerMessage::GameStateUpdate { .. }
+    )
+}
+
+/// A [`PlayerContext`] that applies a [`VisibilityPolicy`] to everything
+/// sent through it.
+pub struct VisibleContext<C> {
+    inner: C,
+    policy: VisibilityPolicy,
+    clock: Arc<dyn Clock>,
+    /// Delayed messages with their release time, oldest first.
+    held: Mutex<VecDeque<(DateTime<Utc>, ServerMessage)>>,
+}
+
+impl<C: std::fmt::Debug> std::fmt::Debug for VisibleContext<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VisibleContext")
+            .field("inner", &self.inner)
+            .field("policy", &self.policy)
+            .field("held", &self.held.lock().map_or(0, |held| held.len()))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: PlayerContext> VisibleContext<C> {
+    pub fn new(inner: C, policy: VisibilityPolicy, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            inner,
+            policy,
+            clock,
+            held: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn policy(&self) -> &VisibilityPolicy {
+        &self.policy
+    }
+
+    /// The role for sends that don't name one.
+    fn role(&self) -> Role {
+        if self.inner.is_spectating() {
+            Role::Spectator
+        } else {
+            Role::Player
+        }
+    }
+
+    fn held(&self) -> MutexGuard<'_, VecDeque<(DateTime<Utc>, ServerMessage)>> {
+        self.held.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// How many delayed messages are waiting.
+    #[must_use]
+    pub fn held_len(&self) -> usize {
+        self.held().len()
+    }
+
+    /// When the oldest delayed message is due, if any are held.
+    #[must_use]
+    pub fn next_deadline(&self) -> Option<DateTime<Utc>> {
+        self.held().front().map(|(due, _)| *due)
+    }
+
+    /// Send every delayed message that is due, in order. Returns how many
+    /// were sent.
+    pub async fn tick(&self) -> usize {
+        let now = self.clock.now();
+        let due: Vec<ServerMessage> = {
+            let mut held = self.held();
+            let count = held.iter().take_while(|(at, _)| *at <= now).count();
+            held.drain(..count).map(|(_, msg)| msg).collect()
+        };
+        let sent = due.len();
+        for msg in due {
+            self.inner.send_message(msg).await;
+        }
+        sent
+    }
+
+    /// Queue `msg` if the policy delays it. Returns it back otherwise.
+    fn hold<'a>(&self, msg: Cow<'a, ServerMessage>, role: Role) -> Option<Cow<'a, ServerMessage>> {
+        let Some(delay) = self.policy.delay(&msg, role) else {
+            return Some(msg);
+        };
+        let due = self.clock.now() + delay;
+        self.held().push_back((due, msg.into_owned()));
+        None
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: PlayerContext> PlayerContext for VisibleContext<C> {
+    async fn send_message(&self, msg: ServerMessage) {
+        self.send_message_as(msg, self.role()).await;
+    }
+
+    async fn send_serialized(&self, msg: &ServerMessage, json: &Arc<str>) {
+        self.send_serialized_as(msg, json, self.role()).await;
+    }
+
+    async fn send_message_as(&self, msg: ServerMessage, role: Role) {
+        let Some(msg) = self.policy.filter(&msg, role) else {
+            return;
+        };
+        if let Some(msg) = self.hold(msg, role) {
+            self.inner.send_message(msg.into_owned()).await;
+        }
+    }
+
+    async fn send_serialized_as(&self, msg: &ServerMessage, json: &Arc<str>, role: Role) {
+        let Some(filtered) = self.policy.filter(msg, role) else {
+            return;
+        };
+        match self.hold(filtered, role) {
+            Some(Cow::Borrowed(msg)) => self.inner.send_serialized(msg, json).await,
+            Some(Cow::Owned(msg)) => self.inner.send_message(msg).await,
+            None => {}
+        }
+    }
+
+    fn identity(&self) -> &dyn PlayerIdentity {
+        self.inner.identity()
+    }
+
+    fn is_admin(&self) -> bool {
+        self.inner.is_admin()
+    }
+
+    fn lobby_id(&self) -> Option<&str> {
+        self.inner.lobby_id()
+    }
+
+    fn game_id(&self) -> Option<&str> {
+        self.inner.game_id()
+    }
+
+    fn is_spectating(&self) -> bool {
+        self.inner.is_spectating()
+    }
+
+    fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::clock::ManualClock;
+    use crate::protocol::{Position, TimerVoteState};
+    use crate::room::Room;
+    use crate::testing::{player_info, MockPlayerContext};
+
+    fn selection() -> ServerMessage {
+        ServerMessage::SelectionUpdate {
+            player_id: 1,
+            game_id: "g1".into(),
+            positions: vec![Position { row: 0, col: 0 }],
+        }
+    }
+
+    fn snapshot() -> ServerMessage {
+        ServerMessage::GameSnapshot {
+            game_id: "g1".into(),
+            game: GameSnapshot {
+                game_id: "g1".into(),
+                state: crate::GameState::InProgress,
+                grid: Vec::new(),
+                players: Vec::new(),
+                spectators: Vec::new(),
+                current_turn: 1,
+                round: 1,
+                max_rounds: 5,
+                used_words: Vec::new(),
+                timer_vote_state: TimerVoteState::Idle,
+                your_player: Some(player_info(1, "ann")),
+                timer_expiration_time: None,
+                custom: None,
+                time_remaining_ms: None,
+            },
+        }
+    }
+
+    fn clock() -> Arc<ManualClock> {
+        Arc::new(ManualClock::new(
+            DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+        ))
+    }
+
+    #[test]
+    fn spectators_lose_selections_swaps_and_your_player() {
+        let policy = VisibilityPolicy::default();
+        let swap = ServerMessage::SwapModeEntered {
+            player_id: 1,
+            game_id: "g1".into(),
+        };
+        for role in [Role::Player, Role::Host] {
+            assert!(matches!(
+                policy.filter(&selection(), role),
+                Some(Cow::Borrowed(_))
+            ));
+            assert!(policy.filter(&swap, role).is_some());
+        }
+        assert!(policy.filter(&selection(), Role::Spectator).is_none());
+        assert!(policy.filter(&swap, Role::Spectator).is_none());
+
+        let snapshot = snapshot();
+        let Some(Cow::Owned(ServerMessage::GameSnapshot { game, .. })) =
+            policy.filter(&snapshot, Role::Spectator)
+        else {
+            panic!("snapshot should be redacted");
+        };
+        assert!(game.your_player.is_none());
+
+        let open = policy.with_spectator_selections(true);
+        assert!(open.filter(&selection(), Role::Spectator).is_some());
+    }
+
+    #[tokio::test]
+    async fn spectator_delay_holds_game_progress_until_due() {
+        let clock = clock();
+        let policy = VisibilityPolicy::default().with_spectator_delay(Duration::seconds(10));
+        let ctx = VisibleContext::new(
+            MockPlayerContext::new(5).with_spectating(true),
+            policy,
+            clock.clone(),
+        );
+
+        ctx.send_message(ServerMessage::TurnPassed {
+            player_id: 1,
+            game_id: "g1".into(),
+        })
+        .await;
+        ctx.send_message(ServerMessage::error(crate::ErrorCode::NotYourTurn))
+            .await;
+        ctx.send_message(selection()).await;
+        assert_eq!(ctx.inner().sent_types(), ["error"]);
+        assert_eq!(ctx.held_len(), 1);
+        assert_eq!(
+            ctx.next_deadline(),
+            Some(clock.now() + Duration::seconds(10))
+        );
+
+        clock.advance(Duration::seconds(9));
+        assert_eq!(ctx.tick().await, 0);
+        clock.advance(Duration::seconds(1));
+        assert_eq!(ctx.tick().await, 1);
+        assert_eq!(ctx.inner().sent_types(), ["error", "turn_passed"]);
+        assert_eq!(ctx.next_deadline(), None);
+    }
+
+    #[tokio::test]
+    async fn room_broadcasts_are_filtered_per_recipient() {
+        let player = Arc::new(VisibleContext::new(
+            MockPlayerContext::new(1),
+            VisibilityPolicy::default(),
+            clock(),
+        ));
+        let spectator = Arc::new(VisibleContext::new(
+            MockPlayerContext::new(2).with_spectating(true),
+            VisibilityPolicy::default(),
+            clock(),
+        ));
+        let mut room = Room::new();
+        room.join(player.clone(), Role::Player);
+        room.join(spectator.clone(), Role::Spectator);
+
+        room.broadcast(&selection()).await;
+        room.broadcast(&snapshot()).await;
+
+        assert_eq!(
+            player.inner().sent_types(),
+            ["selection_update", "game_snapshot"]
+        );
+        // The player's copies arrive pre-serialized; the redacted one cannot.
+        assert_eq!(player.inner().sent_json().len(), 2);
+        let sent = spectator.inner().sent();
+        assert!(matches!(
+            &sent[..],
+            [ServerMessage::GameSnapshot { game, .. }] if game.your_player.is_none()
+        ));
+        assert!(spectator.inner().sent_json().is_empty());
+    }
+
+    #[tokio::test]
+    async fn room_role_wins_over_the_context_flag() {
+        let ctx = Arc::new(VisibleContext::new(
+            MockPlayerContext::new(2),
+            VisibilityPolicy::default(),
+            clock(),
+        ));
+        let mut room = Room::new();
+        room.join(ctx.clone(), Role::Spectator);
+
+        room.broadcast(&selection()).await;
+        room.broadcast_with(|_| Some(selection())).await;
+        ctx.inner().assert_nothing_sent();
+
+        // Sent directly, the context's own flag decides.
+        ctx.send_message(selection()).await;
+        assert_eq!(ctx.inner().sent_types(), ["selection_update"]);
+    }
+}